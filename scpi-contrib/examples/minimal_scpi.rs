@@ -8,12 +8,9 @@ use std::{
     io::{self, BufRead, Write},
 };
 
-use scpi::{tree::Node, Context, Device, Root};
+use scpi::{tree::Node, Context, Device};
+use scpi_contrib::scpi_mandatory;
 use scpi_contrib::{ieee488::prelude::*, scpi1999::prelude::*};
-use scpi_contrib::{
-    ieee488_cls, ieee488_ese, ieee488_esr, ieee488_idn, ieee488_opc, ieee488_rst, ieee488_sre,
-    ieee488_stb, ieee488_tst, ieee488_wai, scpi_status, scpi_system,
-};
 
 struct MinimalScpiDevice {
     /// Event Status Register
@@ -144,24 +141,9 @@ impl ErrorQueue for MinimalScpiDevice {
 // Mostly done by requiring the traits above to be implmented
 impl ScpiDevice for MinimalScpiDevice {}
 
-// Create a minimal command tree
-const MINIMAL_TREE: Node<MinimalScpiDevice> = Root![
-    // Create default IEEE488 mandated commands
-    ieee488_cls!(),
-    ieee488_ese!(),
-    ieee488_esr!(),
-    ieee488_idn!(b"Example Inc", b"T800-101", b"0", b"0"),
-    ieee488_opc!(),
-    ieee488_rst!(),
-    ieee488_sre!(),
-    ieee488_stb!(),
-    ieee488_tst!(),
-    ieee488_wai!(),
-    // Create default SCPI mandated STATus commands
-    scpi_status!(),
-    // Create default SCPI mandated SYSTem commands
-    scpi_system!()
-];
+// Create a minimal command tree with the IEEE488 and SCPI mandated commands
+const MINIMAL_TREE: Node<MinimalScpiDevice> =
+    scpi_mandatory!(b"Example Inc", b"T800-101", b"0", b"0");
 
 fn main() {
     let mut device = MinimalScpiDevice::new();
@@ -179,8 +161,8 @@ fn main() {
         let res = MINIMAL_TREE.run(&command, &mut device, &mut context, &mut response);
 
         // Print response
-        if let Ok(_) = res {
-            stdout.write(&response).unwrap();
+        if res.is_ok() {
+            stdout.write_all(&response).unwrap();
         }
     }
 }
@@ -18,6 +18,14 @@
 //! | *TST     | Self-Test Query                      | 10.38         |
 //! | *WAI     | Wait-To-Continue                     | 10.39         |
 //!
+//! Optional IEEE488.2 commands:
+//!
+//! | Mnemonic | Name                                 | 488.2 Section |
+//! |----------|--------------------------------------|---------------|
+//! | *CAL?    | Calibration Query                    | 10.2          |
+//! | *RCL     | Recall Command                       | 10.29         |
+//! | *SAV     | Save Command                         | 10.33         |
+//!
 //! Note that the comments about the default mandatory commands below are from the IEEE 488.2-1992 document and explain their purpose, not my implementation.
 
 use core::fmt::Display;
@@ -27,7 +35,46 @@ use scpi::error::Result;
 use scpi::tree::prelude::*;
 use scpi::{cmd_both, cmd_nquery, cmd_qonly};
 
-use super::{StatusBit, IEEE4882};
+use super::{EventStatusBit, StatusBit, IEEE4882};
+
+///## 10.2 *CAL?, Calibration Query
+///> The calibration query causes a device to perform an internal self-calibration and generate a response that indicates
+///> whether or not the device completed the self-calibration without error. Optionally, information on the cause of the
+///> failure may be contained in the response. A zero value indicates that the device completed the self-calibration
+///> without error. A value other than zero indicates that the self-calibration was not completed properly.
+///
+/// Responds with the code returned by [IEEE4882::calibrate] or the error code if calibration
+/// failed with `Calibration memory lost` (`-313`) or `Calibration failed` (`-340`).
+/// Any other error is returned as a command error.
+#[derive(Debug, Clone, Copy)]
+pub struct CalCommand;
+
+impl<D> Command<D> for CalCommand
+where
+    D: Device + IEEE4882,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let code = match device.calibrate() {
+            Ok(code) => code,
+            Err(err)
+                if err == ErrorCode::CalibrationMemoryLost
+                    || err == ErrorCode::CalibrationFailed =>
+            {
+                err.get_code()
+            }
+            Err(err) => return Err(err),
+        };
+        response.data(code).finish()
+    }
+}
 
 ///## 10.3 *CLS, Clear Status Command
 ///> The Clear Status command clears status data structures, see 11.1.2, and forces the device to the Operation Complete
@@ -176,6 +223,33 @@ where
     }
 }
 
+/// Same as [IdnCommand] but returns the fields given by [IEEE4882::identification].
+#[derive(Debug, Clone, Copy)]
+pub struct IdnDeviceCommand;
+
+impl<D> Command<D> for IdnDeviceCommand
+where
+    D: Device + IEEE4882,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let (manufacturer, model, serial, firmware) = device.identification();
+        response
+            .data(Character(manufacturer))
+            .data(Character(model))
+            .data(Character(serial))
+            .data(Character(firmware))
+            .finish()
+    }
+}
+
 ///## 10.18 *OPC, Operation Complete Command
 ///> The Operation Complete command causes the device to generate the operation complete message in the Standard
 ///> Event Status Register when all pending selected device operations have been finished. See 12.5.2.2 for details of
@@ -199,15 +273,112 @@ where
 
     fn query(
         &self,
-        _device: &mut D,
+        device: &mut D,
+        context: &mut Context,
+        params: Parameters,
+        response: ResponseUnit,
+    ) -> Result<()> {
+        OpcQueryCommand.query(device, context, params, response)
+    }
+}
+
+///## 10.19 *OPC?, Operation Complete Query
+///> The Operation Complete query places an ASCII character "1" into the device's Output Queue when all pending
+///> selected device operations have been finished. See 12.5.3 for details of operation.
+///
+/// Query-only handler for `*OPC?`. Unlike the `*OPC` command, the query does not set the
+/// Operation Complete bit in the Standard Event Status Register.
+///
+/// Waits for pending operations with [IEEE4882::wait] (same as `*WAI`) before responding.
+/// If the wait returns an error, Eg. a device-specific timeout, no response is generated.
+/// Devices which only implement sequential commands never have pending operations and
+/// respond with `1` immediately.
+#[derive(Debug, Clone, Copy)]
+pub struct OpcQueryCommand;
+
+impl<D> Command<D> for OpcQueryCommand
+where
+    D: Device + IEEE4882,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
         _context: &mut Context,
         _params: Parameters,
         mut response: ResponseUnit,
     ) -> Result<()> {
+        device.wait()?;
         response.data(true).finish()
     }
 }
 
+///## 10.20 *OPT?, Option Identification Query
+///> The Option Identification query is for identifying reportable device options over the interface.
+///
+/// Responds with the options of [IEEE4882::options] separated by commas, Eg. `GPIB,LAN`.
+/// A device without any options responds with `0`.
+#[derive(Debug, Clone, Copy)]
+pub struct OptCommand;
+
+impl<D> Command<D> for OptCommand
+where
+    D: Device + IEEE4882,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let options = device.options();
+        if options.is_empty() {
+            return response.data(0u8).finish();
+        }
+        for option in options {
+            response.data(Character(option));
+        }
+        response.finish()
+    }
+}
+
+///## 10.25 *PSC, Power-On Status Clear Command
+///> The Power-On Status Clear command controls the automatic power-on clearing of the Service Request Enable Register,
+///> the Standard Event Status Enable Register, and the Parallel Poll Enable Register. A value other than zero sets the
+///> power-on status clear flag true, a value of zero sets it false.
+///## 10.26 *PSC?, Power-On Status Clear Query
+///> The Power-On Status Clear query allows the programmer to query the device's power-on status clear flag.
+///
+/// Calls [IEEE4882::set_psc] and [IEEE4882::psc], the query responds with `1` or `0`.
+#[derive(Debug, Clone, Copy)]
+pub struct PscCommand;
+
+impl<D> Command<D> for PscCommand
+where
+    D: Device + IEEE4882,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let on = params.next_data()?;
+        device.set_psc(on)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.psc()).finish()
+    }
+}
+
 ///## 10.32 *RST, Reset Command
 ///> The Reset command performs a device reset. The Reset command is the third level of reset in a three-level reset
 ///> strategy, see 17.1.2 and Appendix D. The Reset command shall do the following:
@@ -249,6 +420,66 @@ where
     }
 }
 
+/// Parse a `*SAV`/`*RCL` register index and check it against [IEEE4882::state_slots]
+fn state_slot<D>(device: &D, params: &mut Parameters) -> Result<usize>
+where
+    D: IEEE4882,
+{
+    let slot: usize = params.next_data().map_err(|err| {
+        // Negative or too large
+        if err == ErrorCode::DataOutOfRange {
+            ErrorCode::IllegalParameterValue.into()
+        } else {
+            err
+        }
+    })?;
+    match device.state_slots() {
+        Some(slots) if slot >= slots => Err(ErrorCode::IllegalParameterValue.into()),
+        _ => Ok(slot),
+    }
+}
+
+///## 10.29 *RCL, Recall Command
+/// Restores the device settings from the given setup register previously stored with `*SAV`.
+/// The scope of the recalled settings is the same as for `*RST`.
+///
+/// Calls [IEEE4882::recall_state] with the register index, which must be less than [IEEE4882::state_slots]
+/// (if any) or a `-224, "Illegal parameter value"` error is returned.
+#[derive(Debug, Clone, Copy)]
+pub struct RclCommand;
+
+impl<D> Command<D> for RclCommand
+where
+    D: Device + IEEE4882,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let slot = state_slot(device, &mut params)?;
+        device.recall_state(slot)
+    }
+}
+
+///## 10.33 *SAV, Save Command
+/// Stores the current device settings in the given setup register, to be restored by `*RCL`.
+///
+/// Calls [IEEE4882::save_state] with the register index, which must be less than [IEEE4882::state_slots]
+/// (if any) or a `-224, "Illegal parameter value"` error is returned.
+#[derive(Debug, Clone, Copy)]
+pub struct SavCommand;
+
+impl<D> Command<D> for SavCommand
+where
+    D: Device + IEEE4882,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let slot = state_slot(device, &mut params)?;
+        device.save_state(slot)
+    }
+}
+
 ///## 10.34 *SRE, Service Request Enable Command
 ///> The Service Request Enable command sets the Service Request Enable Register bits as defined in 11.3.2.
 ///## 10.35 *SRE?, Service Request Enable Query
@@ -320,6 +551,9 @@ where
 ///> Upon successful completion of *TST?, the device settings shall be restored to their values prior to the *TST?; set to
 ///> fixed, known values that are stated in the device documentation; or set to values deÞned by the user and stored in local
 ///> memory.
+///
+/// Responds with the code returned by [IEEE4882::tst_code] or the error code if the self-test
+/// returned an error. A nonzero code sets the device-dependent error bit in the ESR.
 #[derive(Debug, Clone, Copy)]
 pub struct TstCommand;
 
@@ -336,14 +570,11 @@ where
         _params: Parameters,
         mut response: ResponseUnit,
     ) -> Result<()> {
-        response
-            .data(
-                device
-                    .tst()
-                    .map(|_| 0i16)
-                    .unwrap_or_else(|err| err.get_code()),
-            )
-            .finish()
+        let code = device.tst_code().unwrap_or_else(|err| err.get_code());
+        if code != 0 {
+            device.set_esr(device.esr() | EventStatusBit::DeviceDependantError.mask());
+        }
+        response.data(code).finish()
     }
 }
 
@@ -352,6 +583,8 @@ where
 ///> operation-pending flag is TRUE. See 12.5.1.
 ///>
 ///> NOTE - In a device that implements only sequential commands, the no-operation-pending flag is always TRUE
+///
+/// Calls [IEEE4882::wait] which should return only once [IEEE4882::pending_operations] is false.
 #[derive(Debug, Clone, Copy)]
 pub struct WaiCommand;
 
@@ -360,14 +593,23 @@ where
     D: Device + IEEE4882,
 {
     cmd_nquery!();
-    fn event(&self, _device: &mut D, _context: &mut Context, _params: Parameters) -> Result<()> {
-        Ok(())
+    fn event(&self, device: &mut D, _context: &mut Context, _params: Parameters) -> Result<()> {
+        device.wait()
     }
 }
 
 /// Create a command node for `*IDN?`. See [IdnCommand]
+///
+/// Without arguments the fields are read from the device, see [IdnDeviceCommand].
 #[macro_export]
 macro_rules! ieee488_idn {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*IDN",
+            default: false,
+            handler: &$crate::ieee488::common::IdnDeviceCommand,
+        }
+    };
     ($manufacturer:expr, $model:expr, $serial:expr, $firmware:expr) => {
         scpi::tree::prelude::Leaf {
             name: b"*IDN",
@@ -382,6 +624,18 @@ macro_rules! ieee488_idn {
     };
 }
 
+/// Create a command node for `*CAL?`. See [CalCommand]
+#[macro_export]
+macro_rules! ieee488_cal {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*CAL",
+            default: false,
+            handler: &$crate::ieee488::common::CalCommand,
+        }
+    };
+}
+
 /// Create a command node for `*CLS`. See [ClsCommand]
 #[macro_export]
 macro_rules! ieee488_cls {
@@ -430,6 +684,34 @@ macro_rules! ieee488_opc {
     };
 }
 
+/// Create a command node for `*OPT`. See [OptCommand]
+///
+/// Optional, not required by IEEE488.2 / SCPI.
+#[macro_export]
+macro_rules! ieee488_opt {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*OPT",
+            default: false,
+            handler: &$crate::ieee488::common::OptCommand,
+        }
+    };
+}
+
+/// Create a command node for `*PSC`. See [PscCommand]
+///
+/// Optional, not required by IEEE488.2 / SCPI.
+#[macro_export]
+macro_rules! ieee488_psc {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*PSC",
+            default: false,
+            handler: &$crate::ieee488::common::PscCommand,
+        }
+    };
+}
+
 /// Create a command node for `*RST`. See [RstCommand]
 #[macro_export]
 macro_rules! ieee488_rst {
@@ -442,6 +724,30 @@ macro_rules! ieee488_rst {
     };
 }
 
+/// Create a command node for `*RCL`. See [RclCommand]
+#[macro_export]
+macro_rules! ieee488_rcl {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*RCL",
+            default: false,
+            handler: &$crate::ieee488::common::RclCommand,
+        }
+    };
+}
+
+/// Create a command node for `*SAV`. See [SavCommand]
+#[macro_export]
+macro_rules! ieee488_sav {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*SAV",
+            default: false,
+            handler: &$crate::ieee488::common::SavCommand,
+        }
+    };
+}
+
 /// Create a command node for `*SRE`. See [SreCommand]
 #[macro_export]
 macro_rules! ieee488_sre {
@@ -0,0 +1,71 @@
+//! IEEE488.2 Learn device setup query
+//!
+use scpi::{cmd_qonly, error::Result, tree::prelude::*};
+
+use super::IEEE4882;
+
+/// Implements the device setup for the `*LRN?` query and `SYSTem:SET` command
+pub trait CommonLrn {
+    /// Called when `*LRN?` is queried.
+    ///
+    /// Write the current device setup into `out`, either as program message units which restore
+    /// the setup when executed (Eg. `*ESE 8;*SRE 32`) or as response data (Eg. an arbitrary block) which
+    /// is accepted by [Self::apply_set].
+    fn learn_string(&self, out: &mut dyn Formatter) -> Result<()>;
+
+    /// Called when `SYSTem:SET <block>` is executed with the block returned by [Self::learn_string].
+    ///
+    /// Return [ErrorCode::InvalidFormat] if the block is malformed.
+    fn apply_set(&mut self, _data: &[u8]) -> Result<()> {
+        Err(ErrorCode::InvalidFormat.into())
+    }
+}
+
+/// Response data formatted by [CommonLrn::learn_string]
+struct LearnString<'a, D>(&'a D);
+
+impl<D> ResponseData for LearnString<'_, D>
+where
+    D: CommonLrn,
+{
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        self.0.learn_string(formatter)
+    }
+}
+
+///## 10.17 *LRN?, Learn Device Setup Query
+///> The Learn Device Setup query allows the programmer to obtain a sequence of <RESPONSE MESSAGE UNIT>
+///> elements that may later be used as <PROGRAM MESSAGE UNIT> elements to place the device in the state it was
+///> in when the *LRN? common query was made.
+pub struct LrnCommand;
+
+impl<D> Command<D> for LrnCommand
+where
+    D: Device + IEEE4882 + CommonLrn,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(LearnString(device)).finish()
+    }
+}
+
+/// Create a command node for `*LRN?`. See [LrnCommand]
+///
+/// Optional, not required by IEEE488.2 / SCPI.
+#[macro_export]
+macro_rules! ieee488_lrn {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*LRN",
+            default: false,
+            handler: &$crate::ieee488::lrn::LrnCommand,
+        }
+    };
+}
@@ -0,0 +1,333 @@
+//! IEEE488.2 Macro commands
+//!
+//! Macro definitions are stored by the device through [MacroStore], [MacroTable] is a minimal
+//! store a device may delegate to.
+//! Macros are only stored, expansion of macro labels and macro parameters (`$<n>`) is not supported.
+use alloc::vec::Vec;
+
+use scpi::{
+    cmd_nquery, cmd_qonly,
+    error::Result,
+    parser::{tokenize, validate_syntax},
+    tree::prelude::*,
+};
+
+use super::IEEE4882;
+
+/// Storage of macro definitions for the `*DMC`, `*GMC?`, `*LMC?`, `*PMC` and `*RMC` commands
+///
+/// Labels are compared case-insensitively.
+pub trait MacroStore {
+    /// Called when `*DMC` is executed with a valid label and non-recursive contents.
+    ///
+    /// Return `Macro redefinition not allowed` if the label is already defined
+    /// or `Macro definition too long` if the contents do not fit.
+    fn define_macro(&mut self, label: &[u8], contents: &[u8]) -> Result<()>;
+
+    /// Contents of macro `label`, if defined
+    fn get_macro(&self, label: &[u8]) -> Option<&[u8]>;
+
+    /// Labels of all defined macros
+    fn macro_labels(&self) -> Vec<&[u8]>;
+
+    /// Called when `*RMC` is executed.
+    ///
+    /// Return `Macro header not found` if the label is not defined.
+    fn remove_macro(&mut self, label: &[u8]) -> Result<()>;
+
+    /// Called when `*PMC` is executed, removes all macros.
+    fn purge_macros(&mut self);
+}
+
+/// A [MacroStore] keeping definitions in a [Vec], limiting the length of each macro to `max_len` bytes.
+#[derive(Debug, Clone, Default)]
+pub struct MacroTable {
+    macros: Vec<(Vec<u8>, Vec<u8>)>,
+    max_len: usize,
+}
+
+impl MacroTable {
+    /// Create an empty table
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            macros: Vec::new(),
+            max_len,
+        }
+    }
+
+    fn position(&self, label: &[u8]) -> Option<usize> {
+        self.macros
+            .iter()
+            .position(|(l, _)| l.eq_ignore_ascii_case(label))
+    }
+}
+
+impl MacroStore for MacroTable {
+    fn define_macro(&mut self, label: &[u8], contents: &[u8]) -> Result<()> {
+        if self.position(label).is_some() {
+            Err(ErrorCode::MacroRedefinitionNotAllowed.into())
+        } else if contents.len() > self.max_len {
+            Err(ErrorCode::MacroDefinitionTooLong.into())
+        } else {
+            self.macros.push((label.to_vec(), contents.to_vec()));
+            Ok(())
+        }
+    }
+
+    fn get_macro(&self, label: &[u8]) -> Option<&[u8]> {
+        self.position(label).map(|i| self.macros[i].1.as_slice())
+    }
+
+    fn macro_labels(&self) -> Vec<&[u8]> {
+        self.macros.iter().map(|(l, _)| l.as_slice()).collect()
+    }
+
+    fn remove_macro(&mut self, label: &[u8]) -> Result<()> {
+        let i = self.position(label).ok_or(ErrorCode::MacroHeaderNotFound)?;
+        self.macros.remove(i);
+        Ok(())
+    }
+
+    fn purge_macros(&mut self) {
+        self.macros.clear();
+    }
+}
+
+/// A macro label is a command header of at most 12 characters, Eg. `TST` or `*TST`.
+fn valid_label(label: &[u8]) -> bool {
+    let name = label.strip_prefix(b"*").unwrap_or(label);
+    name.len() <= 12
+        && name.first().is_some_and(u8::is_ascii_alphabetic)
+        && name.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'_')
+}
+
+/// Get the next macro label, a string
+fn next_label<'a>(params: &mut Parameters<'a, '_>) -> Result<&'a [u8]> {
+    let label: &[u8] = params.next_data()?;
+    if valid_label(label) {
+        Ok(label)
+    } else {
+        Err(ErrorCode::IllegalMacroLabel.into())
+    }
+}
+
+/// Check if any program message unit header of `contents` is `label`
+fn is_recursive(label: &[u8], contents: &[u8]) -> bool {
+    let mut tokens = tokenize(contents).peekable();
+    let mut unit_start = true;
+    while let Some(tok) = tokens.next() {
+        match tok {
+            Ok(Token::ProgramMnemonic(header)) if unit_start => {
+                let compound = matches!(tokens.peek(), Some(Ok(Token::HeaderMnemonicSeparator)));
+                if !compound && header.eq_ignore_ascii_case(label) {
+                    return true;
+                }
+                unit_start = false;
+            }
+            Ok(Token::ProgramMessageUnitSeparator) => unit_start = true,
+            Ok(Token::HeaderMnemonicSeparator) if unit_start => {}
+            _ => unit_start = false,
+        }
+    }
+    false
+}
+
+///## 10.7 *DMC, Define Macro Command
+///> The Define Macro command allows the programmer to assign a sequence of zero or more <PROGRAM MESSAGE
+///> UNIT> elements to a macro label. The sequence is executed when the label is received as a <command program
+///> header> or <query program header>.
+///
+/// `*DMC <label>,<contents>` where the label is a string and the contents a string or arbitrary block.
+/// Contents which are not a valid program message return `Macro syntax error` and contents which use
+/// their own label return `Macro recursion error`.
+pub struct DmcCommand;
+
+impl<D> Command<D> for DmcCommand
+where
+    D: Device + IEEE4882 + MacroStore,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let label = next_label(&mut params)?;
+        let contents = match params.next_token()? {
+            Token::ArbitraryBlockData(contents) => contents.into(),
+            tok @ Token::StringProgramData(_, _) => {
+                tok.unescape_string().ok_or(ErrorCode::DataTypeError)?
+            }
+            tok if tok.is_data() => return Err(ErrorCode::DataTypeError.into()),
+            _ => return Err(ErrorCode::SyntaxError.into()),
+        };
+        params.finish()?;
+        if validate_syntax(&contents).is_err() {
+            return Err(ErrorCode::MacroSyntaxError.into());
+        }
+        if is_recursive(label, &contents) {
+            return Err(ErrorCode::MacroRecursionError.into());
+        }
+        device.define_macro(label, &contents)
+    }
+}
+
+///## 10.13 *GMC?, Get Macro Contents Query
+///> The Get Macro Contents query allows the current definition of a macro to be retrieved from a device.
+///
+/// `*GMC? <label>` responds with the contents as a definite length arbitrary block or
+/// returns `Macro header not found` if the label is not defined.
+pub struct GmcCommand;
+
+impl<D> Command<D> for GmcCommand
+where
+    D: Device + IEEE4882 + MacroStore,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let label = next_label(&mut params)?;
+        let contents = device
+            .get_macro(label)
+            .ok_or(ErrorCode::MacroHeaderNotFound)?;
+        response.data(Arbitrary(contents)).finish()
+    }
+}
+
+///## 10.16 *LMC?, Learn Macro Query
+///> The Learn Macro query returns the currently defined macro labels.
+///
+/// Responds with the labels as comma separated strings, or an empty string if no macros are defined.
+pub struct LmcCommand;
+
+impl<D> Command<D> for LmcCommand
+where
+    D: Device + IEEE4882 + MacroStore,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let labels = device.macro_labels();
+        if labels.is_empty() {
+            response.data(&b""[..]);
+        }
+        for label in labels {
+            response.data(label);
+        }
+        response.finish()
+    }
+}
+
+///## 10.22 *PMC, Purge Macros Command
+///> The Purge Macros command causes the device to delete all macros that may have been previously defined using
+///> the *DMC command.
+pub struct PmcCommand;
+
+impl<D> Command<D> for PmcCommand
+where
+    D: Device + IEEE4882 + MacroStore,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, _params: Parameters) -> Result<()> {
+        device.purge_macros();
+        Ok(())
+    }
+}
+
+///## 10.30 *RMC, Remove Individual Macro Command
+///> The Remove Individual Macro command removes a single macro definition from the device.
+///
+/// `*RMC <label>` returns `Macro header not found` if the label is not defined.
+pub struct RmcCommand;
+
+impl<D> Command<D> for RmcCommand
+where
+    D: Device + IEEE4882 + MacroStore,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let label = next_label(&mut params)?;
+        device.remove_macro(label)
+    }
+}
+
+/// Create a command node for `*DMC`. See [DmcCommand]
+///
+/// Optional, not required by IEEE488.2 / SCPI.
+#[macro_export]
+macro_rules! ieee488_dmc {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*DMC",
+            default: false,
+            handler: &$crate::ieee488::macros::DmcCommand,
+        }
+    };
+}
+
+/// Create a command node for `*GMC?`. See [GmcCommand]
+///
+/// Optional, not required by IEEE488.2 / SCPI.
+#[macro_export]
+macro_rules! ieee488_gmc {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*GMC",
+            default: false,
+            handler: &$crate::ieee488::macros::GmcCommand,
+        }
+    };
+}
+
+/// Create a command node for `*LMC?`. See [LmcCommand]
+///
+/// Optional, not required by IEEE488.2 / SCPI.
+#[macro_export]
+macro_rules! ieee488_lmc {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*LMC",
+            default: false,
+            handler: &$crate::ieee488::macros::LmcCommand,
+        }
+    };
+}
+
+/// Create a command node for `*PMC`. See [PmcCommand]
+///
+/// Optional, not required by IEEE488.2 / SCPI.
+#[macro_export]
+macro_rules! ieee488_pmc {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*PMC",
+            default: false,
+            handler: &$crate::ieee488::macros::PmcCommand,
+        }
+    };
+}
+
+/// Create a command node for `*RMC`. See [RmcCommand]
+///
+/// Optional, not required by IEEE488.2 / SCPI.
+#[macro_export]
+macro_rules! ieee488_rmc {
+    () => {
+        scpi::tree::prelude::Leaf {
+            name: b"*RMC",
+            default: false,
+            handler: &$crate::ieee488::macros::RmcCommand,
+        }
+    };
+}
@@ -1,9 +1,12 @@
 //! Contains IEEE 488.2 parser and mandatory commands
 //!
 
-use scpi::error::Result;
+use scpi::error::{ErrorCode, Result};
 
 pub mod common;
+pub mod lrn;
+#[cfg(feature = "alloc")]
+pub mod macros;
 pub mod trg;
 
 pub mod prelude {
@@ -98,10 +101,30 @@ pub trait IEEE4882 {
     /// Executed when a `*TST` command is issued.
     /// See [crate::ieee488::common::TstCommand] for details.
     ///
-    /// Return Ok(()) on successfull self-test or
+    /// Return Ok(()) on successful self-test or
     /// some kind of standard or device-specific error on self-test fault
     fn tst(&mut self) -> Result<()>;
 
+    /// # *TST?
+    /// Self-test result code reported by `*TST?`.
+    /// See [crate::ieee488::common::TstCommand] for details.
+    ///
+    /// Return `Ok(0)` on successful self-test or a nonzero device-specific failure code.
+    /// Default implementation calls [IEEE4882::tst] and reports `0` on success.
+    fn tst_code(&mut self) -> Result<i16> {
+        self.tst().map(|_| 0)
+    }
+
+    /// # *IDN?
+    /// Identification fields `(manufacturer, model, serial, firmware)` returned by `*IDN?`.
+    /// See [crate::ieee488::common::IdnDeviceCommand] for details.
+    ///
+    /// Useful when the serial number or firmware version is only known at runtime.
+    /// Default implementation returns empty manufacturer and model fields and `0` for serial and firmware.
+    fn identification(&self) -> (&[u8], &[u8], &[u8], &[u8]) {
+        (b"", b"", b"0", b"0")
+    }
+
     /// # *RST
     /// Executed when a `*RST` command is issued.
     /// See [crate::ieee488::common::RstCommand] for details.
@@ -116,4 +139,95 @@ pub trait IEEE4882 {
     /// Executed when a `*OPC` command is issued.
     /// See [crate::ieee488::common::OpcCommand] for details.
     fn opc(&mut self) -> Result<()>;
+
+    /// # *OPC?
+    /// Returns true while any overlapped (asynchronous) operation is still running.
+    /// See [crate::ieee488::common::OpcQueryCommand] for details.
+    ///
+    /// Default implementation returns false, i.e. no pending operations.
+    fn pending_operations(&self) -> bool {
+        false
+    }
+
+    /// # *WAI/*OPC?
+    /// Executed when a `*WAI` command or `*OPC?` query is issued.
+    /// See [crate::ieee488::common::WaiCommand] for details.
+    ///
+    /// Devices should drain any queued work (e.g. flush hardware FIFOs) and return only once
+    /// [Self::pending_operations] is false. Operations which may never finish should return an
+    /// error after a device-specific timeout. Default implementation does nothing.
+    fn wait(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// # *CAL?
+    /// Executed when a `*CAL?` query is issued.
+    /// See [crate::ieee488::common::CalCommand] for details.
+    ///
+    /// Return `Ok(0)` on successful calibration or a nonzero device-specific failure code.
+    /// Lost calibration data should be reported as a `Calibration memory lost` error and other
+    /// calibration faults as `Calibration failed`, both are reported as the `*CAL?` response.
+    /// Default implementation does nothing and returns `Ok(0)`.
+    fn calibrate(&mut self) -> Result<i16> {
+        Ok(0)
+    }
+
+    /// # *PSC?
+    /// Power-on status clear flag reported by `*PSC?`.
+    /// See [crate::ieee488::common::PscCommand] for details.
+    ///
+    /// At power-on a device should clear the Service Request Enable and Standard Event Status Enable
+    /// registers (and the SCPI enable registers) if the flag is true, otherwise restore them from
+    /// non-volatile memory. The flag itself must be kept in non-volatile memory.
+    /// Default implementation returns true, i.e. the registers are always cleared at power-on.
+    fn psc(&self) -> bool {
+        true
+    }
+
+    /// # *PSC
+    /// Executed when a `*PSC <flag>` command is issued.
+    /// See [crate::ieee488::common::PscCommand] for details.
+    ///
+    /// Default implementation returns a `Hardware missing` error.
+    fn set_psc(&mut self, _on: bool) -> Result<()> {
+        Err(ErrorCode::HardwareMissing.into())
+    }
+
+    /// # *OPT?
+    /// Installed options reported by `*OPT?`, Eg. `[b"GPIB", b"LAN"]`.
+    /// See [crate::ieee488::common::OptCommand] for details.
+    ///
+    /// Default implementation returns no options.
+    fn options(&self) -> &[&'static [u8]] {
+        &[]
+    }
+
+    /// # *SAV/*RCL
+    /// Number of setup registers available to `*SAV` and `*RCL`, valid register indices are `0..state_slots()`.
+    /// See [crate::ieee488::common::SavCommand] for details.
+    ///
+    /// Register indices outside of `0..state_slots()` return an `Illegal parameter value` error.
+    /// Default implementation returns [None], the index is then not checked and passed on to
+    /// [Self::save_state]/[Self::recall_state].
+    fn state_slots(&self) -> Option<usize> {
+        None
+    }
+
+    /// # *SAV
+    /// Executed when a `*SAV <slot>` command is issued with a valid slot, see [Self::state_slots].
+    /// See [crate::ieee488::common::SavCommand] for details.
+    ///
+    /// Default implementation returns a `Hardware missing` error.
+    fn save_state(&mut self, _slot: usize) -> Result<()> {
+        Err(ErrorCode::HardwareMissing.into())
+    }
+
+    /// # *RCL
+    /// Executed when a `*RCL <slot>` command is issued with a valid slot, see [Self::state_slots].
+    /// See [crate::ieee488::common::RclCommand] for details.
+    ///
+    /// Default implementation returns a `Hardware missing` error.
+    fn recall_state(&mut self, _slot: usize) -> Result<()> {
+        Err(ErrorCode::HardwareMissing.into())
+    }
 }
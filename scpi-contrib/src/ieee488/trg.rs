@@ -5,6 +5,9 @@ use scpi::{cmd_nquery, error::Result, tree::prelude::*};
 use super::IEEE4882;
 
 /// Implements trigger logic for the `*TRG` command
+///
+/// Devices with a trigger subsystem (`unproven` feature) may use
+/// `scpi1999::trigger::sequence::TrgBusCommand` instead.
 pub trait CommonTrg {
     /// Called when `*TRG` is executed.
     fn trig_bus(&mut self) -> Result<()>;
@@ -36,7 +39,7 @@ macro_rules! ieee488_trg {
         $crate::prelude::Leaf {
             name: b"*TRG",
             default: false,
-            handler: &TrgCommand,
+            handler: &$crate::ieee488::trg::TrgCommand,
         }
     };
 }
@@ -0,0 +1,133 @@
+//! # 5 CALibration Subsystem
+//! > The CALibration subsystem is used to perform system calibration.
+//!
+//! Implements `CALibration[:ALL]?` (same as `*CAL?`, see [CalCommand]) and a step-based calibration
+//! flow where each step is selected with `CALibration:STEP`, given its reference value with `CALibration:VALue`
+//! and finally stored to nonvolatile memory with `CALibration:SAVE`, see [Calibration].
+
+use scpi::{cmd_both, cmd_nquery, error::Result, tree::prelude::*};
+
+#[doc(no_inline)]
+pub use crate::ieee488::common::CalCommand;
+
+/// Device with a step-based calibration flow
+pub trait Calibration {
+    /// Select the calibration step.
+    ///
+    /// Return an `Illegal parameter value` error if the step does not exist.
+    fn set_calibration_step(&mut self, step: u32) -> Result<()>;
+
+    /// Currently selected calibration step
+    fn calibration_step(&self) -> u32;
+
+    /// Apply the reference value measured by the operator to the current calibration step.
+    fn set_calibration_value(&mut self, value: f64) -> Result<()>;
+
+    /// Store the calibration data to nonvolatile memory.
+    fn save_calibration(&mut self) -> Result<()>;
+}
+
+/// `CALibration:STEP <step>`
+///
+/// Selects the calibration step, see [Calibration::set_calibration_step].
+///
+/// `CALibration:STEP?`
+///
+/// Returns the current calibration step.
+pub struct CalStepCommand;
+
+impl<D> Command<D> for CalStepCommand
+where
+    D: Device + Calibration,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let step: u32 = params.next_data()?;
+        device.set_calibration_step(step)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.calibration_step()).finish()
+    }
+}
+
+///## 5.5 :VALue \<numeric_value\>
+///> Enters a value into the instrument for a calibration step.
+///
+/// See [Calibration::set_calibration_value].
+pub struct CalValueCommand;
+
+impl<D> Command<D> for CalValueCommand
+where
+    D: Device + Calibration,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let value: f64 = params.next_data()?;
+        device.set_calibration_value(value)
+    }
+}
+
+/// `CALibration:SAVE`
+///
+/// Stores the calibration data, see [Calibration::save_calibration].
+pub struct CalSaveCommand;
+
+impl<D> Command<D> for CalSaveCommand
+where
+    D: Device + Calibration,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, _params: Parameters) -> Result<()> {
+        device.save_calibration()
+    }
+}
+
+/// Create a `CALibration` tree branch for a [Calibration](crate::scpi1999::calibration::Calibration).
+///
+/// `CALibration?` runs the same internal self-calibration as `*CAL?`, while `STEP`, `VALue` and `SAVE`
+/// walk through a manual calibration against external references. Extra nodes, Eg. `CALibration:DATE?`,
+/// are added after `SAVE`.
+#[macro_export]
+macro_rules! scpi_calibration {
+    ($($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"CALibration",
+            default: false,
+            sub: &[
+                scpi::tree::prelude::Leaf {
+                    name: b"ALL",
+                    default: true,
+                    handler: &$crate::ieee488::common::CalCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"STEP",
+                    default: false,
+                    handler: &$crate::scpi1999::calibration::CalStepCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"VALue",
+                    default: false,
+                    handler: &$crate::scpi1999::calibration::CalValueCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"SAVE",
+                    default: false,
+                    handler: &$crate::scpi1999::calibration::CalSaveCommand,
+                },
+                $(
+                    $node
+                ),*
+            ],
+        }
+    };
+}
@@ -0,0 +1,118 @@
+//! # 7 DIAGnostic Subsystem
+//! > The DIAGnostic subsystem contains commands that facilitate the servicing of the instrument.
+//! > This includes functions such as internal self-test and debug commands.
+//!
+//! The contents of the subsystem are device specific. This module provides
+//! `DIAGnostic:TEST? <test>` to run a device defined test selected by character data and
+//! `DIAGnostic:RESult?` to read back the result of the last test, see [Diagnostic].
+//!
+//! Tests are usually an enum deriving [ScpiEnum](scpi::option::ScpiEnum):
+//! ```ignore
+//! #[derive(Debug, Clone, Copy, ScpiEnum)]
+//! enum MyTest {
+//!     #[scpi(mnemonic = b"RAM")]
+//!     Ram,
+//!     #[scpi(mnemonic = b"ROM")]
+//!     Rom,
+//! }
+//! ```
+
+use scpi::{
+    cmd_qonly, error::Result, option::ScpiEnum, parser::format::Character, tree::prelude::*,
+};
+
+/// Device with diagnostic tests
+pub trait Diagnostic {
+    /// Tests selectable by `DIAGnostic:TEST?`
+    type Test: ScpiEnum + Copy;
+
+    /// Run a test.
+    ///
+    /// Return `Ok(0)` if the test passed or a nonzero device-specific failure code.
+    fn run_test(&mut self, test: Self::Test) -> Result<i16>;
+
+    /// Most recently run test and its result code, `None` if no test has been run.
+    fn test_result(&self) -> Option<(Self::Test, i16)>;
+}
+
+/// `DIAGnostic:TEST? <test>`
+///
+/// Runs the selected test and returns its result code, see [Diagnostic::run_test].
+/// An unknown test returns an `Illegal parameter value` error.
+pub struct DiagTestCommand;
+
+impl<D> Command<D> for DiagTestCommand
+where
+    D: Device + Diagnostic,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let Character(test) = params.next_data()?;
+        let test = D::Test::from_mnemonic(test).ok_or(ErrorCode::IllegalParameterValue)?;
+        let code = device.run_test(test)?;
+        response.data(code).finish()
+    }
+}
+
+/// `DIAGnostic:RESult?`
+///
+/// Returns the most recently run test and its result code, Eg. `RAM,0`, see [Diagnostic::test_result].
+/// Returns a `Data corrupt or stale` error if no test has been run.
+pub struct DiagResultCommand;
+
+impl<D> Command<D> for DiagResultCommand
+where
+    D: Device + Diagnostic,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let (test, code) = device.test_result().ok_or(ErrorCode::DataCorruptOrStale)?;
+        response
+            .data(Character(test.short_form()))
+            .data(code)
+            .finish()
+    }
+}
+
+/// Create a `DIAGnostic` tree branch for a [Diagnostic](crate::scpi1999::diagnostic::Diagnostic)
+/// which runs a single test with `DIAGnostic:TEST? <test>` and reports the last one with `DIAGnostic:RESult?`.
+///
+/// Extra nodes, Eg. service commands to read internal voltages, are added after `RESult`.
+#[macro_export]
+macro_rules! scpi_diagnostic {
+    ($($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"DIAGnostic",
+            default: false,
+            sub: &[
+                scpi::tree::prelude::Leaf {
+                    name: b"TEST",
+                    default: false,
+                    handler: &$crate::scpi1999::diagnostic::DiagTestCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"RESult",
+                    default: false,
+                    handler: &$crate::scpi1999::diagnostic::DiagResultCommand,
+                },
+                $(
+                    $node
+                ),*
+            ],
+        }
+    };
+}
@@ -0,0 +1,126 @@
+//! # 8 DISPlay Subsystem
+//! > The DISPlay subsystem controls the selection and presentation of textual, graphical, and
+//! > TRACe information.
+//!
+//! A single window is supported, `DISPlay:TEXT "Busy"` shows a message on it and `DISPlay OFF`
+//! blanks it, see [Display].
+
+use scpi::{cmd_both, error::Result, tree::prelude::*};
+
+/// Device with a front panel display
+pub trait Display {
+    /// Show a text message on the display.
+    ///
+    /// Return a `Too much data` error if the text does not fit the display.
+    fn set_display_text(&mut self, text: &str) -> Result<()>;
+
+    /// Text message currently shown
+    fn display_text(&self) -> &str;
+
+    /// Turn the display on or off
+    fn set_display_state(&mut self, on: bool) -> Result<()>;
+
+    /// Returns true if the display is on
+    fn display_state(&self) -> bool;
+}
+
+/// `DISPlay[:WINDow]:TEXT[:DATA] <string>`
+///
+/// Shows a text message on the display, see [Display::set_display_text].
+///
+/// `DISPlay[:WINDow]:TEXT[:DATA]?`
+///
+/// Returns the current text message as a quoted string.
+pub struct DispTextCommand;
+
+impl<D> Command<D> for DispTextCommand
+where
+    D: Device + Display,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let text: &str = params.next_data()?;
+        device.set_display_text(text)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.display_text().as_bytes()).finish()
+    }
+}
+
+/// `DISPlay[:WINDow][:STATe] <Boolean>`
+///
+/// Turns the display on or off, see [Display::set_display_state].
+///
+/// `DISPlay[:WINDow][:STATe]?`
+///
+/// Returns `1` if the display is on, `0` otherwise.
+pub struct DispStateCommand;
+
+impl<D> Command<D> for DispStateCommand
+where
+    D: Device + Display,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let on: bool = params.next_data()?;
+        device.set_display_state(on)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.display_state()).finish()
+    }
+}
+
+/// Create a `DISPlay` tree branch for a [Display](crate::scpi1999::display::Display) with one
+/// default `WINDow`, so `DISPlay:TEXT` and `DISPlay:WINDow:TEXT:DATA` are the same command.
+///
+/// Extra nodes, Eg. a `DISPlay:BRIGhtness` command, are added next to `WINDow`.
+#[macro_export]
+macro_rules! scpi_display {
+    ($($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"DISPlay",
+            default: false,
+            sub: &[
+                scpi::tree::prelude::Branch {
+                    name: b"WINDow",
+                    default: true,
+                    sub: &[
+                        scpi::tree::prelude::Leaf {
+                            name: b"STATe",
+                            default: true,
+                            handler: &$crate::scpi1999::display::DispStateCommand,
+                        },
+                        scpi::tree::prelude::Branch {
+                            name: b"TEXT",
+                            default: false,
+                            sub: &[scpi::tree::prelude::Leaf {
+                                name: b"DATA",
+                                default: true,
+                                handler: &$crate::scpi1999::display::DispTextCommand,
+                            }],
+                        },
+                    ],
+                },
+                $(
+                    $node
+                ),*
+            ],
+        }
+    };
+}
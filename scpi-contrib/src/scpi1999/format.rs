@@ -0,0 +1,175 @@
+//! # 9 FORMat Subsystem
+//! > The FORMat subsystem sets a data format for transferring numeric and array information.
+
+use scpi::{
+    cmd_both,
+    error::Result,
+    parser::response::{ByteOrder, NumericFormat},
+    tree::prelude::*,
+};
+
+/// `<type>` parameter of `FORMat[:DATA]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scpi_derive::ScpiEnum)]
+enum DataType {
+    #[scpi(mnemonic = b"ASCii")]
+    Ascii,
+    #[scpi(mnemonic = b"REAL")]
+    Real,
+}
+
+/// `<type>` parameter of `FORMat:BORDer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scpi_derive::ScpiEnum)]
+enum BorderType {
+    #[scpi(mnemonic = b"NORMal")]
+    Normal,
+    #[scpi(mnemonic = b"SWAPped")]
+    Swapped,
+}
+
+/// ## 9.2 :BORDer \<type\>
+/// > `FORMat:BORDer NORMal|SWAPped`
+/// > Controls whether binary data is transferred in normal or swapped byte order.
+///
+/// Sets the [ByteOrder] of the [Context], used by `REAL` data (see [FormDataCommand]) and
+/// [RealBlock]/[RealBlock64] responses.
+///
+/// `FORMat:BORDer?` returns `NORM` or `SWAP`.
+pub struct FormBorderCommand;
+
+impl<D> Command<D> for FormBorderCommand
+where
+    D: Device,
+{
+    cmd_both!();
+
+    fn event(&self, _device: &mut D, context: &mut Context, mut params: Parameters) -> Result<()> {
+        context.set_byte_order(match params.next_data()? {
+            BorderType::Normal => ByteOrder::Normal,
+            BorderType::Swapped => ByteOrder::Swapped,
+        });
+        Ok(())
+    }
+
+    fn query(
+        &self,
+        _device: &mut D,
+        context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        match context.byte_order() {
+            ByteOrder::Normal => response.data(Character(b"NORM")),
+            ByteOrder::Swapped => response.data(Character(b"SWAP")),
+        }
+        .finish()
+    }
+}
+
+/// Device stored data format settings
+pub trait Format {
+    /// Byte order last selected by `FORMat:BORDer`
+    fn byte_order(&self) -> ByteOrder;
+
+    /// Set byte order, called by `FORMat:BORDer`
+    fn set_byte_order(&mut self, byte_order: ByteOrder);
+
+    /// Set up a [Context] with the stored settings.
+    ///
+    /// Call before executing a message in a new context so that binary responses,
+    /// Eg. [RealBlock], use the byte order selected by a previous `FORMat:BORDer`.
+    fn restore_format(&self, context: &mut Context) {
+        context.set_byte_order(self.byte_order());
+    }
+}
+
+/// ## 9.2 :BORDer \<type\>
+/// > `FORMat:BORDer NORMal|SWAPped`
+/// > Controls whether binary data is transferred in normal or swapped byte order.
+///
+/// Same as [FormBorderCommand] but also saves the selected [ByteOrder] on the device (see [Format])
+/// so that the setting outlives the [Context]. New contexts should be set up with [Format::restore_format].
+///
+/// `FORMat:BORDer?` returns the byte order saved on the device.
+pub struct FormBorderDeviceCommand;
+
+impl<D> Command<D> for FormBorderDeviceCommand
+where
+    D: Device + Format,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, context: &mut Context, params: Parameters) -> Result<()> {
+        FormBorderCommand.event(device, context, params)?;
+        device.set_byte_order(context.byte_order());
+        Ok(())
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        context: &mut Context,
+        params: Parameters,
+        response: ResponseUnit,
+    ) -> Result<()> {
+        device.restore_format(context);
+        FormBorderCommand.query(device, context, params, response)
+    }
+}
+
+/// Significant digits of a double precision real
+const MAX_ASCII_LENGTH: u8 = 17;
+
+/// ## 9.1 \[:DATA\] \<type\>\[,\<length\>\]
+/// > `FORMat[:DATA] ASCii|REAL[,<length>]`
+/// > The DATA command selects the data format and type for transferring numeric and array
+/// > data.
+///
+/// Sets the [NumericFormat] of the [Context] and thus only affects queries executed in the same context.
+/// The `<length>` of `ASCii` is the number of significant digits of `<NR3>` reals, limited to 17.
+/// A length of 0 (default) uses the shortest representation of each value.
+/// `REAL` accepts 32 or 64 (default) bits.
+///
+/// `FORMat[:DATA]?` returns `ASC,<length>`, `REAL,32` or `REAL,64`.
+/// Other ASCII formats set directly on the context are returned as `ASC,0`.
+pub struct FormDataCommand;
+
+impl<D> Command<D> for FormDataCommand
+where
+    D: Device,
+{
+    cmd_both!();
+
+    fn event(&self, _device: &mut D, context: &mut Context, mut params: Parameters) -> Result<()> {
+        let format = match params.next_data()? {
+            DataType::Ascii => match params.next_optional_data::<u8>()? {
+                Some(0) | None => NumericFormat::Ascii,
+                Some(length) => NumericFormat::Nr3(length.min(MAX_ASCII_LENGTH) - 1),
+            },
+            DataType::Real => match params.next_optional_data::<u8>()? {
+                Some(32) => NumericFormat::Real32,
+                Some(64) | None => NumericFormat::Real64,
+                Some(_) => return Err(ErrorCode::IllegalParameterValue.into()),
+            },
+        };
+        context.set_numeric_format(format);
+        Ok(())
+    }
+
+    fn query(
+        &self,
+        _device: &mut D,
+        context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        match context.numeric_format() {
+            NumericFormat::Nr3(decimals) => response.data(Character(b"ASC")).data(decimals + 1),
+            NumericFormat::Ascii | NumericFormat::Nr1 | NumericFormat::Nr2(_) => {
+                response.data(Character(b"ASC")).data(0u8)
+            }
+            NumericFormat::Real32 => response.data(Character(b"REAL")).data(32u8),
+            NumericFormat::Real64 => response.data(Character(b"REAL")).data(64u8),
+        }
+        .finish()
+    }
+}
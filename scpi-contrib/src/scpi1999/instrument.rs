@@ -0,0 +1,161 @@
+//! # 12 INSTrument Subsystem
+//! > An instrument may have multiple logical instruments. The INSTrument subsystem provides a
+//! > mechanism to identify and select logical instruments by either name or number.
+//!
+//! A logical instrument is selected by name with `INSTrument OUTPut2` or by number with
+//! `INSTrument:NSELect 2`, `INSTrument:CATalog?` lists the names.
+//! Commands are routed to the selected logical instrument by the device, see [InstrumentSelect].
+
+use scpi::{cmd_both, cmd_qonly, error::Result, parser::mnemonic_match, tree::prelude::*};
+
+/// Device with multiple logical instruments
+///
+/// Logical instrument `n` (starting at 1) is named `instruments()[n - 1]`.
+/// Commands affecting a logical instrument should use [InstrumentSelect::selected_instrument]
+/// to route the command to the currently selected instrument.
+pub trait InstrumentSelect {
+    /// Names of the logical instruments, Eg. `[b"PSU", b"DMM"]`.
+    fn instruments(&self) -> &[&'static [u8]];
+
+    /// Select logical instrument `n`, `n` is always a valid instrument number.
+    fn select_instrument(&mut self, n: usize) -> Result<()>;
+
+    /// Number of the selected logical instrument
+    fn selected_instrument(&self) -> usize;
+}
+
+/// `INSTrument[:SELect] <name>`
+///
+/// Selects a logical instrument by name, see [InstrumentSelect::select_instrument].
+/// Returns `Illegal parameter value` if there's no instrument with that name.
+///
+/// `INSTrument[:SELect]?`
+///
+/// Returns the name of the selected logical instrument.
+pub struct InstSelectCommand;
+
+impl<D> Command<D> for InstSelectCommand
+where
+    D: Device + InstrumentSelect,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let Character(name) = params.next_data()?;
+        let n = device
+            .instruments()
+            .iter()
+            .position(|instrument| mnemonic_match(instrument, name))
+            .ok_or(ErrorCode::IllegalParameterValue)?;
+        device.select_instrument(n + 1)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let name = device
+            .instruments()
+            .get(device.selected_instrument().wrapping_sub(1))
+            .ok_or(ErrorCode::DeviceSpecificError)?;
+        response.data(Character(name)).finish()
+    }
+}
+
+/// `INSTrument:NSELect <NR1>`
+///
+/// Selects a logical instrument by number, see [InstrumentSelect::select_instrument].
+/// Returns `Illegal parameter value` if there's no instrument with that number.
+///
+/// `INSTrument:NSELect?`
+///
+/// Returns the number of the selected logical instrument.
+pub struct InstNSelectCommand;
+
+impl<D> Command<D> for InstNSelectCommand
+where
+    D: Device + InstrumentSelect,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let n: usize = params.next_data()?;
+        if n == 0 || n > device.instruments().len() {
+            return Err(ErrorCode::IllegalParameterValue.into());
+        }
+        device.select_instrument(n)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.selected_instrument()).finish()
+    }
+}
+
+/// `INSTrument:CATalog?`
+///
+/// Returns a comma separated list of the logical instrument names as strings.
+pub struct InstCatalogCommand;
+
+impl<D> Command<D> for InstCatalogCommand
+where
+    D: Device + InstrumentSelect,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        for name in device.instruments() {
+            response.data(*name);
+        }
+        response.finish()
+    }
+}
+
+/// Create a `INSTrument` tree branch for an [InstrumentSelect](crate::scpi1999::instrument::InstrumentSelect)
+/// where the default `SELect` leaf makes `INSTrument <name>` select by name.
+///
+/// Extra nodes, Eg. `INSTrument:COUPle` for devices which can couple their logical instruments,
+/// are added after `CATalog`.
+#[macro_export]
+macro_rules! scpi_instrument {
+    ($($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"INSTrument",
+            default: false,
+            sub: &[
+                scpi::tree::prelude::Leaf {
+                    name: b"SELect",
+                    default: true,
+                    handler: &$crate::scpi1999::instrument::InstSelectCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"NSELect",
+                    default: false,
+                    handler: &$crate::scpi1999::instrument::InstNSelectCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"CATalog",
+                    default: false,
+                    handler: &$crate::scpi1999::instrument::InstCatalogCommand,
+                },
+                $(
+                    $node
+                ),*
+            ],
+        }
+    };
+}
@@ -0,0 +1,205 @@
+//! # 14 MMEMory Subsystem
+//! > The Mass MEMory subsystem provides mass storage capabilities for the instrument.
+//!
+//! Files are listed with `MMEMory:CATalog?`, transferred as 488.2 blocks with `MMEMory:DATA`
+//! and removed with `MMEMory:DELete`, see [MassMemory].
+//!
+//! File names are string program data, Eg. `MMEMory:DELete "wave.dat"`.
+
+use scpi::{
+    cmd_nquery, cmd_qonly,
+    error::Result,
+    parser::{
+        format::Arbitrary,
+        response::{Formatter, ResponseData},
+    },
+    tree::prelude::*,
+};
+
+/// Device with mass memory.
+///
+/// Methods given the name of a missing file should return a `Filename not found` error.
+pub trait MassMemory {
+    /// Number of bytes used and available `(used, free)`.
+    fn memory_usage(&self) -> (usize, usize);
+
+    /// Name and size in bytes of all files in `directory`, or the current directory if [None].
+    ///
+    /// Return a `Filename not found` error if the directory does not exist.
+    fn files(&self, directory: Option<&str>) -> Result<impl Iterator<Item = (&str, usize)> + '_>;
+
+    /// Size in bytes of a file.
+    fn file_size(&self, name: &str) -> Result<usize>;
+
+    /// Write the contents of a file, exactly [MassMemory::file_size] bytes, to `out`.
+    fn read_file(&mut self, name: &str, out: &mut dyn Formatter) -> Result<()>;
+
+    /// Create or overwrite a file.
+    fn write_file(&mut self, name: &str, data: &[u8]) -> Result<()>;
+
+    /// Remove a file.
+    fn delete_file(&mut self, name: &str) -> Result<()>;
+
+    /// Create a directory.
+    fn make_directory(&mut self, name: &str) -> Result<()>;
+}
+
+/// `<file_entry>` of `MMEMory:CATalog?`, Eg. `"wave.dat,,1024"`
+struct FileEntry<'a>(&'a str, usize);
+
+impl ResponseData for FileEntry<'_> {
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        // Response data must be ascii
+        if !self.0.is_ascii() {
+            return Err(ErrorCode::ExecutionError.into());
+        }
+        formatter.push_byte(b'"')?;
+        formatter.push_ascii_escaped(self.0.as_bytes())?;
+        formatter.push_str(b",,")?;
+        self.1.format_response_data(formatter)?;
+        formatter.push_byte(b'"')
+    }
+}
+
+///## 14.2 :CATalog? \[\<directory_name\>\]
+///> Query only. The query returns the following information:
+///> `<numeric_value>,<numeric_value>{,<file_entry>}`
+///> The first two parameters are the total amount of storage currently used and available, in bytes.
+///
+/// Each `<file_entry>` is a string `"<file_name>,<file_type>,<file_size>"` with an empty file type,
+/// see [MassMemory::files]. Files with non-ascii names return an `Execution error`.
+pub struct MmemCatalogCommand;
+
+impl<D> Command<D> for MmemCatalogCommand
+where
+    D: Device + MassMemory,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let directory: Option<&str> = params.next_optional_data()?;
+        let files = device.files(directory)?;
+        let (used, free) = device.memory_usage();
+        response.data(used).data(free);
+        for (name, size) in files {
+            response.data(FileEntry(name, size));
+        }
+        response.finish()
+    }
+}
+
+///## 14.4 :DATA \<file_name\>,\<data\>
+///> The command form is `MMEMory:DATA <file_name>,<data>`. It loads `<data>` into the file `<file_name>`.
+///> `<data>` is in 488.2 block format.
+///>
+///> The query form is `MMEMory:DATA? <file_name>` with the response being the associated `<data>`
+///> in block format.
+///
+/// See [MassMemory::write_file] and [MassMemory::read_file].
+pub struct MmemDataCommand;
+
+impl<D> Command<D> for MmemDataCommand
+where
+    D: Device + MassMemory,
+{
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let name: &str = params.next_data()?;
+        let Arbitrary(data) = params.next_data()?;
+        device.write_file(name, data)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let name: &str = params.next_data()?;
+        let len = device.file_size(name)?;
+        response
+            .block(len, |out| device.read_file(name, out))
+            .finish()
+    }
+}
+
+///## 14.5 :DELete \<file_name\>
+///> Removes a file from the specified mass storage device.
+///
+/// See [MassMemory::delete_file].
+pub struct MmemDeleteCommand;
+
+impl<D> Command<D> for MmemDeleteCommand
+where
+    D: Device + MassMemory,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let name: &str = params.next_data()?;
+        device.delete_file(name)
+    }
+}
+
+///## 14.11 :MDIRectory \<directory_name\>
+///> Make directory. Creates a new directory.
+///
+/// See [MassMemory::make_directory].
+pub struct MmemMdirectoryCommand;
+
+impl<D> Command<D> for MmemMdirectoryCommand
+where
+    D: Device + MassMemory,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let name: &str = params.next_data()?;
+        device.make_directory(name)
+    }
+}
+
+/// Create a `MMEMory` tree branch for a [MassMemory](crate::scpi1999::mmemory::MassMemory) with
+/// `CATalog?`, `DATA`, `DELete` and `MDIRectory`.
+///
+/// Extra nodes, Eg. `MMEMory:LOAD:STATe` to recall instrument settings from a file, are added after `MDIRectory`.
+#[macro_export]
+macro_rules! scpi_mmemory {
+    ($($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"MMEMory",
+            default: false,
+            sub: &[
+                scpi::tree::prelude::Leaf {
+                    name: b"CATalog",
+                    default: false,
+                    handler: &$crate::scpi1999::mmemory::MmemCatalogCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"DATA",
+                    default: false,
+                    handler: &$crate::scpi1999::mmemory::MmemDataCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"DELete",
+                    default: false,
+                    handler: &$crate::scpi1999::mmemory::MmemDeleteCommand,
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"MDIRectory",
+                    default: false,
+                    handler: &$crate::scpi1999::mmemory::MmemMdirectoryCommand,
+                },
+                $(
+                    $node
+                ),*
+            ],
+        }
+    };
+}
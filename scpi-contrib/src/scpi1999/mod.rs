@@ -9,7 +9,11 @@ use scpi::Device;
 
 use crate::ieee488::{StatusBit, IEEE4882};
 
-use self::status::{operation::Operation, questionable::Questionable};
+use self::status::{
+    instrument::{OperationInstrument, OperationInstrumentSummary},
+    operation::Operation,
+    questionable::Questionable,
+};
 
 #[doc(hidden)]
 mod numeric;
@@ -17,19 +21,38 @@ mod numeric;
 pub use numeric::{NumericBuilder, NumericValue, NumericValueDefaults, NumericValueQuery};
 
 // Subsystems
+#[cfg(feature = "unproven")]
+pub mod calibration;
+#[cfg(feature = "unproven")]
+pub mod diagnostic;
+#[cfg(feature = "unproven")]
+pub mod display;
+pub mod format;
 //pub mod input;
+#[cfg(feature = "unproven")]
+pub mod instrument;
 //pub mod measurement;
-//pub mod output;
-//pub mod sense;
+#[cfg(feature = "unproven")]
+pub mod mmemory;
+#[cfg(feature = "unproven")]
+pub mod output;
+#[cfg(feature = "unproven")]
+pub mod route;
+#[cfg(feature = "unproven")]
+pub mod sense;
+#[cfg(feature = "unproven")]
+pub mod source;
 pub mod status;
 pub mod system;
-//pub mod trigger;
-//pub mod unit;
+#[cfg(feature = "unproven")]
+pub mod trigger;
+#[cfg(feature = "unit")]
+pub mod unit;
 
 pub mod prelude {
     pub use super::{
         status::{operation::Operation, questionable::Questionable},
-        EventRegister, GetEventRegister, ScpiDevice,
+        EventRegister, GetEventRegister, ScpiDevice, SummaryRegister,
     };
     pub use scpi::error::{Error, ErrorQueue};
 }
@@ -41,12 +64,58 @@ pub trait ScpiDevice:
         // Do nothing
     }
 
+    /// Device identifier prepended to the extended part of errors returned by `SYSTem:ERRor?`,
+    /// Eg. `-100,"Command error;PREFIX: <extended>"`.
+    ///
+    /// Useful when multiple logical instruments share the same error queue.
+    fn error_extended_prefix(&self) -> Option<&'static [u8]> {
+        None
+    }
+
+    /// SCPI version `(year, revision)` returned by `SYSTem:VERSion?`,
+    /// see [system::SystVersionDeviceCommand].
+    fn scpi_version(&self) -> (u16, u8) {
+        (1999, 0)
+    }
+
+    /// Event classes entering the error/event queue, set by `STATus:QUEue:ENABle`.
+    ///
+    /// Each bit is the ESR bit of a class (see [Error::esr_mask]), events without a class are always queued.
+    /// Default implementation enables all classes.
+    fn queue_enable(&self) -> u8 {
+        0xff
+    }
+
+    /// Set the event classes entering the error/event queue, see [ScpiDevice::queue_enable].
+    ///
+    /// Default implementation only accepts the current mask and returns [ErrorCode::SettingsConflict] otherwise,
+    /// devices storing the mask also add `STATus:QUEue:ENABle` with [scpi_status!](crate::scpi_status).
+    fn set_queue_enable(&mut self, mask: u8) -> Result<()> {
+        if mask == self.queue_enable() {
+            Ok(())
+        } else {
+            Err(ErrorCode::SettingsConflict.into())
+        }
+    }
+
+    /// Device-defined event registers, Eg. added with [scpi_register!](crate::scpi_register),
+    /// which are preset by [ScpiDevice::preset] in addition to OPERation and QUEStionable.
+    ///
+    /// Default implementation returns no registers.
+    fn custom_registers(&mut self) -> impl Iterator<Item = &mut EventRegister> + '_ {
+        core::iter::empty()
+    }
+
     /// Preset registers
     fn preset(&mut self) -> Result<()> {
         // Preset operation register
         self.preset_register::<Operation>();
         // Preset questionable
         self.preset_register::<Questionable>();
+        // Preset device-defined registers
+        for register in self.custom_registers() {
+            register.preset();
+        }
         Ok(())
     }
 
@@ -101,8 +170,23 @@ pub trait ScpiDevice:
         // Set ESR mask
         let esr = self.esr() | err.esr_mask();
         self.set_esr(esr);
-        // Add error to error/event queue
-        self.push_back_error(err);
+        // Add error to error/event queue unless its class is disabled
+        if is_queued(err, self.queue_enable()) {
+            self.push_back_error(err);
+        }
+    }
+
+    /// Same as [ScpiDevice::push_error] but with a runtime extended message returned by `SYSTem:ERRor?`,
+    /// see [ErrorQueue::push_back_error_message].
+    #[cfg(feature = "alloc")]
+    fn push_error_message(&mut self, err: Error, msg: alloc::vec::Vec<u8>) {
+        // Set ESR mask
+        let esr = self.esr() | err.esr_mask();
+        self.set_esr(esr);
+        // Add error to error/event queue unless its class is disabled
+        if is_queued(err, self.queue_enable()) {
+            self.push_back_error_message(err, msg);
+        }
     }
 
     /// Get event register
@@ -140,8 +224,50 @@ pub trait ScpiDevice:
     {
         <Self as GetEventRegister<REG>>::register(self).get_summary()
     }
+
+    /// Copy the summary of register `REG` into its summary bit of the `PARENT` condition register
+    /// (see [SummaryRegister]), recording a transition in the parent event register if it changed.
+    ///
+    /// Should be called after the condition or enable register of `REG` has been changed.
+    /// For multi-level hierarchies call it for each level, starting with the lowest.
+    fn update_register_summary<REG, PARENT>(&mut self)
+    where
+        Self: GetEventRegister<REG> + GetEventRegister<PARENT>,
+        REG: SummaryRegister<PARENT>,
+        PARENT: EventRegisterName,
+    {
+        let summary = self.get_register_summary::<REG>();
+        let parent = self.get_register_mut::<PARENT>();
+        if summary {
+            parent.set_condition_bits(1 << REG::SUMMARY_BIT)
+        } else {
+            parent.clear_condition_bits(1 << REG::SUMMARY_BIT)
+        }
+    }
+
+    /// Update the summary of logical instrument `N` in `STATus:OPERation:INSTrument` and its summary
+    /// in `STATus:OPERation`, see [status::instrument].
+    ///
+    /// Should be called after the condition or enable register of logical instrument `N` has been changed.
+    fn update_instrument_summary<const N: u16>(&mut self)
+    where
+        Self:
+            GetEventRegister<OperationInstrumentSummary<N>> + GetEventRegister<OperationInstrument>,
+    {
+        self.update_register_summary::<OperationInstrumentSummary<N>, OperationInstrument>();
+        self.update_register_summary::<OperationInstrument, Operation>();
+    }
 }
 
+/// Check if the class of `err` is enabled in the queue enable mask, see [ScpiDevice::queue_enable].
+fn is_queued(err: Error, enable: u8) -> bool {
+    let mask = err.esr_mask();
+    mask == 0 || mask & enable != 0
+}
+
+/// Bits used in a status register, bit 15 is reserved and always 0
+const EVENT_REGISTER_MASK: u16 = 0x7fff;
+
 /// This struct contains a register with event/enable functionality
 /// (used in OPERation/QUEStionable registers)
 ///
@@ -169,6 +295,18 @@ pub trait EventRegisterName {
     type BitFlags;
 }
 
+/// A register whose summary is reported in a bit of a parent register,
+/// Eg. a `STATus:QUEStionable:INSTrument` register summarized in [status::questionable::QuestionableBits::InstrumentSummary].
+///
+/// See [ScpiDevice::update_register_summary].
+pub trait SummaryRegister<P>: EventRegisterName
+where
+    P: EventRegisterName,
+{
+    /// Position of the summary bit in the parent register
+    const SUMMARY_BIT: u16;
+}
+
 pub trait GetEventRegister<X>
 where
     X: EventRegisterName,
@@ -220,7 +358,7 @@ impl EventRegister {
     /// Returns true if any enabled condition bit is set, false otherwise.
     ///
     pub fn get_summary(&self) -> bool {
-        (self.condition & self.enable) & 0x7fffu16 != 0u16
+        (self.condition & self.enable) & EVENT_REGISTER_MASK != 0u16
     }
 
     /// Get the state of relevant bit in status register. Returns true if bit is set, false otherwise.
@@ -228,8 +366,11 @@ impl EventRegister {
         self.condition & bitmask != 0
     }
 
-    /// Update condition register and event register based on pos-/neg-transition filters
+    /// Update condition register and event register based on pos-/neg-transition filters.
+    ///
+    /// The reserved bit 15 is always cleared and never recorded in the event register.
     pub fn set_condition(&mut self, condition: u16) {
+        let condition = condition & EVENT_REGISTER_MASK;
         let transitions = self.condition ^ condition;
         // Record pos-/negative-transitions to event register
         self.event |=
@@ -247,6 +388,73 @@ impl EventRegister {
     pub fn clear_condition_bits(&mut self, bitmask: u16) {
         self.set_condition(self.condition & !bitmask)
     }
+
+    /// Set or clear a named bit in condition register (see [status::operation::OperationBits]
+    /// and [status::questionable::QuestionableBits]).
+    pub fn set_condition_flag<B>(&mut self, bit: B, value: bool)
+    where
+        B: BitFlags<u16>,
+    {
+        if value {
+            self.set_condition_bits(bit.get_mask())
+        } else {
+            self.clear_condition_bits(bit.get_mask())
+        }
+    }
+
+    /// Get the state of a named bit in condition register. Returns true if bit is set, false otherwise.
+    pub fn get_condition_flag<B>(&self, bit: B) -> bool
+    where
+        B: BitFlags<u16>,
+    {
+        self.get_condition_bit(bit.get_mask())
+    }
+}
+
+/// Create a root node with the IEEE488.2 mandated common commands and the SCPI mandated
+/// `STATus:` and `SYSTem:` branches.
+///
+/// Takes the `*IDN?` fields (manufacturer, model, serial, firmware) followed by any
+/// device-specific nodes to add to the root.
+///
+/// # Example
+/// ```ignore
+/// const TREE: Node<MyDevice> = scpi_mandatory!(b"Example Inc", b"T800-101", b"0", b"0";
+///     Leaf {
+///         name: b"*LRN",
+///         default: false,
+///         handler: &LrnCommand,
+///     }
+/// );
+/// ```
+#[macro_export]
+macro_rules! scpi_mandatory {
+    ($manufacturer:expr, $model:expr, $serial:expr, $firmware:expr) => {
+        $crate::scpi_mandatory!($manufacturer, $model, $serial, $firmware; )
+    };
+    ($manufacturer:expr, $model:expr, $serial:expr, $firmware:expr; $($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"",
+            default: false,
+            sub: &[
+                $crate::ieee488_cls!(),
+                $crate::ieee488_ese!(),
+                $crate::ieee488_esr!(),
+                $crate::ieee488_idn!($manufacturer, $model, $serial, $firmware),
+                $crate::ieee488_opc!(),
+                $crate::ieee488_rst!(),
+                $crate::ieee488_sre!(),
+                $crate::ieee488_stb!(),
+                $crate::ieee488_tst!(),
+                $crate::ieee488_wai!(),
+                $crate::scpi_status!(),
+                $crate::scpi_system!(),
+                $(
+                    $node
+                ),*
+            ],
+        }
+    };
 }
 
 pub mod util {
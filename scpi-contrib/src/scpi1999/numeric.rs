@@ -2,7 +2,11 @@ use core::ops::{Add, Div, Mul, Sub};
 
 use scpi::{
     error::{Error, ErrorCode, Result},
-    parser::{mnemonic_compare, tokenizer::Token},
+    parser::{
+        mnemonic_compare,
+        response::{Formatter, ResponseData},
+        tokenizer::Token,
+    },
     units::uom::{
         num_traits::Num,
         si::{Dimension, Units},
@@ -25,7 +29,7 @@ use scpi::{
 ///
 /// A <non-decimal numeric> (IEEE 488.2, section 7.7.4), a <numeric_expression>, or a
 /// <label> is part of <numeric_value> if an instrument implements these features.
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
 pub enum NumericValue<T> {
     /// ## <NRf>
     Value(T),
@@ -51,10 +55,10 @@ pub enum NumericValue<T> {
     /// three parameters, and allows MIN/MAX queries on this command. The following queries
     /// shall have these results:
     /// * `SYST:TIME?<nl>` shall return the current setting of the time-of-day clock in the
-    /// instrument.
+    ///   instrument.
     /// * `SYST:TIME? MAX,MAX,MAX<nl>` could return 23,59,59.
     /// * `SYST:TIME? MAX<nl>` shall set an error (-109, “Missing parameter”), since
-    /// three parameters are required and only one was sent.
+    ///   three parameters are required and only one was sent.
     Minimum,
 
     /// ## DEFault
@@ -80,6 +84,7 @@ pub enum NumericValue<T> {
     /// ```text
     ///  MEASure:VOLTage:DC DEFault,0.001V
     /// ```
+    #[default]
     Default,
     Up,
     /// ## UP|DOWN
@@ -97,12 +102,6 @@ pub enum NumericValue<T> {
     Down,
 }
 
-impl<T> Default for NumericValue<T> {
-    fn default() -> Self {
-        Self::Default
-    }
-}
-
 impl<T> NumericValue<T> {
     /// Return value if possible
     pub fn value(&self) -> Option<&T> {
@@ -154,6 +153,28 @@ impl<T> NumericValue<T> {
         self.build().max(max).min(min).finish()
     }
 
+    /// Resolve MINimum, MAXimum and DEFault into the given values.
+    /// Explicit values are checked to be within `min..=max`.
+    ///
+    /// Shorthand for `self.build().min(min).max(max).default(default).finish()`.
+    ///
+    /// ```
+    /// # use scpi_contrib::scpi1999::NumericValue;
+    /// let x: NumericValue<f32> = NumericValue::Default;
+    /// assert_eq!(x.resolve(0.0, 1e6, 1e3).unwrap(), 1e3);
+    ///
+    /// let x: NumericValue<f32> = NumericValue::Value(10.0);
+    /// assert_eq!(x.resolve(0.0, 1e6, 1e3).unwrap(), 10.0);
+    /// ```
+    pub fn resolve(self, min: T, max: T, default: T) -> Result<T>
+    where
+        T: PartialOrd,
+    {
+        NumericBuilder::new(self, max, min)
+            .default(default)
+            .finish()
+    }
+
     pub fn map<F, U>(self, f: F) -> NumericValue<U>
     where
         F: FnOnce(T) -> U,
@@ -322,14 +343,63 @@ where
         }
     }
 
+    /// Check that `value` is within the configured MINimum and MAXimum values.
+    ///
+    /// Returns [ErrorCode::DataOutOfRange] if outside `min..=max`.
+    ///
+    /// ```
+    /// # use scpi_contrib::scpi1999::NumericValue;
+    /// # use scpi::error::ErrorCode;
+    /// let x: NumericValue<f32> = NumericValue::Default;
+    /// let builder = x.build().min(-10.0).max(10.0);
+    /// assert_eq!(builder.clamp_or_error(20.0).unwrap_err(), ErrorCode::DataOutOfRange);
+    /// ```
+    pub fn clamp_or_error(self, value: T) -> Result<T> {
+        if value <= self.max && value >= self.min {
+            Ok(value)
+        } else {
+            Err(ErrorCode::DataOutOfRange.into())
+        }
+    }
+
+    /// Saturate `value` to the configured MINimum and MAXimum values.
+    ///
+    /// Quantities are compared in their base unit so the limits may be given in any unit.
+    ///
+    /// ```
+    /// # use scpi_contrib::scpi1999::NumericValue;
+    /// use scpi::units::uom::si::{f32::Time, time::{millisecond, second}};
+    ///
+    /// let x: NumericValue<Time> = NumericValue::Default;
+    /// let builder = x.build().max(Time::new::<second>(1.0));
+    /// let value = builder.clamp(Time::new::<millisecond>(1500.0));
+    /// assert_eq!(value, Time::new::<second>(1.0));
+    /// ```
+    pub fn clamp(self, value: T) -> T {
+        if value > self.max {
+            self.max
+        } else if value < self.min {
+            self.min
+        } else {
+            value
+        }
+    }
+
     /// Resolve value or return an appropriate error
+    ///
+    /// Returns [ErrorCode::DataTypeError] if DEFault is used but no default value has been set.
+    ///
+    /// ```
+    /// # use scpi_contrib::scpi1999::NumericValue;
+    /// # use scpi::error::{Error, ErrorCode};
+    /// let x: NumericValue<f32> = NumericValue::Default;
+    /// assert_eq!(x.build().finish(), Err(Error::new(ErrorCode::DataTypeError)));
+    /// ```
     pub fn finish(self) -> Result<T> {
         match self.value {
             NumericValue::Maximum => Ok(self.max),
             NumericValue::Minimum => Ok(self.min),
-            NumericValue::Default => self
-                .default
-                .ok_or_else(|| ErrorCode::IllegalParameterValue.into()),
+            NumericValue::Default => self.default.ok_or_else(|| ErrorCode::DataTypeError.into()),
             NumericValue::Up => Err(ErrorCode::IllegalParameterValue.into()),
             NumericValue::Down => Err(ErrorCode::IllegalParameterValue.into()),
             NumericValue::Value(t) => {
@@ -364,8 +434,31 @@ where
     }
 }
 
+/// Formats explicit values as `T` and special values as the short form keyword, Eg. `MAX` for [NumericValue::Maximum].
+///
+/// Useful for echoing a stored setting which may be a special value. There is no AUTO form, see [super::util::Auto]
+/// for settings which may also be automatic.
+impl<T> ResponseData for NumericValue<T>
+where
+    T: ResponseData,
+{
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        match self {
+            NumericValue::Value(t) => t.format_response_data(formatter),
+            NumericValue::Maximum => formatter.push_ascii(b"MAX"),
+            NumericValue::Minimum => formatter.push_ascii(b"MIN"),
+            NumericValue::Default => formatter.push_ascii(b"DEF"),
+            NumericValue::Up => formatter.push_ascii(b"UP"),
+            NumericValue::Down => formatter.push_ascii(b"DOWN"),
+        }
+    }
+}
+
 /// A mirror of [NumericValue] which only matches MAXimum|MINimum|DEFault for queries of said values.
+///
+/// Also matches STEP (or RESolution) for queries of the step size used by UP|DOWN, Eg. `SOURce:VOLTage? STEP`.
 #[derive(Debug, Clone, Copy, scpi_derive::ScpiEnum)]
+#[non_exhaustive]
 pub enum NumericValueQuery {
     /// See [NumericValue::Maximum]
     #[scpi(mnemonic = b"MAXimum")]
@@ -376,4 +469,7 @@ pub enum NumericValueQuery {
     /// See [NumericValue::Default]
     #[scpi(mnemonic = b"DEFault")]
     Default,
+    /// Step size or resolution, see [NumericValue::Up]
+    #[scpi(mnemonic = b"STEP", alias = b"RESolution")]
+    Step,
 }
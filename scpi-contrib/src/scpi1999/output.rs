@@ -0,0 +1,73 @@
+//! # 15 OUTPut Subsystem
+//! > The OUTPut subsystem controls the characteristics of the source's output port.
+//!
+//! `OUTPut ON|OFF` connects or disconnects the output terminals, see [OutpStateCommand].
+//! Protection, coupling and filter settings depend on the kind of source and are left to the device.
+
+use scpi::{cmd_both, error::Result, tree::prelude::*};
+
+/// Device with a switchable signal output
+pub trait Output {
+    /// Enable or disable the output.
+    ///
+    /// Return a `Settings conflict` error if the output cannot be enabled in the current state.
+    fn set_output(&mut self, on: bool) -> Result<()>;
+
+    /// Returns true if the output is enabled
+    fn output(&self) -> bool;
+}
+
+/// `OUTPut[:STATe] <Boolean>`
+///
+/// Enables or disables the output, see [Output::set_output].
+///
+/// `OUTPut[:STATe]?`
+///
+/// Returns `1` if the output is enabled, `0` otherwise.
+pub struct OutpStateCommand;
+
+impl<D> Command<D> for OutpStateCommand
+where
+    D: Device + Output,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let on: bool = params.next_data()?;
+        device.set_output(on)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.output()).finish()
+    }
+}
+
+/// Create a `OUTPut` tree branch for an [Output](crate::scpi1999::output::Output) where the default
+/// `STATe` leaf makes `OUTPut ON` switch the output.
+///
+/// Extra nodes, Eg. `OUTPut:PROTection` of a power supply, are added next to `STATe`.
+#[macro_export]
+macro_rules! scpi_output {
+    ($($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"OUTPut",
+            default: false,
+            sub: &[
+                scpi::tree::prelude::Leaf {
+                    name: b"STATe",
+                    default: true,
+                    handler: &$crate::scpi1999::output::OutpStateCommand,
+                },
+                $(
+                    $node
+                ),*
+            ],
+        }
+    };
+}
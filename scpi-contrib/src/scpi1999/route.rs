@@ -0,0 +1,134 @@
+//! # 17 ROUTe Subsystem
+//! > The ROUTe subsystem is used to control signal routing through the instrument, Eg. the
+//! > relays of a switch matrix.
+//!
+//! Relays are addressed with channel lists, Eg. `ROUTe:CLOSe (@1:4,7)` closes the channels
+//! 1 to 4 and 7 and `ROUTe:CLOSe? (@1:8)` returns the state of each of the channels 1 to 8, see [Route].
+
+use scpi::{
+    cmd_both, cmd_nquery,
+    error::Result,
+    parser::expression::channel_list::{ChannelEntry, ChannelList},
+    tree::prelude::*,
+};
+
+/// Device with routable channels.
+pub trait Route {
+    /// Close a channel.
+    ///
+    /// Ranges of one-dimensional channels are expanded into [ChannelEntry::Single] before this is called,
+    /// other entries are passed as-is.
+    fn route_close(&mut self, channel: ChannelEntry) -> Result<()>;
+
+    /// Open a channel, see [Route::route_close].
+    fn route_open(&mut self, channel: ChannelEntry) -> Result<()>;
+
+    /// All one-dimensional channels currently closed, returned by `ROUTe:CLOSe?`.
+    fn closed_channels(&self) -> impl Iterator<Item = u32> + '_;
+
+    /// Returns true if a channel is closed, returned by `ROUTe:CLOSe? <channel_list>`.
+    ///
+    /// Ranges of one-dimensional channels are expanded as for [Route::route_close].
+    /// Default implementation looks up [ChannelEntry::Single] in [Route::closed_channels] and
+    /// returns an `Illegal parameter value` error for other entries.
+    fn route_closed(&self, channel: ChannelEntry) -> Result<bool> {
+        match channel {
+            ChannelEntry::Single(ch) => Ok(self.closed_channels().any(|x| x == ch)),
+            _ => Err(ErrorCode::IllegalParameterValue.into()),
+        }
+    }
+}
+
+/// Apply `f` to each entry of the channel list parameter, expanding one-dimensional ranges.
+fn for_each_channel<D, F>(device: &mut D, mut params: Parameters, mut f: F) -> Result<()>
+where
+    F: FnMut(&mut D, ChannelEntry) -> Result<()>,
+{
+    let list: ChannelList = params.next_data()?;
+    for entry in list.entries() {
+        let entry = entry?;
+        match entry.channels() {
+            Some(channels) => {
+                for ch in channels {
+                    f(device, ChannelEntry::Single(ch))?;
+                }
+            }
+            None => f(device, entry)?,
+        }
+    }
+    Ok(())
+}
+
+/// Closed channels formatted as a channel list, Eg. `(@1,2,3)`
+struct ClosedChannels<'d, D>(&'d D);
+
+impl<D> ResponseData for ClosedChannels<'_, D>
+where
+    D: Route,
+{
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        formatter.push_str(b"(@")?;
+        for (i, ch) in self.0.closed_channels().enumerate() {
+            if i > 0 {
+                formatter.push_byte(b',')?;
+            }
+            ch.format_response_data(formatter)?;
+        }
+        formatter.push_byte(b')')
+    }
+}
+
+///## 17.2 :CLOSe
+///> `ROUTe:CLOSe <channel_list>` closes the channels specified in the channel list.
+///
+/// `ROUTe:CLOSe?`
+///
+/// Returns the closed channels as a channel list, Eg. `(@1,2,3)`, see [Route::closed_channels].
+///
+/// `ROUTe:CLOSe? <channel_list>`
+///
+/// Returns `1` for each closed and `0` for each open channel in the channel list, see [Route::route_closed].
+pub struct RoutCloseCommand;
+
+impl<D> Command<D> for RoutCloseCommand
+where
+    D: Device + Route,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, params: Parameters) -> Result<()> {
+        for_each_channel(device, params, |device, ch| device.route_close(ch))
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        if params.peek_token()?.is_none() {
+            return response.data(ClosedChannels(device)).finish();
+        }
+        for_each_channel(device, params, |device, ch| {
+            response.data(device.route_closed(ch)?);
+            Ok(())
+        })?;
+        response.finish()
+    }
+}
+
+///## 17.5 :OPEN
+///> `ROUTe:OPEN <channel_list>` opens the channels specified in the channel list.
+pub struct RoutOpenCommand;
+
+impl<D> Command<D> for RoutOpenCommand
+where
+    D: Device + Route,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, params: Parameters) -> Result<()> {
+        for_each_channel(device, params, |device, ch| device.route_open(ch))
+    }
+}
@@ -0,0 +1,140 @@
+//! ## 18.16 FUNCtion Subsystem
+//! > The FUNCtion subsystem is used to select the sensor function which is to be sensed.
+//!
+//! Sensor functions are strings of the form `<sensor_function>[:<function_modifier>]`,
+//! Eg. `"VOLTage:DC"`, where each element may be given in short or long form.
+
+use scpi::{error::Result, parser::mnemonic_match, tree::prelude::*};
+
+use super::Sense;
+
+/// A sensor function selectable by `SENSe:FUNCtion`
+pub trait SensorFunction: Copy + PartialEq + 'static {
+    /// All sensor functions supported, in the order they are listed by `SENSe:FUNCtion:OFF?`
+    const FUNCTIONS: &'static [Self];
+
+    /// Sensor function string, Eg. `b"VOLTage:DC"`
+    fn function(&self) -> &'static [u8];
+
+    /// Find the function matching a (short or long form) sensor function string, Eg. `b"volt:dc"`
+    fn from_function(s: &[u8]) -> Option<Self> {
+        Self::FUNCTIONS
+            .iter()
+            .copied()
+            .find(|f| function_match(f.function(), s))
+    }
+}
+
+/// Compare each `:`-separated element of a sensor function string
+fn function_match(function: &[u8], s: &[u8]) -> bool {
+    let mut expected = function.split(|c| *c == b':');
+    let mut actual = s.split(|c| *c == b':');
+    loop {
+        match (expected.next(), actual.next()) {
+            (Some(m), Some(x)) if mnemonic_match(m, x) => {}
+            (None, None) => break true,
+            _ => break false,
+        }
+    }
+}
+
+/// Apply `f` to each sensor function string parameter, at least one is required.
+fn for_each_function<D, F>(device: &mut D, mut params: Parameters, mut f: F) -> Result<()>
+where
+    D: Sense,
+    F: FnMut(&mut D, D::Function) -> Result<()>,
+{
+    let mut s: Option<&[u8]> = Some(params.next_data()?);
+    while let Some(function) = s {
+        let function =
+            D::Function::from_function(function).ok_or(ErrorCode::IllegalParameterValue)?;
+        f(device, function)?;
+        s = params.next_optional_data()?;
+    }
+    Ok(())
+}
+
+/// Respond with the given functions as a list of quoted strings,
+/// or an empty string if there are none.
+fn respond_functions<F>(
+    mut response: ResponseUnit,
+    functions: impl Iterator<Item = F>,
+) -> Result<()>
+where
+    F: SensorFunction,
+{
+    let mut any = false;
+    for function in functions {
+        response.data(function.function());
+        any = true;
+    }
+    if !any {
+        response.data(&b""[..]);
+    }
+    response.finish()
+}
+
+/// `[SENSe:]FUNCtion[:ON] <sensor_function>{,<sensor_function>}`
+///
+/// Turns on the listed sensor functions, see [Sense::set_function_on].
+///
+/// `[SENSe:]FUNCtion[:ON]?`
+///
+/// Returns all functions which are on as comma-separated quoted strings, Eg. `"VOLTage:DC","CURRent:DC"`,
+/// see [Sense::functions_on].
+pub struct SensFunctionOnCommand;
+
+impl<D> Command<D> for SensFunctionOnCommand
+where
+    D: Device + Sense,
+{
+    fn event(&self, device: &mut D, _context: &mut Context, params: Parameters) -> Result<()> {
+        for_each_function(device, params, |device, function| {
+            device.set_function_on(function)
+        })
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        response: ResponseUnit,
+    ) -> Result<()> {
+        respond_functions(response, device.functions_on())
+    }
+}
+
+/// `[SENSe:]FUNCtion:OFF <sensor_function>{,<sensor_function>}`
+///
+/// Turns off the listed sensor functions, see [Sense::set_function_off].
+///
+/// `[SENSe:]FUNCtion:OFF?`
+///
+/// Returns all functions in [SensorFunction::FUNCTIONS] which are not on.
+pub struct SensFunctionOffCommand;
+
+impl<D> Command<D> for SensFunctionOffCommand
+where
+    D: Device + Sense,
+{
+    fn event(&self, device: &mut D, _context: &mut Context, params: Parameters) -> Result<()> {
+        for_each_function(device, params, |device, function| {
+            device.set_function_off(function)
+        })
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        response: ResponseUnit,
+    ) -> Result<()> {
+        let off = D::Function::FUNCTIONS
+            .iter()
+            .copied()
+            .filter(|f| !device.functions_on().any(|on| on == *f));
+        respond_functions(response, off)
+    }
+}
@@ -0,0 +1,45 @@
+//! # 18 SENSe Subsystem
+//! > The purpose of the SENSe command subsystem is to directly configure device specific settings
+//! > used to make measurements, generally specifying parameters related to the sensor.
+//!
+//! Only the `SENSe:FUNCtion`, `SENSe:<function>:RANGe:AUTO` and `SENSe:<function>:NPLCycles` commands
+//! are currently implemented, see [function], [range] and [nplc].
+
+use scpi::error::{ErrorCode, Result};
+
+use self::function::SensorFunction;
+
+pub mod function;
+pub mod nplc;
+pub mod range;
+
+/// Device with one or more sensor functions.
+///
+/// Single-function devices only need to implement [Sense::get_function_on] and [Sense::set_function_on],
+/// devices with concurrent functions should also implement [Sense::functions_on] and [Sense::set_function_off].
+pub trait Sense {
+    /// Sensor functions supported by the device
+    type Function: SensorFunction;
+
+    /// Currently sensed function, `None` if no function is on.
+    fn get_function_on(&self) -> Option<Self::Function>;
+
+    /// Turn on a sensor function.
+    ///
+    /// Devices without concurrent sensing should turn off any other function.
+    fn set_function_on(&mut self, function: Self::Function) -> Result<()>;
+
+    /// Turn off a sensor function.
+    ///
+    /// Returns a `SettingsConflict` error by default as a single-function device cannot turn its only function off.
+    fn set_function_off(&mut self, _function: Self::Function) -> Result<()> {
+        Err(ErrorCode::SettingsConflict.into())
+    }
+
+    /// All sensor functions currently on.
+    ///
+    /// Defaults to the single function returned by [Sense::get_function_on].
+    fn functions_on(&self) -> impl Iterator<Item = Self::Function> + '_ {
+        self.get_function_on().into_iter()
+    }
+}
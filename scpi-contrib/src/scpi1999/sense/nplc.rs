@@ -0,0 +1,87 @@
+//! ## \<function\>:NPLCycles
+//! > Specifies the integration time in number of power line cycles, Eg. `SENSe:VOLTage:NPLCycles 10`.
+//!
+//! Integrating over a whole number of power line cycles rejects noise at the line frequency.
+
+use core::marker::PhantomData;
+
+use scpi::{cmd_both, error::Result, tree::prelude::*};
+
+use crate::scpi1999::{NumericValue, NumericValueQuery};
+
+/// Device with an integration time setting for the sensor function `F`.
+///
+/// `F` is a marker type and allows a device to have one setting per sensor function, see [super::range::RangeAuto].
+pub trait Nplc<F> {
+    /// Set the integration time in power line cycles, the value has been checked to be within
+    /// [Self::nplc_minimum] and [Self::nplc_maximum].
+    fn set_nplc(&mut self, nplc: f32) -> Result<()>;
+
+    /// Current integration time in power line cycles
+    fn nplc(&self) -> f32;
+
+    /// Shortest allowed integration time, used by `MINimum`
+    fn nplc_minimum(&self) -> f32;
+
+    /// Longest allowed integration time, used by `MAXimum`
+    fn nplc_maximum(&self) -> f32;
+
+    /// Default integration time, used by `DEFault`
+    fn nplc_default(&self) -> f32;
+}
+
+/// `[SENSe:]<function>:NPLCycles <numeric_value>`
+///
+/// Sets the integration time in power line cycles after resolving MINimum|MAXimum|DEFault,
+/// see [Nplc::set_nplc]. Values outside of the allowed range return a `Data out of range` error.
+///
+/// `[SENSe:]<function>:NPLCycles? [MINimum|MAXimum|DEFault]`
+///
+/// Returns the current setting or the requested limit.
+pub struct SensNplcCommand<F>(PhantomData<F>);
+
+impl<F> SensNplcCommand<F> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for SensNplcCommand<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, F> Command<D> for SensNplcCommand<F>
+where
+    D: Device + Nplc<F>,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let nplc: NumericValue<f32> = params.next_data()?;
+        let nplc = nplc.resolve(
+            device.nplc_minimum(),
+            device.nplc_maximum(),
+            device.nplc_default(),
+        )?;
+        device.set_nplc(nplc)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let nplc = match params.next_optional_data()? {
+            Some(NumericValueQuery::Minimum) => device.nplc_minimum(),
+            Some(NumericValueQuery::Maximum) => device.nplc_maximum(),
+            Some(NumericValueQuery::Default) => device.nplc_default(),
+            Some(NumericValueQuery::Step) => return Err(ErrorCode::IllegalParameterValue.into()),
+            None => device.nplc(),
+        };
+        response.data(nplc).finish()
+    }
+}
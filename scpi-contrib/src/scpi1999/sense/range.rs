@@ -0,0 +1,70 @@
+//! ## \<function\>:RANGe Subsystem
+//! Selects the measurement range of a sensor function, Eg. `SENSe:VOLTage:RANGe`.
+//!
+//! `RANGe:AUTO ON|OFF|ONCE` lets the device select the range, see [SensRangeAutoCommand].
+//! The available fixed ranges differ per sensor function and are set with device-specific
+//! `RANGe[:UPPer]` commands.
+
+use core::marker::PhantomData;
+
+use scpi::{cmd_both, error::Result, tree::prelude::*};
+
+use crate::scpi1999::util::Auto;
+
+/// Device with an autorange setting for the sensor function `F`.
+///
+/// `F` is a marker type and allows a device to have one setting per sensor function.
+pub trait RangeAuto<F> {
+    /// Set autorange, called by `RANGe:AUTO`
+    fn set_auto_range(&mut self, auto: Auto);
+
+    /// Current autorange setting.
+    ///
+    /// **Note:** Devices should call [Auto::auto_once] on the setting when autorange has been executed,
+    /// turning [Auto::Once] off.
+    fn auto_range(&self) -> Auto;
+}
+
+/// `[SENSe:]<function>:RANGe:AUTO <Boolean>|ONCE`
+///
+/// Sets automatic range selection, `ONCE` selects a range once and then turns automatic range selection off.
+/// Stores the setting using [RangeAuto::set_auto_range].
+///
+/// `[SENSe:]<function>:RANGe:AUTO?`
+///
+/// Returns the current setting from [RangeAuto::auto_range], Eg. `0` once a requested `ONCE` has been executed.
+pub struct SensRangeAutoCommand<F>(PhantomData<F>);
+
+impl<F> SensRangeAutoCommand<F> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F> Default for SensRangeAutoCommand<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, F> Command<D> for SensRangeAutoCommand<F>
+where
+    D: Device + RangeAuto<F>,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        device.set_auto_range(params.next_data()?);
+        Ok(())
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.auto_range()).finish()
+    }
+}
@@ -0,0 +1,128 @@
+//! # 19 SOURce Subsystem
+//! > The SOURce subsystem is used to control the signal generation of the instrument, Eg. the
+//! > output voltage of a power supply or the frequency of a signal generator.
+//!
+//! Settings are [uom] quantities (see [scpi::units]) which accept suffixes and MINimum|MAXimum|DEFault,
+//! see [SourLevelCommand].
+
+use core::marker::PhantomData;
+
+use scpi::{
+    cmd_both,
+    error::{ErrorCode, Result},
+    tree::prelude::*,
+};
+
+use super::{NumericValue, NumericValueQuery};
+
+/// Device with a source setting of quantity `Q`, Eg. a [scpi::units::ElectricPotential] for `SOURce:VOLTage`.
+pub trait Source<Q> {
+    /// Set the source, the value has been checked to be within [Self::source_minimum] and [Self::source_maximum].
+    fn set_source(&mut self, value: Q) -> Result<()>;
+
+    /// Current source setting
+    fn get_source(&self) -> Q;
+
+    /// Smallest allowed setting, used by `MINimum`
+    fn source_minimum(&self) -> Q;
+
+    /// Largest allowed setting, used by `MAXimum`
+    fn source_maximum(&self) -> Q;
+
+    /// Default setting, used by `DEFault`
+    fn source_default(&self) -> Q;
+
+    /// Step size or resolution of the setting, used by `STEP` queries.
+    ///
+    /// Returns [None] by default in which case a `STEP` query returns a `Illegal parameter value` error.
+    fn source_step(&self) -> Option<Q> {
+        None
+    }
+}
+
+/// `[SOURce:]<quantity> <numeric_value>`
+///
+/// Sets the source after resolving MINimum|MAXimum|DEFault, see [Source::set_source].
+/// Values outside of the allowed range return a `Data out of range` error.
+///
+/// `[SOURce:]<quantity>? [MINimum|MAXimum|DEFault|STEP]`
+///
+/// Returns the current setting, the requested limit or the step size (see [Source::source_step]),
+/// in the base unit of the quantity.
+pub struct SourLevelCommand<Q>(PhantomData<Q>);
+
+impl<Q> SourLevelCommand<Q> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<Q> Default for SourLevelCommand<Q> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, Q> Command<D> for SourLevelCommand<Q>
+where
+    D: Device + Source<Q>,
+    Q: for<'a> TryFrom<Token<'a>, Error = Error> + PartialOrd + ResponseData,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let value: NumericValue<Q> = params.next_data()?;
+        let value = value.resolve(
+            device.source_minimum(),
+            device.source_maximum(),
+            device.source_default(),
+        )?;
+        device.set_source(value)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let value = match params.next_optional_data()? {
+            Some(NumericValueQuery::Minimum) => device.source_minimum(),
+            Some(NumericValueQuery::Maximum) => device.source_maximum(),
+            Some(NumericValueQuery::Default) => device.source_default(),
+            Some(NumericValueQuery::Step) => device
+                .source_step()
+                .ok_or(ErrorCode::IllegalParameterValue)?,
+            None => device.get_source(),
+        };
+        response.data(value).finish()
+    }
+}
+
+/// Create a `SOURce` tree branch with a [SourLevelCommand] leaf for each given mnemonic and quantity.
+///
+/// `SOURce` is a default branch as the keyword is optional. Optional nodes below the quantity
+/// (Eg. `VOLTage[:LEVel][:IMMediate]`) are not included, build the branch manually if needed.
+///
+/// ```ignore
+/// scpi_source!(b"VOLTage": ElectricPotential, b"FREQuency": Frequency)
+/// ```
+#[macro_export]
+macro_rules! scpi_source {
+    ($($name:literal: $quantity:ty),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"SOURce",
+            default: true,
+            sub: &[
+                $(
+                    scpi::tree::prelude::Leaf {
+                        name: $name,
+                        default: false,
+                        handler: &$crate::scpi1999::source::SourLevelCommand::<$quantity>::new(),
+                    }
+                ),*
+            ],
+        }
+    };
+}
@@ -0,0 +1,80 @@
+use super::{
+    operation::{Operation, OperationBits},
+    *,
+};
+
+/// The `STATus:OPERation:INSTrument` register summarizes the OPERation registers of the logical
+/// instruments of a multi-instrument device, Eg. a mainframe with plug-in modules.
+///
+/// Bit `N` (1 through 14) holds the summary of [OperationInstrumentSummary<N>], the register
+/// itself is summarized in [OperationBits::InstrumentSummary].
+pub struct OperationInstrument;
+impl EventRegisterName for OperationInstrument {
+    type BitFlags = ();
+}
+
+impl SummaryRegister<Operation> for OperationInstrument {
+    const SUMMARY_BIT: u16 = OperationBits::InstrumentSummary as u16;
+}
+
+/// The `STATus:OPERation:INSTrument:ISUMmary<N>` register of logical instrument `N`,
+/// summarized in bit `N` of [OperationInstrument].
+///
+/// `N` must be 1 through 14, other values fail to compile when the summary is updated.
+pub struct OperationInstrumentSummary<const N: u16>;
+impl<const N: u16> EventRegisterName for OperationInstrumentSummary<N> {
+    type BitFlags = OperationBits;
+}
+
+impl<const N: u16> SummaryRegister<OperationInstrument> for OperationInstrumentSummary<N> {
+    const SUMMARY_BIT: u16 = {
+        assert!(
+            matches!(N, 1..=14),
+            "logical instrument must be 1 through 14"
+        );
+        N
+    };
+}
+
+///> `STATus:OPERation:INSTrument:ISUMmary<N>:ENABle`
+///> Same as [EnableCommand] but also updates the summary of logical instrument `N`,
+///> see [ScpiDevice::update_instrument_summary].
+pub struct StatOperInstIsumEnableCommand<const N: u16>;
+
+impl<const N: u16> StatOperInstIsumEnableCommand<N> {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<const N: u16> Default for StatOperInstIsumEnableCommand<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, const N: u16> Command<D> for StatOperInstIsumEnableCommand<N>
+where
+    D: ScpiDevice
+        + GetEventRegister<OperationInstrumentSummary<N>>
+        + GetEventRegister<OperationInstrument>,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, context: &mut Context, params: Parameters) -> Result<()> {
+        EnableCommand::<OperationInstrumentSummary<N>>::new().event(device, context, params)?;
+        device.update_instrument_summary::<N>();
+        Ok(())
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        context: &mut Context,
+        params: Parameters,
+        response: ResponseUnit,
+    ) -> Result<()> {
+        EnableCommand::<OperationInstrumentSummary<N>>::new()
+            .query(device, context, params, response)
+    }
+}
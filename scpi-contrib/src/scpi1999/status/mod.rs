@@ -19,8 +19,12 @@ use scpi::{cmd_both, cmd_nquery, cmd_qonly, error::Result, tree::prelude::*};
 
 use core::marker::PhantomData;
 
-use super::{BitFlags, EventRegisterName, GetEventRegister, ScpiDevice};
+use super::{
+    system::error::SystErrNextCommand, BitFlags, EventRegisterName, GetEventRegister, ScpiDevice,
+    SummaryRegister,
+};
 
+pub mod instrument;
 pub mod operation;
 pub mod questionable;
 
@@ -119,7 +123,7 @@ where
 }
 
 ///> `ENABle`
-///Defined the same as STATus:OPERation:ENABle. See Section 20.1.3 for details.
+///> Defined the same as STATus:OPERation:ENABle. See Section 20.1.3 for details.
 pub struct EnableCommand<T>(PhantomData<T>);
 
 impl<T> EnableCommand<T> {
@@ -157,6 +161,48 @@ where
     }
 }
 
+///> `ENABle`
+///> Same as [EnableCommand] but also updates the summary bit of the parent register `P`,
+///> see [ScpiDevice::update_register_summary].
+pub struct SummaryEnableCommand<T, P>(PhantomData<(T, P)>);
+
+impl<T, P> SummaryEnableCommand<T, P> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, P> Default for SummaryEnableCommand<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, T, P> Command<D> for SummaryEnableCommand<T, P>
+where
+    T: SummaryRegister<P>,
+    P: EventRegisterName,
+    D: ScpiDevice + GetEventRegister<T> + GetEventRegister<P>,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, context: &mut Context, params: Parameters) -> Result<()> {
+        EnableCommand::<T>::new().event(device, context, params)?;
+        device.update_register_summary::<T, P>();
+        Ok(())
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        context: &mut Context,
+        params: Parameters,
+        response: ResponseUnit,
+    ) -> Result<()> {
+        EnableCommand::<T>::new().query(device, context, params, response)
+    }
+}
+
 ///> `NTRansition`
 ///> Defined the same as STATus:OPERation:NTRansition. See Section 20.1.6 for details.
 pub struct NTransitionCommand<T>(PhantomData<T>);
@@ -239,6 +285,43 @@ where
     }
 }
 
+///## 20.4.4 \[:NEXT\]?
+///> `STATus:QUEue:NEXT?`
+///> Returns the next item from the status queue, defined the same as SYSTem:ERRor:NEXT?.
+///
+/// The status queue is the error/event queue, see [SystErrNextCommand].
+pub type StatQueNextCommand = SystErrNextCommand;
+
+///## 20.4.2 :ENABle
+///> `STATus:QUEue:ENABle`
+///> Enables events to be reported in the status queue.
+///
+/// This implementation accepts a `<NRf> | <non-decimal numeric>` mask of the event classes
+/// (ESR bits, Eg. `#H3C` for all errors) which enter the queue instead of a list of event numbers,
+/// see [ScpiDevice::queue_enable].
+pub struct StatQueEnableCommand;
+
+impl<D> Command<D> for StatQueEnableCommand
+where
+    D: ScpiDevice,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        device.set_queue_enable(params.next_data()?)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.queue_enable()).finish()
+    }
+}
+
 /// Create command nodes for a SCPI registers like `OPERation`, `QUEStionable`, or custom event registers.
 #[macro_export]
 macro_rules! scpi_register {
@@ -283,25 +366,175 @@ macro_rules! scpi_register {
     };
 }
 
+/// Create command nodes for a SCPI register summarized in a bit of a parent register,
+/// Eg. `INSTrument` in `QUEStionable`. See [crate::scpi1999::SummaryRegister].
+///
+/// Same as [crate::scpi_register] but `ENABle` also updates the summary bit of the parent.
+/// The device must call [crate::scpi1999::ScpiDevice::update_register_summary] when the condition changes.
+#[macro_export]
+macro_rules! scpi_summary_register {
+    ($name:literal, $register:path => $parent:path) => {
+        $crate::scpi_summary_register!($name, $register => $parent; )
+    };
+    ($name:literal, $register:path => $parent:path; $($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: $name,
+            default: false,
+            sub: &[
+                scpi::tree::prelude::Leaf {
+                    name: b"EVENt",
+                    default: true,
+                    handler: &$crate::scpi1999::status::EventCommand::<$register>::new(),
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"CONDition",
+                    default: false,
+                    handler: &$crate::scpi1999::status::ConditionCommand::<$register>::new(),
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"ENABle",
+                    default: false,
+                    handler: &$crate::scpi1999::status::SummaryEnableCommand::<$register, $parent>::new(),
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"NTRansition",
+                    default: false,
+                    handler: &$crate::scpi1999::status::NTransitionCommand::<$register>::new(),
+                },
+                scpi::tree::prelude::Leaf {
+                    name: b"PTRansition",
+                    default: false,
+                    handler: &$crate::scpi1999::status::PTransitionCommand::<$register>::new(),
+                },
+                $(
+                    $node
+                ),*
+            ],
+        }
+    };
+}
+
+/// Create a `STATus:OPERation:INSTrument` register with a `ISUMmary<N>` register for each logical
+/// instrument `N`, see [crate::scpi1999::status::instrument].
+///
+/// The device must call [crate::scpi1999::ScpiDevice::update_instrument_summary] when the condition
+/// of a logical instrument changes.
+/// ```ignore
+/// scpi_operation_instrument!(b"ISUMmary1" => 1, b"ISUMmary2" => 2)
+/// ```
+#[macro_export]
+macro_rules! scpi_operation_instrument {
+    ($($name:literal => $n:literal),*) => {
+        $crate::scpi_summary_register!(
+            b"INSTrument",
+            $crate::scpi1999::status::instrument::OperationInstrument => $crate::scpi1999::status::operation::Operation;
+            $(
+                scpi::tree::prelude::Branch {
+                    name: $name,
+                    default: false,
+                    sub: &[
+                        scpi::tree::prelude::Leaf {
+                            name: b"EVENt",
+                            default: true,
+                            handler: &$crate::scpi1999::status::EventCommand::<$crate::scpi1999::status::instrument::OperationInstrumentSummary<$n>>::new(),
+                        },
+                        scpi::tree::prelude::Leaf {
+                            name: b"CONDition",
+                            default: false,
+                            handler: &$crate::scpi1999::status::ConditionCommand::<$crate::scpi1999::status::instrument::OperationInstrumentSummary<$n>>::new(),
+                        },
+                        scpi::tree::prelude::Leaf {
+                            name: b"ENABle",
+                            default: false,
+                            handler: &$crate::scpi1999::status::instrument::StatOperInstIsumEnableCommand::<$n>::new(),
+                        },
+                        scpi::tree::prelude::Leaf {
+                            name: b"NTRansition",
+                            default: false,
+                            handler: &$crate::scpi1999::status::NTransitionCommand::<$crate::scpi1999::status::instrument::OperationInstrumentSummary<$n>>::new(),
+                        },
+                        scpi::tree::prelude::Leaf {
+                            name: b"PTRansition",
+                            default: false,
+                            handler: &$crate::scpi1999::status::PTransitionCommand::<$crate::scpi1999::status::instrument::OperationInstrumentSummary<$n>>::new(),
+                        },
+                    ],
+                }
+            ),*
+        )
+    };
+}
+
 /// Create a `STATus:` tree branch with mandatory commands.
+///
+/// Use `scpi_status!(instruments: [b"ISUMmary1" => 1, ...]; <nodes>)` to also create a
+/// `STATus:OPERation:INSTrument` register, see [crate::scpi_operation_instrument].
+///
+/// `STATus:QUEue:ENABle` is only added with `scpi_status!(queue_enable; <nodes>)` or
+/// `scpi_status!(queue_enable, instruments: [...]; <nodes>)` as the device must store the mask,
+/// see [ScpiDevice::set_queue_enable](crate::scpi1999::ScpiDevice::set_queue_enable).
 #[macro_export]
 macro_rules! scpi_status {
-    ($($node:expr),*) => {
+    (@branch [$($queue:expr),*] $operation:expr $(, $node:expr)*) => {
         scpi::tree::prelude::Branch {
             name: b"STATus",
             default: false,
             sub: &[
-                $crate::scpi_register!(b"OPERation", $crate::scpi1999::status::operation::Operation),
+                $operation,
                 $crate::scpi_register!(b"QUEStionable", $crate::scpi1999::status::questionable::Questionable),
                 scpi::tree::prelude::Leaf {
                     name: b"PRESet",
                     default: false,
                     handler: &$crate::scpi1999::status::StatPresetCommand,
                 },
+                scpi::tree::prelude::Branch {
+                    name: b"QUEue",
+                    default: false,
+                    sub: &[
+                        scpi::tree::prelude::Leaf {
+                            name: b"NEXT",
+                            default: true,
+                            handler: &$crate::scpi1999::system::error::SystErrNextCommand,
+                        },
+                        $(
+                            $queue
+                        ),*
+                    ],
+                },
                 $(
                     $node
                 ),*
             ],
         }
     };
+    (@operation) => {
+        $crate::scpi_register!(b"OPERation", $crate::scpi1999::status::operation::Operation)
+    };
+    (@operation [$($name:literal => $n:literal),*]) => {
+        $crate::scpi_register!(b"OPERation", $crate::scpi1999::status::operation::Operation;
+            $crate::scpi_operation_instrument!($($name => $n),*)
+        )
+    };
+    (queue_enable $(, instruments: [$($name:literal => $n:literal),*])? $(; $($node:expr),*)?) => {
+        $crate::scpi_status!(@branch
+            [
+                scpi::tree::prelude::Leaf {
+                    name: b"ENABle",
+                    default: false,
+                    handler: &$crate::scpi1999::status::StatQueEnableCommand,
+                }
+            ]
+            $crate::scpi_status!(@operation $([$($name => $n),*])?)
+            $($(, $node)*)?
+        )
+    };
+    (instruments: [$($name:literal => $n:literal),*] $(; $($node:expr),*)?) => {
+        $crate::scpi_status!(@branch []
+            $crate::scpi_status!(@operation [$($name => $n),*])
+            $($(, $node)*)?
+        )
+    };
+    ($($node:expr),*) => {
+        $crate::scpi_status!(@branch [] $crate::scpi_status!(@operation) $(, $node)*)
+    };
 }
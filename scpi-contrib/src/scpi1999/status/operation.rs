@@ -7,41 +7,65 @@ impl EventRegisterName for Operation {
 
 /// The OPERation status register contains conditions which are part of the instrument’s normal
 /// operation.
+///
+/// ```
+/// # use scpi_contrib::scpi1999::{EventRegister, status::operation::OperationBits};
+/// let mut reg = EventRegister::new();
+/// reg.set_condition_flag(OperationBits::Measuring, true);
+/// assert_eq!(reg.condition, 1 << 4);
+/// assert!(reg.get_condition_flag(OperationBits::Measuring));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scpi_derive::ScpiEnum)]
 pub enum OperationBits {
     /// The instrument is currently performing a calibration.
+    #[scpi(mnemonic = b"CALibrating")]
     Calibrating = 0,
     /// The instrument is waiting for signals it controls to stabilize
     /// enough to begin measurements.
+    #[scpi(mnemonic = b"SETTling")]
     Settling = 1,
     /// The instrument is currently changing its range.
+    #[scpi(mnemonic = b"RANGing")]
     Ranging = 2,
     /// A sweep is in progress.
+    #[scpi(mnemonic = b"SWEeping")]
     Sweeping = 3,
     /// The instrument is actively measuring.
+    #[scpi(mnemonic = b"MEASuring")]
     Measuring = 4,
     /// The instrument is in a “wait for trigger” state of the
     /// trigger model.
+    #[scpi(mnemonic = b"WTRigger")]
     WaitingForTrig = 5,
     /// The instrument is in a “wait for arm” state of the trigger
     /// model.
+    #[scpi(mnemonic = b"WARM")]
     WaitingForArm = 6,
     /// The instrument is currently performing a correction.
+    #[scpi(mnemonic = b"CORRecting")]
     Correcting = 7,
     /// Available to designer.
+    #[scpi(mnemonic = b"DESigner1")]
     Designer1 = 8,
     /// Available to designer.
+    #[scpi(mnemonic = b"DESigner2")]
     Designer2 = 9,
     /// Available to designer.
+    #[scpi(mnemonic = b"DESigner3")]
     Designer3 = 10,
     /// Available to designer.
+    #[scpi(mnemonic = b"DESigner4")]
     Designer4 = 11,
     /// Available to designer.
+    #[scpi(mnemonic = b"DESigner5")]
     Designer5 = 12,
     /// One of n multiple logical instruments is
     /// reporting OPERational status.
+    #[scpi(mnemonic = b"INSTrument")]
     InstrumentSummary = 13,
     /// A user-defined programming is currently in the run
     /// state.
+    #[scpi(mnemonic = b"PROGram")]
     ProgramRunning = 14,
 }
 
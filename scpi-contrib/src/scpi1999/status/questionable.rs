@@ -7,21 +7,36 @@ impl EventRegisterName for Questionable {
 
 /// The QUEStionable status register set contains bits which give an indication of the quality of
 /// various aspects of the signal.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scpi_derive::ScpiEnum)]
 pub enum QuestionableBits {
     /// Indicates that the data is currently being acquired or generated
+    #[scpi(mnemonic = b"VOLTage")]
     SummaryVoltage = 0,
+    #[scpi(mnemonic = b"CURRent")]
     SummaryCurrent = 1,
+    #[scpi(mnemonic = b"TIME")]
     SummaryTime = 2,
+    #[scpi(mnemonic = b"POWer")]
     SummaryPower = 3,
+    #[scpi(mnemonic = b"TEMPerature")]
     SummaryTemperature = 4,
+    #[scpi(mnemonic = b"FREQuency")]
     SummaryFrequency = 5,
+    #[scpi(mnemonic = b"PHASe")]
     SummaryPhase = 6,
+    #[scpi(mnemonic = b"MODulation")]
     SummaryModulation = 7,
+    #[scpi(mnemonic = b"CALibration")]
     SummaryCalibration = 8,
+    #[scpi(mnemonic = b"DESigner1")]
     Designer1 = 9,
+    #[scpi(mnemonic = b"DESigner2")]
     Designer2 = 10,
+    #[scpi(mnemonic = b"DESigner3")]
     Designer3 = 11,
+    #[scpi(mnemonic = b"DESigner4")]
     Designer4 = 12,
+    #[scpi(mnemonic = b"INSTrument")]
     InstrumentSummary = 13,
     /// Bit 14 is defined as the Command Warning bit. This bit indicates a non-fatal warning that
     /// relates to the instrument’s interpretation of a command, query, or one or more parameters of
@@ -31,6 +46,7 @@ pub enum QuestionableBits {
     /// For example, the Command Warning bit is set whenever a parameter in one of the
     /// Measurement Instruction commands or queries is ignored during execution. Such a
     /// parameter may be ignored because it cannot be specified by a particular instrument.
+    #[scpi(mnemonic = b"WARNing")]
     CommandWarning = 14,
 }
 
@@ -56,7 +72,7 @@ pub type StatQuesConditionCommand = ConditionCommand<Questionable>;
 
 ///# 20.3.3 :ENABle \<NRf\> | \<non-decimal numeric\>
 ///> `STATus:QUEStionable:ENABle`
-///Defined the same as STATus:OPERation:ENABle. See Section 20.1.3 for details.
+///> Defined the same as STATus:OPERation:ENABle. See Section 20.1.3 for details.
 pub type StatQuesEnableCommand = EnableCommand<Questionable>;
 
 ///# 20.3.6 :NTRansition \<NRf\> | \<non-decimal numeric\>
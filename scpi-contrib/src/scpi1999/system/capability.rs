@@ -0,0 +1,58 @@
+//! ## 21.2 :CAPability?
+//! > `SYSTem:CAPability?` query returns an `<instrument_specifier>` describing the instrument
+//! > classes the instrument complies with.
+
+use scpi::{cmd_qonly, error::Result, tree::prelude::*};
+
+use crate::scpi1999::ScpiDevice;
+
+/// Separator between the specifiers of each class in the capability string
+pub const CAPABILITY_SEPARATOR: &[u8] = b"\\";
+
+/// Device advertising the instrument classes it complies with.
+pub trait Capability {
+    /// Return the `<instrument_specifier>` of each advertised class,
+    /// see [crate::classes::InstrumentClass::instrument_specifier].
+    fn capabilities(&self) -> &[&'static [u8]];
+}
+
+/// Quoted string of specifiers joined by [CAPABILITY_SEPARATOR]
+struct CapabilityString<'a>(&'a [&'static [u8]]);
+
+impl ResponseData for CapabilityString<'_> {
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        formatter.push_byte(b'"')?;
+        for (i, specifier) in self.0.iter().enumerate() {
+            if i > 0 {
+                formatter.push_ascii(CAPABILITY_SEPARATOR)?;
+            }
+            formatter.push_ascii_escaped(specifier)?;
+        }
+        formatter.push_byte(b'"')
+    }
+}
+
+/// `SYSTem:CAPability?`
+///
+/// Returns the specifiers of [Capability::capabilities] as a single quoted string,
+/// Eg. `"DIGITIZER\SWITCH"`. No advertised classes returns an empty string (`""`).
+pub struct SystCapabilityCommand;
+
+impl<D> Command<D> for SystCapabilityCommand
+where
+    D: ScpiDevice + Capability,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response
+            .data(CapabilityString(device.capabilities()))
+            .finish()
+    }
+}
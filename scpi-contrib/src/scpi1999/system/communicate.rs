@@ -0,0 +1,231 @@
+//! ## 21.6 :COMMunicate
+//! > The COMMunicate subsystem collects together the configuration of the control/communication
+//! > interfaces.
+//!
+//! Each supported interface is a separate device trait, use [scpi_communicate!](crate::scpi_communicate)
+//! to create the branch with the interfaces a device supports.
+
+use scpi::{cmd_both, error::Result, tree::prelude::*};
+
+use crate::scpi1999::NumericValue;
+
+/// Device with a GPIB interface
+pub trait GpibCommunicate {
+    /// Current primary address
+    fn gpib_address(&self) -> u8;
+
+    /// Set primary address, `0..=30`
+    fn set_gpib_address(&mut self, addr: u8) -> Result<()>;
+}
+
+/// Device with a serial interface
+pub trait SerialCommunicate {
+    /// Current baud rate
+    fn serial_baud(&self) -> u32;
+
+    /// Set baud rate.
+    ///
+    /// Should return a [ErrorCode::DataOutOfRange] or [ErrorCode::IllegalParameterValue] error for unsupported rates.
+    fn set_serial_baud(&mut self, baud: u32) -> Result<()>;
+}
+
+/// Device with a LAN interface
+pub trait LanCommunicate {
+    /// Current IPv4 address
+    fn lan_address(&self) -> [u8; 4];
+
+    /// Set IPv4 address
+    fn set_lan_address(&mut self, addr: [u8; 4]) -> Result<()>;
+}
+
+/// Highest GPIB primary address
+const GPIB_ADDRESS_MAX: u8 = 30;
+
+/// `SYSTem:COMMunicate:GPIB[:SELF]:ADDRess <numeric_value>`
+///
+/// Sets the GPIB primary address, see [GpibCommunicate::set_gpib_address].
+/// Accepts `0..=30` as well as `MINimum`/`MAXimum`.
+///
+/// `SYSTem:COMMunicate:GPIB[:SELF]:ADDRess?`
+///
+/// Returns the current GPIB primary address.
+pub struct SystCommGpibAddressCommand;
+
+impl<D> Command<D> for SystCommGpibAddressCommand
+where
+    D: Device + GpibCommunicate,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let addr: NumericValue<u8> = params.next_data()?;
+        device.set_gpib_address(addr.finish_with(GPIB_ADDRESS_MAX, 0)?)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.gpib_address()).finish()
+    }
+}
+
+/// `SYSTem:COMMunicate:SERial[:RECeive]:BAUD <numeric_value>`
+///
+/// Sets the baud rate, see [SerialCommunicate::set_serial_baud].
+///
+/// `SYSTem:COMMunicate:SERial[:RECeive]:BAUD?`
+///
+/// Returns the current baud rate.
+pub struct SystCommSerBaudCommand;
+
+impl<D> Command<D> for SystCommSerBaudCommand
+where
+    D: Device + SerialCommunicate,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let baud: u32 = params.next_data()?;
+        device.set_serial_baud(baud)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.serial_baud()).finish()
+    }
+}
+
+/// Parse a dotted IPv4 address, Eg. `192.168.0.10`
+fn parse_ipv4(s: &[u8]) -> Option<[u8; 4]> {
+    let mut addr = [0u8; 4];
+    let mut octets = s.split(|c| *c == b'.');
+    for octet in addr.iter_mut() {
+        let digits = octets.next()?;
+        if digits.is_empty() || digits.len() > 3 || !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let value = digits
+            .iter()
+            .fold(0u16, |acc, d| acc * 10 + (*d - b'0') as u16);
+        *octet = value.try_into().ok()?;
+    }
+    octets.next().is_none().then_some(addr)
+}
+
+/// IPv4 address formatted as a quoted string, Eg. `"192.168.0.10"`
+struct Ipv4String([u8; 4]);
+
+impl ResponseData for Ipv4String {
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        formatter.push_byte(b'"')?;
+        for (i, octet) in self.0.iter().enumerate() {
+            if i > 0 {
+                formatter.push_byte(b'.')?;
+            }
+            octet.format_response_data(formatter)?;
+        }
+        formatter.push_byte(b'"')
+    }
+}
+
+/// `SYSTem:COMMunicate:LAN:ADDRess <string>`
+///
+/// Sets the IPv4 address given as a dotted string, Eg. `"192.168.0.10"`,
+/// see [LanCommunicate::set_lan_address]. Malformed addresses returns a [ErrorCode::IllegalParameterValue] error.
+///
+/// `SYSTem:COMMunicate:LAN:ADDRess?`
+///
+/// Returns the current IPv4 address as a dotted string.
+pub struct SystCommLanAddressCommand;
+
+impl<D> Command<D> for SystCommLanAddressCommand
+where
+    D: Device + LanCommunicate,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let s: &[u8] = params.next_data()?;
+        let addr = parse_ipv4(s).ok_or(ErrorCode::IllegalParameterValue)?;
+        device.set_lan_address(addr)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(Ipv4String(device.lan_address())).finish()
+    }
+}
+
+/// Create a `COMMunicate` tree branch with the given interfaces, to be placed in the `SYSTem` branch.
+///
+/// Supported interfaces are `gpib` ([GpibCommunicate]), `serial` ([SerialCommunicate]), and `lan` ([LanCommunicate]),
+/// Eg. `scpi_system!(scpi_communicate!(gpib, lan))`.
+#[macro_export]
+macro_rules! scpi_communicate {
+    (@gpib) => {
+        scpi::tree::prelude::Branch {
+            name: b"GPIB",
+            default: false,
+            sub: &[scpi::tree::prelude::Branch {
+                name: b"SELF",
+                default: true,
+                sub: &[scpi::tree::prelude::Leaf {
+                    name: b"ADDRess",
+                    default: false,
+                    handler: &$crate::scpi1999::system::communicate::SystCommGpibAddressCommand,
+                }],
+            }],
+        }
+    };
+    (@serial) => {
+        scpi::tree::prelude::Branch {
+            name: b"SERial",
+            default: false,
+            sub: &[scpi::tree::prelude::Branch {
+                name: b"RECeive",
+                default: true,
+                sub: &[scpi::tree::prelude::Leaf {
+                    name: b"BAUD",
+                    default: false,
+                    handler: &$crate::scpi1999::system::communicate::SystCommSerBaudCommand,
+                }],
+            }],
+        }
+    };
+    (@lan) => {
+        scpi::tree::prelude::Branch {
+            name: b"LAN",
+            default: false,
+            sub: &[scpi::tree::prelude::Leaf {
+                name: b"ADDRess",
+                default: false,
+                handler: &$crate::scpi1999::system::communicate::SystCommLanAddressCommand,
+            }],
+        }
+    };
+    ($($interface:ident),+) => {
+        scpi::tree::prelude::Branch {
+            name: b"COMMunicate",
+            default: false,
+            sub: &[
+                $(
+                    $crate::scpi_communicate!(@$interface)
+                ),+
+            ],
+        }
+    };
+}
@@ -2,10 +2,67 @@ use scpi::{cmd_qonly, error::Result, tree::prelude::*};
 
 use super::ScpiDevice;
 
+/// Runtime extended message of a queued error
+#[cfg(feature = "alloc")]
+type Message = alloc::vec::Vec<u8>;
+#[cfg(not(feature = "alloc"))]
+type Message = &'static [u8];
+
+/// Get the next error and its runtime extended message (if supported)
+fn pop_error<D: ScpiDevice>(device: &mut D) -> Option<(Error, Option<Message>)> {
+    #[cfg(feature = "alloc")]
+    {
+        device.pop_front_error_message()
+    }
+    #[cfg(not(feature = "alloc"))]
+    {
+        device.pop_front_error().map(|err| (err, None))
+    }
+}
+
+/// Error queue item with a device identifier prepended to the extended message,
+/// see [ScpiDevice::error_extended_prefix].
+///
+/// A runtime message replaces the extended message of the error.
+struct PrefixedError(Error, Option<&'static [u8]>, Option<Message>);
+
+impl ResponseData for PrefixedError {
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        let PrefixedError(err, prefix, msg) = self;
+        let msg = msg.as_deref();
+        let prefix = prefix.filter(|_| err.get_code() != 0);
+        let ext = msg.or(err.get_extended());
+        if prefix.is_none() && msg.is_none() {
+            return err.format_response_data(formatter);
+        }
+        err.get_code().format_response_data(formatter)?;
+        formatter.data_separator()?;
+        formatter.push_byte(b'"')?;
+        formatter.push_ascii_escaped(err.get_message())?;
+        if prefix.is_some() || ext.is_some() {
+            formatter.push_byte(b';')?;
+        }
+        if let Some(prefix) = prefix {
+            formatter.push_ascii_escaped(prefix)?;
+            if ext.is_some() {
+                formatter.push_str(b": ")?;
+            }
+        }
+        if let Some(ext) = ext {
+            formatter.push_ascii_escaped(ext)?;
+        }
+        formatter.push_byte(b'"')
+    }
+}
+
 ///## 21.8.8 \[NEXT\]?
 ///> `SYSTem:ERRor:NEXT?` queries the error/event queue for the next item and removes it
 ///> from the queue. The response returns the full queue item consisting of an integer and a string
 ///> as described in the introduction to the SYSTem:ERRor subsystem.
+///
+/// Extended error information is returned after a semicolon in the string, Eg. `-100,"Command error;<extended>"`.
+/// With the `alloc` feature runtime messages (see [ScpiDevice::push_error_message]) are returned in the same way.
+/// See [ScpiDevice::error_extended_prefix] to also identify the source of the error.
 pub struct SystErrNextCommand;
 
 impl<D> Command<D> for SystErrNextCommand
@@ -22,8 +79,9 @@ where
         mut response: ResponseUnit,
     ) -> Result<()> {
         //Always return first error (NoError if empty)
+        let (err, msg) = pop_error(device).unwrap_or_default();
         response
-            .data(device.pop_front_error().unwrap_or_default())
+            .data(PrefixedError(err, device.error_extended_prefix(), msg))
             .finish()
     }
 }
@@ -58,6 +116,10 @@ where
 ///> `SYSTem:ERRor:ALL?` queries the error/event queue for all the unread items and
 ///> removes them from the queue. The response returns a comma separated list of only the
 ///> error/event code numbers in FIFO order. If the queue is empty, the response is 0.
+///
+/// This implementation returns the full queue items (as [SystErrNextCommand]) in FIFO order,
+/// Eg. `-100,"Command error",-200,"Execution error"`, leaving the queue empty.
+/// An empty queue returns `0,"No error"`.
 pub struct SystErrAllCommand;
 
 impl<D> Command<D> for SystErrAllCommand
@@ -73,12 +135,12 @@ where
         _params: Parameters,
         mut response: ResponseUnit,
     ) -> Result<()> {
-        //Always return first error (NoError if empty)
+        //Return NoError only if already empty
         if device.is_empty() {
             response.data(Error::new(ErrorCode::NoError)).finish()
         } else {
-            while let Some(err) = device.pop_front_error() {
-                response.data(err);
+            while let Some((err, msg)) = pop_error(device) {
+                response.data(PrefixedError(err, device.error_extended_prefix(), msg));
             }
             response.finish()
         }
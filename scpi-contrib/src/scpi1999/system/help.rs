@@ -0,0 +1,60 @@
+//! ## SYSTem:HELP
+//! > `SYSTem:HELP:HEADers?` returns a list of all the command and query headers the instrument
+//! > implements.
+
+use alloc::vec::Vec;
+
+use scpi::{cmd_qonly, error::Result, tree::prelude::*};
+
+/// Device which knows its own command tree
+pub trait HelpHeaders: Device + Sized + 'static {
+    /// Return the command tree the device is executed by.
+    fn command_tree(&self) -> &'static Node<'static, Self>;
+}
+
+/// `SYSTem:HELP:HEADers?`
+///
+/// Returns a definite length arbitrary block with the full long form header of every command in
+/// [HelpHeaders::command_tree], one per line. Query capable commands are listed with a trailing `?`,
+/// commands accepting both forms are listed twice. Eg.
+/// ```text
+/// *IDN?
+/// :SYSTem:ERRor:NEXT?
+/// :SYSTem:SET
+/// ```
+pub struct SystHelpHeadersCommand;
+
+impl<D> Command<D> for SystHelpHeadersCommand
+where
+    D: HelpHeaders,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let mut headers = Vec::new();
+        device.command_tree().for_each_header(|path, leaf| {
+            let mut header = Vec::new();
+            for mnemonic in path {
+                if !mnemonic.starts_with(b"*") {
+                    header.push(b':');
+                }
+                header.extend_from_slice(mnemonic);
+            }
+            if leaf.is_event_capable() {
+                headers.extend_from_slice(&header);
+                headers.push(b'\n');
+            }
+            if leaf.is_query_capable() {
+                headers.extend_from_slice(&header);
+                headers.extend_from_slice(b"?\n");
+            }
+        });
+        response.data(Arbitrary(&headers)).finish()
+    }
+}
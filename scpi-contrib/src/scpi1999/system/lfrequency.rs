@@ -0,0 +1,62 @@
+//! ## 21.9 :LFRequency
+//! > `SYSTem:LFRequency <numeric_value>` sets the power line frequency of the instrument, used by
+//! > instruments which synchronize measurements or filtering to the mains frequency.
+
+use scpi::{
+    cmd_both,
+    error::Result,
+    tree::prelude::*,
+    units::{
+        uom::si::{frequency::hertz, time::second},
+        Frequency, Time,
+    },
+};
+
+/// Device with a configurable power line frequency
+pub trait LineFrequency {
+    /// Set the power line frequency.
+    ///
+    /// Return [ErrorCode::IllegalParameterValue] if the frequency is not supported,
+    /// Eg. anything but 50 Hz and 60 Hz.
+    fn set_line_frequency(&mut self, frequency: Frequency) -> Result<()>;
+
+    /// Current power line frequency
+    fn line_frequency(&self) -> Frequency;
+
+    /// Duration of `cycles` power line cycles at the current line frequency,
+    /// Eg. the integration time of [crate::scpi1999::sense::nplc::Nplc::nplc].
+    fn line_cycles(&self, cycles: f32) -> Time {
+        Time::new::<second>(cycles / self.line_frequency().get::<hertz>())
+    }
+}
+
+/// `SYSTem:LFRequency <numeric_value>`
+///
+/// Sets the power line frequency, a value without suffix is in Hz. See [LineFrequency::set_line_frequency].
+///
+/// `SYSTem:LFRequency?`
+///
+/// Returns the current power line frequency in Hz.
+pub struct SystLfrequencyCommand;
+
+impl<D> Command<D> for SystLfrequencyCommand
+where
+    D: Device + LineFrequency,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let frequency: Frequency = params.next_data()?;
+        device.set_line_frequency(frequency)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.line_frequency()).finish()
+    }
+}
@@ -3,18 +3,22 @@
 //! performance. Examples include functions for performing general housekeeping and
 //! functions related to setting global configurations, such as TIME or SECurity
 
-use scpi::{cmd_qonly, error::Result, tree::prelude::*};
+use scpi::{cmd_nquery, cmd_qonly, error::Result, tree::prelude::*};
 
 use super::ScpiDevice;
+use crate::ieee488::lrn::CommonLrn;
 
-//#[cfg(feature = "unproven")]
-// LFRequency requires frequency units
-//pub mod lfrequency;
+#[cfg(feature = "unproven")]
+pub mod lfrequency;
 
-//#[cfg(feature = "unproven")]
-//pub mod capability;
+#[cfg(feature = "unproven")]
+pub mod capability;
+#[cfg(feature = "unproven")]
+pub mod communicate;
 
 pub mod error;
+#[cfg(feature = "alloc")]
+pub mod help;
 
 ///## 21.21 :VERSion?
 ///> `SYSTem:VERSion?` query returns an <NR2> formatted numeric value corresponding to the SCPI version
@@ -57,6 +61,45 @@ where
     }
 }
 
+/// Same as [SystVersionCommand] but returns the version given by [ScpiDevice::scpi_version].
+pub struct SystVersionDeviceCommand;
+
+impl<D> Command<D> for SystVersionDeviceCommand
+where
+    D: ScpiDevice,
+{
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let (year, rev) = device.scpi_version();
+        response.data(&SystVersionCommand::new(year, rev)).finish()
+    }
+}
+
+///## :SET
+/// `SYSTem:SET <block>` restores a device setup previously returned as an arbitrary block by `*LRN?`.
+///
+/// Calls [CommonLrn::apply_set] with the block payload.
+pub struct SystSetCommand;
+
+impl<D> Command<D> for SystSetCommand
+where
+    D: ScpiDevice + CommonLrn,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let Arbitrary(data) = params.next_data()?;
+        device.apply_set(data)
+    }
+}
+
 /// Create a `SYSTem:` tree branch with mandatory commands.
 #[macro_export]
 macro_rules! scpi_system {
@@ -0,0 +1,101 @@
+//! # 10 INITiate Subsystem
+//! > The INITiate command subsystem controls the initiation of the trigger system.
+//!
+//! The trigger system is either idle or initiated, an initiated trigger system returns to idle
+//! when the sequence completes unless continuous initiation is on (see [Continuous]).
+
+use scpi::{cmd_both, cmd_nquery, error::Result, tree::prelude::*};
+
+/// Device with a trigger system
+pub trait Initiate {
+    /// Initiate the trigger system, leaving the idle state.
+    fn initiate(&mut self) -> Result<()>;
+
+    /// Abort any action in progress and return the trigger system to the idle state.
+    fn abort(&mut self) -> Result<()>;
+
+    /// Returns true if continuous initiation is on.
+    ///
+    /// Default implementation returns false, devices implementing [Continuous] must override this.
+    fn continuous(&self) -> bool {
+        false
+    }
+}
+
+/// Device with continuous initiation of the trigger system
+pub trait Continuous: Initiate {
+    /// Turn continuous initiation on or off.
+    ///
+    /// Turning it on should also initiate an idle trigger system.
+    ///
+    /// The current setting is returned by [Initiate::continuous].
+    fn set_continuous(&mut self, on: bool) -> Result<()>;
+}
+
+///## 10.2 \[:IMMediate\]
+///> `INITiate[:IMMediate]` causes the trigger system to leave the idle state, see [Initiate::initiate].
+pub struct InitImmCommand;
+
+impl<D> Command<D> for InitImmCommand
+where
+    D: Device + Initiate,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, _params: Parameters) -> Result<()> {
+        device.initiate()
+    }
+}
+
+///## 10.1 :CONTinuous \<Boolean\>
+///> `INITiate:CONTinuous ON|OFF` selects whether the trigger system is continuously initiated,
+///> see [Continuous::set_continuous].
+///
+/// `INITiate:CONTinuous?`
+///
+/// Returns `1` if continuous initiation is on, otherwise `0`.
+pub struct InitContinuousCommand;
+
+impl<D> Command<D> for InitContinuousCommand
+where
+    D: Device + Continuous,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let on: bool = params.next_data()?;
+        device.set_continuous(on)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.continuous()).finish()
+    }
+}
+
+///## 3 ABORt
+///> `ABORt` resets the trigger system and places all trigger sequences in the idle state.
+///> If INITiate:CONTinuous is ON, the trigger system shall immediately be initiated again.
+///
+/// Calls [Initiate::abort] and then [Initiate::initiate] if [Initiate::continuous] is on.
+pub struct AbortCommand;
+
+impl<D> Command<D> for AbortCommand
+where
+    D: Device + Initiate,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, _params: Parameters) -> Result<()> {
+        device.abort()?;
+        if device.continuous() {
+            device.initiate()?;
+        }
+        Ok(())
+    }
+}
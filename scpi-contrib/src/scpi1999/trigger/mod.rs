@@ -0,0 +1,8 @@
+//! # 24 TRIGger Subsystem
+//! > The TRIGger subsystem is used to synchronize device action(s) with events.
+//!
+//! The `INITiate` and `ABORt` commands controlling the trigger system state are implemented in
+//! [initiate], the trigger source and bus triggers in [sequence].
+
+pub mod initiate;
+pub mod sequence;
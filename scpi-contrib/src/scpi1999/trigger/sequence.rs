@@ -0,0 +1,132 @@
+//! # 24 TRIGger\[:SEQuence\] Subsystem
+//! > The TRIGger subsystem is used to synchronize device action(s) with events.
+//!
+//! Only the trigger source is currently configurable, see [TrigSeqSourceCommand]. A bus trigger
+//! (`*TRG` with [TrgBusCommand], or GET) should call [Trigger::bus_trigger] which only triggers
+//! the device when the source is [TriggerSource::Bus].
+
+use scpi::{
+    cmd_both, cmd_nquery,
+    error::{ErrorCode, Result},
+    tree::prelude::*,
+};
+
+/// `<source>` parameter of `TRIGger[:SEQuence]:SOURce`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scpi_derive::ScpiEnum)]
+pub enum TriggerSource {
+    /// Trigger immediately, without waiting for an event
+    #[scpi(mnemonic = b"IMMediate")]
+    Immediate,
+    /// Trigger on `*TRG` or the IEEE 488.1 GET message
+    #[scpi(mnemonic = b"BUS")]
+    Bus,
+    /// Trigger on an external signal
+    #[scpi(mnemonic = b"EXTernal")]
+    External,
+    /// Trigger on an internal timer
+    #[scpi(mnemonic = b"TIMer")]
+    Timer,
+}
+
+/// Device with a configurable trigger source
+pub trait Trigger {
+    /// Set the trigger source.
+    ///
+    /// Return [ErrorCode::IllegalParameterValue] if the source is not supported by the device.
+    fn set_trigger_source(&mut self, source: TriggerSource) -> Result<()>;
+
+    /// Returns the current trigger source
+    fn trigger_source(&self) -> TriggerSource;
+
+    /// Trigger the device, advancing the trigger system state.
+    fn trigger(&mut self) -> Result<()>;
+
+    /// Called on a bus trigger (`*TRG` or GET).
+    ///
+    /// Calls [Trigger::trigger] if the trigger source is [TriggerSource::Bus], otherwise
+    /// returns [ErrorCode::TriggerIgnored].
+    fn bus_trigger(&mut self) -> Result<()> {
+        if self.trigger_source() == TriggerSource::Bus {
+            self.trigger()
+        } else {
+            Err(ErrorCode::TriggerIgnored.into())
+        }
+    }
+}
+
+///## 10.37 *TRG, Trigger Command
+///> The Trigger command is the device-specific analog of the IEEE 488.1 defined Group Execute Trigger (GET) interface
+///> message, and has exactly the same effect as a GET when received, parsed, and executed by the device.
+///
+/// Same as [crate::ieee488::trg::TrgCommand] for devices with a trigger subsystem, calls [Trigger::bus_trigger].
+pub struct TrgBusCommand;
+
+impl<D> Command<D> for TrgBusCommand
+where
+    D: Device + Trigger,
+{
+    cmd_nquery!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, _params: Parameters) -> Result<()> {
+        device.bus_trigger()
+    }
+}
+
+///## 24.7 :SOURce
+///> `TRIGger[:SEQuence]:SOURce IMMediate|BUS|EXTernal|TIMer` selects the source for the trigger
+///> event, see [Trigger::set_trigger_source].
+///
+/// `TRIGger[:SEQuence]:SOURce?`
+///
+/// Returns the short form of the current source, Eg. `BUS`.
+pub struct TrigSeqSourceCommand;
+
+impl<D> Command<D> for TrigSeqSourceCommand
+where
+    D: Device + Trigger,
+{
+    cmd_both!();
+
+    fn event(&self, device: &mut D, _context: &mut Context, mut params: Parameters) -> Result<()> {
+        let source: TriggerSource = params.next_data()?;
+        device.set_trigger_source(source)
+    }
+
+    fn query(
+        &self,
+        device: &mut D,
+        _context: &mut Context,
+        _params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        response.data(device.trigger_source()).finish()
+    }
+}
+
+/// Create a `TRIGger[:SEQuence]` tree branch with the `SOURce` command and other optional
+/// `$node`s in the `SEQuence` branch.
+#[macro_export]
+macro_rules! scpi_trigger {
+    ($($node:expr),*) => {
+        scpi::tree::prelude::Branch {
+            name: b"TRIGger",
+            default: false,
+            sub: &[
+                scpi::tree::prelude::Branch {
+                    name: b"SEQuence",
+                    default: true,
+                    sub: &[
+                        scpi::tree::prelude::Leaf {
+                            name: b"SOURce",
+                            default: false,
+                            handler: &$crate::scpi1999::trigger::sequence::TrigSeqSourceCommand,
+                        },
+                        $(
+                            $node
+                        ),*
+                    ],
+                },
+            ],
+        }
+    };
+}
@@ -0,0 +1,136 @@
+//! # UNIT Subsystem
+//! The UNIT subsystem provides a mechanism to change default units.
+//!
+//! Logarithmic units are converted from the linear [uom] quantity, Eg. `20*log10(V/1uV)` for `DBUV`.
+//! The logarithm of zero is negative infinity and a negative (or NaN) quantity converts to NaN,
+//! both of which are formatted as the SCPI `-9.9E+37` and `9.91E+37` values in responses.
+
+use scpi::units::{
+    uom::si::{
+        electric_current::ampere, electric_potential::volt, electrical_resistance::ohm, power::watt,
+    },
+    ElectricCurrent, ElectricPotential, ElectricalResistance, Power,
+};
+
+/// `factor*log10(value/reference)`, NaN for negative values
+fn log_ratio(value: f32, reference: f32, factor: f32) -> f32 {
+    if value.is_nan() || value < 0.0 {
+        f32::NAN
+    } else {
+        factor * (value / reference).log10()
+    }
+}
+
+/// Units of `UNIT:POWer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scpi_derive::ScpiEnum)]
+pub enum PowerUnit {
+    /// Watt
+    #[scpi(mnemonic = b"W")]
+    Watt,
+    /// dB referenced to 1 W
+    #[scpi(mnemonic = b"DBW")]
+    DbW,
+    /// dB referenced to 1 mW (dBm)
+    #[scpi(mnemonic = b"DBM", alias = b"DBMW")]
+    Dbm,
+    /// dB referenced to 1 uW
+    #[scpi(mnemonic = b"DBUW")]
+    DbuW,
+}
+
+impl PowerUnit {
+    /// Convert a power into this unit
+    pub fn convert(self, power: Power) -> f32 {
+        let w = power.get::<watt>();
+        match self {
+            PowerUnit::Watt => w,
+            PowerUnit::DbW => log_ratio(w, 1.0, 10.0),
+            PowerUnit::Dbm => log_ratio(w, 1.0e-3, 10.0),
+            PowerUnit::DbuW => log_ratio(w, 1.0e-6, 10.0),
+        }
+    }
+
+    /// Convert the power dissipated by (RMS) voltage `voltage` over `impedance` into this unit,
+    /// Eg. a 50 Ohm system.
+    pub fn convert_voltage(
+        self,
+        voltage: ElectricPotential,
+        impedance: ElectricalResistance,
+    ) -> f32 {
+        let v = voltage.get::<volt>();
+        let w = v * v / impedance.get::<ohm>();
+        self.convert(Power::new::<watt>(w))
+    }
+}
+
+/// Units of `UNIT:VOLTage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scpi_derive::ScpiEnum)]
+pub enum VoltageUnit {
+    /// Volt
+    #[scpi(mnemonic = b"V")]
+    Volt,
+    /// dB referenced to 1 V
+    #[scpi(mnemonic = b"DBV")]
+    DbV,
+    /// dB referenced to 1 mV
+    #[scpi(mnemonic = b"DBMV")]
+    DbmV,
+    /// dB referenced to 1 uV
+    #[scpi(mnemonic = b"DBUV")]
+    DbuV,
+}
+
+impl VoltageUnit {
+    /// Convert a voltage into this unit
+    pub fn convert(self, voltage: ElectricPotential) -> f32 {
+        let v = voltage.get::<volt>();
+        match self {
+            VoltageUnit::Volt => v,
+            VoltageUnit::DbV => log_ratio(v, 1.0, 20.0),
+            VoltageUnit::DbmV => log_ratio(v, 1.0e-3, 20.0),
+            VoltageUnit::DbuV => log_ratio(v, 1.0e-6, 20.0),
+        }
+    }
+
+    /// Convert the (RMS) voltage required to dissipate `power` over `impedance` into this unit,
+    /// Eg. a 50 Ohm system.
+    pub fn convert_power(self, power: Power, impedance: ElectricalResistance) -> f32 {
+        let w = power.get::<watt>();
+        let v = if w < 0.0 {
+            f32::NAN
+        } else {
+            (w * impedance.get::<ohm>()).sqrt()
+        };
+        self.convert(ElectricPotential::new::<volt>(v))
+    }
+}
+
+/// Units of `UNIT:CURRent`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scpi_derive::ScpiEnum)]
+pub enum CurrentUnit {
+    /// Ampere
+    #[scpi(mnemonic = b"A")]
+    Ampere,
+    /// dB referenced to 1 A
+    #[scpi(mnemonic = b"DBA")]
+    DbA,
+    /// dB referenced to 1 mA
+    #[scpi(mnemonic = b"DBMA")]
+    DbmA,
+    /// dB referenced to 1 uA
+    #[scpi(mnemonic = b"DBUA")]
+    DbuA,
+}
+
+impl CurrentUnit {
+    /// Convert a current into this unit
+    pub fn convert(self, current: ElectricCurrent) -> f32 {
+        let a = current.get::<ampere>();
+        match self {
+            CurrentUnit::Ampere => a,
+            CurrentUnit::DbA => log_ratio(a, 1.0, 20.0),
+            CurrentUnit::DbmA => log_ratio(a, 1.0e-3, 20.0),
+            CurrentUnit::DbuA => log_ratio(a, 1.0e-6, 20.0),
+        }
+    }
+}
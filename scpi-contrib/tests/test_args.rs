@@ -1,7 +1,7 @@
 use scpi::{cmd_qonly, error::Result, tree::prelude::*};
 use scpi_contrib::{
     ieee488_cls, ieee488_ese, ieee488_esr, ieee488_idn, ieee488_opc, ieee488_rst, ieee488_sre,
-    ieee488_stb, ieee488_tst, ieee488_wai, scpi_status, scpi_system,
+    ieee488_stb, ieee488_tst, ieee488_wai, scpi1999::NumericValue, scpi_status, scpi_system,
 };
 
 use std::convert::TryFrom;
@@ -373,6 +373,7 @@ const IEEE488_TREE: &Node<TestDevice> = &Branch {
         add_numeric_command!(b"*I8": &EchoCommand::<i8>::new()),
         add_numeric_command!(b"*USIZE": &EchoCommand::<usize>::new()),
         add_numeric_command!(b"*ISIZE": &EchoCommand::<isize>::new()),
+        add_numeric_command!(b"*NUMV": &EchoCommand::<NumericValue<f32>>::new()),
     ],
 };
 
@@ -400,6 +401,27 @@ test_integer!(integer_usize; "*USIZE?", usize::MIN, usize::MAX);
 
 test_integer!(integer_isize; "*ISIZE?", isize::MIN, isize::MAX);
 
+mod numeric_value {
+    use super::*;
+    #[test]
+    fn test_numeric_value() {
+        let mut dev = TestDevice::new();
+
+        let res = util::test_execute_str(
+            IEEE488_TREE,
+            b"*NUMV? 1.5;*NUMV? maximum;*NUMV? MIN",
+            &mut dev,
+        )
+        .unwrap();
+        assert_eq!(res.as_slice(), b"1.5;MAX;MIN\n");
+
+        let res =
+            util::test_execute_str(IEEE488_TREE, b"*NUMV? DEF;*NUMV? UP;*NUMV? DOWN", &mut dev)
+                .unwrap();
+        assert_eq!(res.as_slice(), b"DEF;UP;DOWN\n");
+    }
+}
+
 mod string {
     use super::*;
     #[test]
@@ -6,8 +6,10 @@ use scpi::prelude::*;
 
 use scpi::{cmd_nquery, cmd_qonly, tree::prelude::*};
 use scpi_contrib::{
-    ieee488_cls, ieee488_ese, ieee488_esr, ieee488_idn, ieee488_opc, ieee488_rst, ieee488_sre,
-    ieee488_stb, ieee488_tst, ieee488_wai, scpi1999::prelude::*, scpi_status, scpi_system,
+    ieee488_cls, ieee488_ese, ieee488_esr, ieee488_idn, ieee488_lrn, ieee488_opc, ieee488_opt,
+    ieee488_psc, ieee488_rcl, ieee488_rst, ieee488_sav, ieee488_sre, ieee488_stb, ieee488_tst,
+    ieee488_wai, scpi1999::prelude::*, scpi_mandatory, scpi_register, scpi_status,
+    scpi_summary_register, scpi_system,
 };
 
 mod util;
@@ -30,8 +32,17 @@ const IEEE488_TREE: Node<TestDevice> = Branch {
         ieee488_stb!(),
         ieee488_tst!(),
         ieee488_wai!(),
-        scpi_status!(),
-        scpi_system!(),
+        ieee488_sav!(),
+        ieee488_rcl!(),
+        ieee488_lrn!(),
+        ieee488_opt!(),
+        ieee488_psc!(),
+        scpi_status!(queue_enable),
+        scpi_system!(Leaf {
+            name: b"SET",
+            default: false,
+            handler: &scpi_contrib::scpi1999::system::SystSetCommand,
+        }),
         Leaf {
             name: b"*ERR",
             default: false,
@@ -120,6 +131,26 @@ impl Command<TestDevice> for QuesCommand {
     }
 }
 
+struct InstCommand {}
+
+impl Command<TestDevice> for InstCommand {
+    cmd_nquery!();
+
+    fn event(
+        &self,
+        device: &mut TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+    ) -> Result<()> {
+        let condition: u16 = params.next_data()?;
+        device
+            .get_register_mut::<util::Instrument>()
+            .set_condition(condition);
+        device.update_register_summary::<util::Instrument, Questionable>();
+        Ok(())
+    }
+}
+
 struct QueryCommand {}
 
 impl Command<TestDevice> for QueryCommand {
@@ -151,6 +182,11 @@ impl Command<TestDevice> for EventCommand {
     }
 }
 
+#[test]
+fn test_validate() {
+    assert_eq!(IEEE488_TREE.validate(), Ok(()));
+}
+
 #[test]
 fn test_qonly() {
     let mut dev = TestDevice::new();
@@ -196,6 +232,15 @@ fn test_tst() {
 
     let res = util::test_execute_str(&IEEE488_TREE, b"*TST?", &mut dev).unwrap();
     assert_eq!(res.as_slice(), b"0\n");
+    let res = util::test_execute_str(&IEEE488_TREE, b"*ESR?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+
+    // Nonzero self-test code sets the device-dependent error bit
+    dev.tst_code = 42;
+    let res = util::test_execute_str(&IEEE488_TREE, b"*TST?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"42\n");
+    let res = util::test_execute_str(&IEEE488_TREE, b"*ESR?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"8\n");
 }
 
 #[test]
@@ -239,15 +284,212 @@ fn test_opc() {
 
     let res = util::test_execute_str(&IEEE488_TREE, b"*RST;*OPC;*ESR?", &mut dev).unwrap();
     assert_eq!(res.as_slice(), b"1\n");
+
+    // Query form must not generate an operation complete event
+    let mut dev = TestDevice::new();
+    let res = util::test_execute_str(&IEEE488_TREE, b"*OPC?;*ESR?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1;0\n");
+    assert!(dev.errors.is_empty());
+}
+
+#[test]
+fn test_opc_query_pending() {
+    // Response is generated once the pending operations finish
+    let mut dev = TestDevice::new();
+    dev.pending = Some(5);
+    let res = util::test_execute_str(&IEEE488_TREE, b"*OPC?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1\n");
+    assert_eq!(dev.pending, Some(0));
+
+    // Operations which never finish report the device timeout
+    let mut dev = TestDevice::new();
+    dev.pending = None;
+    let res = util::test_execute_str(&IEEE488_TREE, b"*OPC?", &mut dev).unwrap_err();
+    assert_eq!(res, Error::from(ErrorCode::ExecutionError));
+}
+
+#[test]
+fn test_sav_rcl() {
+    let mut dev = TestDevice::new();
+
+    let _res = util::test_execute_str(&IEEE488_TREE, b"*ESE 8;*SAV 1", &mut dev).unwrap();
+    assert_eq!(dev.states[1], Some(8));
+
+    let res = util::test_execute_str(&IEEE488_TREE, b"*ESE 0;*RCL 1;*ESE?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"8\n");
+
+    // Out of range
+    let res = util::test_execute_str(&IEEE488_TREE, b"*SAV 4", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+    let res = util::test_execute_str(&IEEE488_TREE, b"*RCL -1", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+    let res = util::test_execute_str(&IEEE488_TREE, b"*SAV", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::MissingParameter);
+}
+
+#[test]
+fn test_lrn() {
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&IEEE488_TREE, b"*ESE 8;*SRE 32;*LRN?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"*ESE 8;*SRE 32\n");
+
+    // Learn string restores setup
+    let mut dev2 = TestDevice::new();
+    let _res = util::test_execute_str(&IEEE488_TREE, &res, &mut dev2).unwrap();
+    assert_eq!((dev2.ese, dev2.sre), (8, 32));
+
+    let _res = util::test_execute_str(&IEEE488_TREE, b"SYST:SET #12\x04\x10", &mut dev2).unwrap();
+    assert_eq!((dev2.ese, dev2.sre), (4, 16));
+
+    let res = util::test_execute_str(&IEEE488_TREE, b"SYST:SET #11\x04", &mut dev2).unwrap_err();
+    assert_eq!(res, ErrorCode::InvalidFormat);
 }
 
 #[test]
 fn test_wai() {
     // Do not support overlapped commands so
-    // WAI is a NOP command
+    // WAI only calls the wait hook
     let mut dev = TestDevice::new();
 
     let _res = util::test_execute_str(&IEEE488_TREE, b"*WAI", &mut dev).unwrap();
+    assert_eq!(dev.waits, 1);
+}
+
+#[test]
+fn test_psc() {
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&IEEE488_TREE, b"*PSC?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1\n");
+
+    let res = util::test_execute_str(&IEEE488_TREE, b"*PSC 0;*PSC?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+    assert!(!dev.psc);
+
+    // Nonzero values are true
+    let res = util::test_execute_str(&IEEE488_TREE, b"*PSC 5;*PSC?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1\n");
+}
+
+#[test]
+fn test_opt() {
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&IEEE488_TREE, b"*OPT?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+
+    dev.options = &[b"GPIB", b"LAN"];
+    let res = util::test_execute_str(&IEEE488_TREE, b"*OPT?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"GPIB,LAN\n");
+
+    let res = util::test_execute_str(&IEEE488_TREE, b"*OPT", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::UndefinedHeader);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_syst_help_headers() {
+    use scpi::tree::command::Todo;
+    use scpi_contrib::scpi1999::system::help::SystHelpHeadersCommand;
+    use scpi_contrib::scpi_system;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            ieee488_cls!(),
+            ieee488_ese!(),
+            scpi_system!(Branch {
+                name: b"HELP",
+                default: false,
+                sub: &[Leaf {
+                    name: b"HEADers",
+                    default: false,
+                    handler: &SystHelpHeadersCommand,
+                }],
+            }),
+            // Default leaf without a name
+            scpi::Branch!(b"INITiate" => &Todo;
+                scpi::Leaf!(b"CONTinuous" => &Todo)
+            ),
+        ],
+    };
+    let mut dev = TestDevice::new();
+    dev.help_tree = Some(&TREE);
+
+    let res = util::test_execute_str(&TREE, b"SYST:HELP:HEAD?", &mut dev).unwrap();
+    let headers: &[u8] = b"*CLS\n\
+        *ESE\n\
+        *ESE?\n\
+        :SYSTem:ERRor:NEXT?\n\
+        :SYSTem:ERRor:ALL?\n\
+        :SYSTem:ERRor:COUNt?\n\
+        :SYSTem:VERSion?\n\
+        :SYSTem:HELP:HEADers?\n\
+        :INITiate\n\
+        :INITiate?\n\
+        :INITiate:CONTinuous\n\
+        :INITiate:CONTinuous?\n";
+    let mut expected = format!("#3{}", headers.len()).into_bytes();
+    expected.extend_from_slice(headers);
+    expected.push(b'\n');
+    assert_eq!(res.as_slice(), expected.as_slice());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_macros() {
+    use scpi_contrib::{ieee488_dmc, ieee488_gmc, ieee488_lmc, ieee488_pmc, ieee488_rmc};
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            ieee488_dmc!(),
+            ieee488_gmc!(),
+            ieee488_lmc!(),
+            ieee488_pmc!(),
+            ieee488_rmc!(),
+        ],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"*LMC?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"\"\n");
+
+    // Block and string contents
+    let res = util::test_execute_str(
+        &TREE,
+        b"*DMC 'TST',#19*RST;*CLS;*DMC 'VOLT','SOUR:VOLT 5';*GMC? 'tst';*LMC?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"#19*RST;*CLS;\"TST\",\"VOLT\"\n");
+
+    let res = util::test_execute_str(&TREE, b"*DMC 'TST','*CLS'", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::MacroRedefinitionNotAllowed);
+    let res = util::test_execute_str(&TREE, b"*DMC 'A B','*CLS'", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalMacroLabel);
+    let res = util::test_execute_str(&TREE, b"*DMC 'LOOP','*CLS;LOOP'", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::MacroRecursionError);
+    let res = util::test_execute_str(&TREE, b"*DMC 'BAD','*CLS;&'", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::MacroSyntaxError);
+    let res = util::test_execute_str(
+        &TREE,
+        b"*DMC 'LONG',#234*RST;*RST;*RST;*RST;*RST;*RST;*CLS",
+        &mut dev,
+    )
+    .unwrap_err();
+    assert_eq!(res, ErrorCode::MacroDefinitionTooLong);
+
+    let res = util::test_execute_str(&TREE, b"*RMC 'TST';*LMC?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"VOLT\"\n");
+    let res = util::test_execute_str(&TREE, b"*GMC? 'TST'", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::MacroHeaderNotFound);
+
+    let res = util::test_execute_str(&TREE, b"*PMC;*LMC?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"\"\n");
 }
 
 #[test]
@@ -341,6 +583,147 @@ fn test_syst_err() {
     assert_eq!(res.as_slice(), b"0,\"No error\"\n");
 }
 
+#[test]
+fn test_stat_queue() {
+    let mut dev = TestDevice::new();
+
+    // Same as SYSTem:ERRor:NEXT?
+    let res = util::test_execute_str(&IEEE488_TREE, b"stat:que:next?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0,\"No error\"\n");
+
+    let res = util::test_execute_str(
+        &IEEE488_TREE,
+        b"*err -100;*err -200;stat:que?;:syst:err?;:stat:que?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"-100,\"Command error\";-200,\"Execution error\";0,\"No error\"\n"
+    );
+
+    // Only enabled classes are queued, ESR is still set
+    let res = util::test_execute_str(
+        &IEEE488_TREE,
+        b"stat:que:enab #H10;enab?;*err -100;*err -200;*esr?;:stat:que?;:stat:que?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"16;48;-200,\"Execution error\";0,\"No error\"\n"
+    );
+
+    // Queue enable is opt-in
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_status!()],
+    };
+    let res = util::test_execute_str(&TREE, b"stat:que:enab #H10", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::UndefinedHeader);
+    let res = util::test_execute_str(&TREE, b"stat:que?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0,\"No error\"\n");
+}
+
+#[test]
+fn test_syst_err_all() {
+    let mut dev = TestDevice::new();
+
+    // Empty queue
+    let res = util::test_execute_str(&IEEE488_TREE, b"syst:err:all?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0,\"No error\"\n");
+
+    // All errors are returned in order and the queue is left empty
+    dev.push_error(Error::new(ErrorCode::CommandError));
+    dev.push_error(Error::new(ErrorCode::ExecutionError));
+    let res = util::test_execute_str(&IEEE488_TREE, b"syst:err:all?;count?", &mut dev).unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"-100,\"Command error\",-200,\"Execution error\";0\n"
+    );
+}
+
+#[test]
+fn test_syst_err_extended() {
+    let mut dev = TestDevice::new();
+
+    dev.push_error(Error::new(ErrorCode::CommandError).extended(b"Bad \"thing\""));
+    let res = util::test_execute_str(&IEEE488_TREE, b"syst:err?", &mut dev).unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"-100,\"Command error;Bad \"\"thing\"\"\"\n"
+    );
+
+    dev.error_prefix = Some(b"PSU1");
+    dev.push_error(Error::new(ErrorCode::CommandError).extended(b"ext"));
+    dev.push_error(Error::new(ErrorCode::ExecutionError));
+    let res = util::test_execute_str(&IEEE488_TREE, b"syst:err?;err?", &mut dev).unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"-100,\"Command error;PSU1: ext\";-200,\"Execution error;PSU1\"\n"
+    );
+
+    // Prefix is not added to the empty queue response
+    let res = util::test_execute_str(&IEEE488_TREE, b"syst:err?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0,\"No error\"\n");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_syst_err_message() {
+    let mut dev = TestDevice::new();
+
+    let file = "data.csv";
+    dev.push_error_message(
+        Error::new(ErrorCode::FileNameNotFound),
+        format!("{file} missing").into_bytes(),
+    );
+    dev.push_error(Error::new(ErrorCode::CommandError));
+    let res = util::test_execute_str(&IEEE488_TREE, b"syst:err?;err?", &mut dev).unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"-256,\"Filename not found;data.csv missing\";-100,\"Command error\"\n"
+    );
+
+    dev.error_prefix = Some(b"PSU1");
+    dev.push_error_message(Error::new(ErrorCode::ExecutionError), b"ext".to_vec());
+    let res = util::test_execute_str(&IEEE488_TREE, b"syst:err:all?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"-200,\"Execution error;PSU1: ext\"\n");
+}
+
+#[test]
+fn test_run_all() {
+    let mut dev = TestDevice::new();
+    let mut context = Context::new();
+    let mut buf = Vec::new();
+
+    let mut responses = Vec::new();
+    IEEE488_TREE
+        .run_all(b"*IDN?\n*ESR?\n", &mut dev, &mut context, &mut buf, |res| {
+            responses.push(res.to_vec())
+        })
+        .unwrap();
+    assert_eq!(
+        responses,
+        [&b"GPA-Robotics,T800-101,0,0\n"[..], &b"0\n"[..]]
+    );
+
+    // Errors are reported but do not abort, empty lines are ignored
+    // and the trailing message does not need a terminator.
+    let mut responses = Vec::new();
+    let res = IEEE488_TREE.run_all(
+        b"*ESR?;*FOO?\n\n  \n*STB?\n*ESR?",
+        &mut dev,
+        &mut context,
+        &mut buf,
+        |res| responses.push(res.to_vec()),
+    );
+    assert_eq!(res, Err(ErrorCode::UndefinedHeader.into()));
+    assert_eq!(responses, [&b"4\n"[..], &b"32\n"[..]]);
+    assert_eq!(dev.errors.len(), 1);
+}
+
 #[test]
 fn test_syst_version() {
     let mut dev = TestDevice::new();
@@ -349,6 +732,563 @@ fn test_syst_version() {
     assert_eq!(res.as_slice(), b"1999.0\n");
 }
 
+#[test]
+fn test_syst_version_device() {
+    use scpi_contrib::scpi1999::system::SystVersionDeviceCommand;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[Branch {
+            name: b"SYSTem",
+            default: false,
+            sub: &[Leaf {
+                name: b"VERSion",
+                default: false,
+                handler: &SystVersionDeviceCommand,
+            }],
+        }],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"syst:vers?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1999.0\n");
+
+    dev.scpi_version = (1999, 5);
+    let res = util::test_execute_str(&TREE, b"syst:vers?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1999.5\n");
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_syst_capability() {
+    use scpi_contrib::scpi1999::system::capability::SystCapabilityCommand;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_system!(Leaf {
+            name: b"CAPability",
+            default: false,
+            handler: &SystCapabilityCommand,
+        })],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"syst:cap?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"\"\n");
+
+    dev.capabilities = &[b"DIGITIZER", b"SWITCH"];
+    let res = util::test_execute_str(&TREE, b"syst:cap?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"DIGITIZER\\SWITCH\"\n");
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_syst_lfrequency() {
+    use scpi::units::uom::si::{frequency::hertz, time::second};
+    use scpi_contrib::scpi1999::system::lfrequency::{LineFrequency, SystLfrequencyCommand};
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_system!(Leaf {
+            name: b"LFRequency",
+            default: false,
+            handler: &SystLfrequencyCommand,
+        })],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"syst:lfr?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"50.0\n");
+
+    let res = util::test_execute_str(&TREE, b"SYST:LFR 60;LFR?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"60.0\n");
+    assert_eq!(dev.line_frequency.get::<hertz>(), 60.0);
+    assert_eq!(dev.line_cycles(6.0).get::<second>(), 0.1);
+
+    let res = util::test_execute_str(&TREE, b"syst:lfr 0.05 kHz", &mut dev).unwrap();
+    assert!(res.is_empty());
+    assert_eq!(dev.line_frequency.get::<hertz>(), 50.0);
+
+    let res = util::test_execute_str(&TREE, b"syst:lfr 400", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_syst_communicate() {
+    use scpi_contrib::scpi_communicate;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_system!(scpi_communicate!(gpib, serial, lan))],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"syst:comm:gpib:addr 7;addr?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"7\n");
+    assert_eq!(dev.comm.gpib_address, 7);
+
+    let res =
+        util::test_execute_str(&TREE, b"syst:comm:gpib:self:addr max;addr?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"30\n");
+
+    let res = util::test_execute_str(&TREE, b"syst:comm:gpib:addr 31", &mut dev);
+    assert_eq!(res.unwrap_err(), ErrorCode::DataOutOfRange);
+
+    let res = util::test_execute_str(&TREE, b"syst:comm:ser:baud 115200;baud?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"115200\n");
+
+    let res = util::test_execute_str(&TREE, b"syst:comm:ser:rec:baud 1234", &mut dev);
+    assert_eq!(res.unwrap_err(), ErrorCode::IllegalParameterValue);
+
+    let res = util::test_execute_str(
+        &TREE,
+        b"syst:comm:lan:addr \"192.168.0.10\";addr?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"\"192.168.0.10\"\n");
+
+    for bad in [
+        &b"'1.2.3'"[..],
+        b"'1.2.3.4.5'",
+        b"'256.0.0.1'",
+        b"'a.b.c.d'",
+    ] {
+        let cmd = [&b"syst:comm:lan:addr "[..], bad].concat();
+        let res = util::test_execute_str(&TREE, &cmd, &mut dev);
+        assert_eq!(res.unwrap_err(), ErrorCode::IllegalParameterValue);
+    }
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_init_continuous() {
+    use scpi_contrib::scpi1999::trigger::initiate::{
+        AbortCommand, InitContinuousCommand, InitImmCommand,
+    };
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            Leaf {
+                name: b"ABORt",
+                default: false,
+                handler: &AbortCommand,
+            },
+            Branch {
+                name: b"INITiate",
+                default: false,
+                sub: &[
+                    Leaf {
+                        name: b"IMMediate",
+                        default: true,
+                        handler: &InitImmCommand,
+                    },
+                    Leaf {
+                        name: b"CONTinuous",
+                        default: false,
+                        handler: &InitContinuousCommand,
+                    },
+                ],
+            },
+        ],
+    };
+    let mut dev = TestDevice::new();
+
+    // Abort returns to idle
+    let res = util::test_execute_str(&TREE, b"init;:abor", &mut dev);
+    assert!(res.is_ok());
+    assert!(!dev.initiated);
+
+    let res = util::test_execute_str(&TREE, b"init:cont ON;cont?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1\n");
+    assert!(dev.initiated);
+
+    // Re-initiated after abort when continuous
+    let res = util::test_execute_str(&TREE, b"abor", &mut dev);
+    assert!(res.is_ok());
+    assert!(dev.initiated);
+
+    let res = util::test_execute_str(&TREE, b"init:cont 0;cont?;:abor", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+    assert!(!dev.initiated);
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_trigger_bus() {
+    use scpi_contrib::scpi1999::trigger::sequence::TrgBusCommand;
+    use scpi_contrib::scpi_trigger;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            Leaf {
+                name: b"*TRG",
+                default: false,
+                handler: &TrgBusCommand,
+            },
+            scpi_trigger!(),
+        ],
+    };
+    let mut dev = TestDevice::new();
+
+    // Ignored unless the trigger source is BUS
+    let res = util::test_execute_str(&TREE, b"*trg", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::TriggerIgnored);
+    assert_eq!(dev.trigger.1, 0);
+
+    let res = util::test_execute_str(&TREE, b"trig:sour bus;sour?;*trg", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"BUS\n");
+    assert_eq!(dev.trigger.1, 1);
+
+    let res = util::test_execute_str(&TREE, b"trig:seq:sour ext;:trig:sour?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"EXT\n");
+    let res = util::test_execute_str(&TREE, b"*trg", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::TriggerIgnored);
+    assert_eq!(dev.trigger.1, 1);
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_route() {
+    use scpi_contrib::scpi1999::route::{RoutCloseCommand, RoutOpenCommand};
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[Branch {
+            name: b"ROUTe",
+            default: false,
+            sub: &[
+                Leaf {
+                    name: b"CLOSe",
+                    default: false,
+                    handler: &RoutCloseCommand,
+                },
+                Leaf {
+                    name: b"OPEN",
+                    default: false,
+                    handler: &RoutOpenCommand,
+                },
+            ],
+        }],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"rout:clos?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"(@)\n");
+
+    // Ranges are expanded
+    let res = util::test_execute_str(&TREE, b"rout:clos (@1:3);clos?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"(@1,2,3)\n");
+    assert_eq!(dev.closed, 0b1110);
+
+    let res = util::test_execute_str(&TREE, b"rout:open (@3:2,7);clos (@10,5)", &mut dev);
+    assert!(res.is_ok());
+    let res = util::test_execute_str(&TREE, b"rout:clos?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"(@1,5,10)\n");
+
+    // Query form with a channel list
+    let res = util::test_execute_str(&TREE, b"rout:clos? (@4:6,1)", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0,1,0,1\n");
+
+    // Channel list is required
+    let res = util::test_execute_str(&TREE, b"rout:clos 1", &mut dev);
+    assert!(res.is_err());
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_output() {
+    use scpi_contrib::scpi_output;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_output!()],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"outp?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+    let res = util::test_execute_str(&TREE, b"OUTP ON;OUTP?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1\n");
+    let res = util::test_execute_str(&TREE, b"output:state off;state?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+
+    // Cannot enable output with the interlock open
+    dev.output.1 = true;
+    let res = util::test_execute_str(&TREE, b"outp on", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::SettingsConflict);
+    assert!(!dev.output.0);
+    let _res = util::test_execute_str(&TREE, b"outp off", &mut dev).unwrap();
+}
+
+#[cfg(all(feature = "unproven", feature = "unit"))]
+#[test]
+fn test_source() {
+    use scpi::units::{uom::si::electric_potential::volt, ElectricPotential};
+    use scpi_contrib::scpi_source;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_source!(b"VOLTage": ElectricPotential)],
+    };
+    let mut dev = TestDevice::new();
+
+    let _res = util::test_execute_str(&TREE, b"SOUR:VOLT 3.3", &mut dev).unwrap();
+    assert_eq!(dev.voltage.get::<volt>(), 3.3);
+    let res = util::test_execute_str(&TREE, b"SOUR:VOLT?;VOLT? MAX;VOLT? min", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"3.3;10.0;0.0\n");
+
+    // Suffixes and special values, SOURce is optional
+    let res = util::test_execute_str(&TREE, b"volt 500mV;volt?;volt def;volt?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0.5;5.0\n");
+    let _res = util::test_execute_str(&TREE, b"volt max", &mut dev).unwrap();
+    assert_eq!(dev.voltage.get::<volt>(), 10.0);
+
+    let res = util::test_execute_str(&TREE, b"volt 11", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::DataOutOfRange);
+    let res = util::test_execute_str(&TREE, b"volt 1 A", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+
+    // Step size
+    let res = util::test_execute_str(&TREE, b"volt? step;volt? res", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0.1;0.1\n");
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_display() {
+    use scpi_contrib::scpi_display;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_display!()],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, br#"DISP:TEXT "HELLO";TEXT?"#, &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"HELLO\"\n");
+    let res = util::test_execute_str(&TREE, b"disp:wind:text:data?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"HELLO\"\n");
+
+    let res =
+        util::test_execute_str(&TREE, b"disp off;disp?;disp:wind:stat on;stat?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0;1\n");
+
+    let res =
+        util::test_execute_str(&TREE, br#"DISP:TEXT "HELLO WORLD, HELLO""#, &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::TooMuchData);
+    assert_eq!(dev.display.0, "HELLO");
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_instrument() {
+    use scpi_contrib::scpi_instrument;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_instrument!()],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"INST:CAT?;SEL?;NSEL?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"PSU\",\"DMM\";PSU;1\n");
+
+    let res = util::test_execute_str(&TREE, b"INST:NSEL 2;SEL?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"DMM\n");
+
+    let res = util::test_execute_str(&TREE, b"inst psu;:inst:nsel?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1\n");
+
+    let res = util::test_execute_str(&TREE, b"INST:NSEL 3", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+    let res = util::test_execute_str(&TREE, b"INST:SEL SCOPE", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+    assert_eq!(dev.selected_instrument, 1);
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_calibration() {
+    use scpi_contrib::{ieee488_cal, scpi_calibration};
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[ieee488_cal!(), scpi_calibration!()],
+    };
+    let mut dev = TestDevice::new();
+
+    // No calibration data saved
+    let res = util::test_execute_str(&TREE, b"*CAL?;:CAL?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"-313;-313\n");
+
+    let res = util::test_execute_str(
+        &TREE,
+        b"cal:step 1;val 2.5;step?;step 0;val 1e-3;save",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"1\n");
+    assert_eq!(dev.calibration.saved, Some([1e-3, 2.5]));
+
+    let res = util::test_execute_str(&TREE, b"*CAL?;:CAL:ALL?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0;0\n");
+
+    let res = util::test_execute_str(&TREE, b"cal:step 2", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+
+    // Errors other than calibration faults are not a response
+    dev.pending = Some(1);
+    let res = util::test_execute_str(&TREE, b"*CAL?", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::ExecutionError);
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_diagnostic() {
+    use scpi_contrib::scpi_diagnostic;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_diagnostic!()],
+    };
+    let mut dev = TestDevice::new();
+
+    // No test has been run yet
+    let res = util::test_execute_str(&TREE, b"diag:res?", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::DataCorruptOrStale);
+
+    let res = util::test_execute_str(&TREE, b"diag:test? RAM;res?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0;RAM,0\n");
+
+    let res = util::test_execute_str(&TREE, b"diag:test? rom;res?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"3;ROM,3\n");
+
+    // Unknown test
+    let res = util::test_execute_str(&TREE, b"diag:test? CPU", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_mmemory() {
+    use scpi_contrib::scpi_mmemory;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_mmemory!()],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"mmem:cat?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0,64\n");
+
+    let res = util::test_execute_str(
+        &TREE,
+        b"mmem:data \"wave.dat\",#15hello;mdir \"data\";cat?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"5,59,\"data/,,0\",\"wave.dat,,5\"\n");
+
+    let res = util::test_execute_str(&TREE, b"mmem:data? \"wave.dat\"", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"#15hello\n");
+
+    // Catalog of a directory
+    let res = util::test_execute_str(
+        &TREE,
+        b"mmem:data \"data/raw.dat\",#11x;cat? \"data\";cat?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"6,58,\"raw.dat,,1\";6,58,\"data/,,0\",\"wave.dat,,5\"\n"
+    );
+    let res = util::test_execute_str(&TREE, b"mmem:cat? \"none\"", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::FileNameNotFound);
+
+    // Missing files
+    let res = util::test_execute_str(&TREE, b"mmem:del \"wave.dat\";data? \"wave.dat\"", &mut dev)
+        .unwrap_err();
+    assert_eq!(res, ErrorCode::FileNameNotFound);
+    let res = util::test_execute_str(&TREE, b"mmem:del \"wave.dat\"", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::FileNameNotFound);
+
+    // File names must be ascii to be listed
+    dev.files.insert("wäve.dat".into(), Vec::new());
+    let res = util::test_execute_str(&TREE, b"mmem:cat?", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::ExecutionError);
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_sens_function() {
+    use scpi_contrib::scpi1999::sense::function::{SensFunctionOffCommand, SensFunctionOnCommand};
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[Branch {
+            name: b"SENSe",
+            default: true,
+            sub: &[Branch {
+                name: b"FUNCtion",
+                default: false,
+                sub: &[
+                    Leaf {
+                        name: b"ON",
+                        default: true,
+                        handler: &SensFunctionOnCommand,
+                    },
+                    Leaf {
+                        name: b"OFF",
+                        default: false,
+                        handler: &SensFunctionOffCommand,
+                    },
+                ],
+            }],
+        }],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"sens:func:on?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"\"\n");
+
+    let _res = util::test_execute_str(&TREE, br#"func "volt:dc","CURRent:DC""#, &mut dev).unwrap();
+    let res = util::test_execute_str(&TREE, b"sens:func:on?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"VOLTage:DC\",\"CURRent:DC\"\n");
+    let res = util::test_execute_str(&TREE, b"func:off?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"RESistance\"\n");
+
+    let _res = util::test_execute_str(&TREE, br#"func:off "VOLT:DC""#, &mut dev).unwrap();
+    let res = util::test_execute_str(&TREE, b"func?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"\"CURRent:DC\"\n");
+
+    // Unknown function or missing modifier
+    let res = util::test_execute_str(&TREE, br#"func "VOLT""#, &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+    let res = util::test_execute_str(&TREE, b"func", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::MissingParameter);
+}
+
 #[test]
 fn test_stat_operation() {
     let mut dev = TestDevice::new();
@@ -399,6 +1339,441 @@ fn test_stat_operation() {
     assert_eq!(res.as_slice(), b"0;32767;0\n");
 }
 
+#[test]
+fn test_register_reserved_bit() {
+    let mut reg = EventRegister::new();
+    reg.enable = 0xffff;
+    reg.ntr_filter = 0xffff;
+
+    reg.set_condition(0xffff);
+    assert_eq!(reg.condition, 0x7fff);
+    assert_eq!(reg.event, 0x7fff);
+    assert!(!reg.get_condition_bit(0x8000));
+
+    // Bit 15 alone never produces a transition or summary
+    reg.set_condition(0);
+    reg.clear_event();
+    reg.set_condition_bits(0x8000);
+    assert_eq!(reg.condition, 0);
+    assert_eq!(reg.event, 0);
+    assert!(!reg.get_summary());
+}
+
+#[test]
+fn test_stat_summary_register() {
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            ieee488_stb!(),
+            Branch {
+                name: b"STATus",
+                default: false,
+                sub: &[scpi_register!(b"QUEStionable", Questionable;
+                    scpi_summary_register!(b"INSTrument", util::Instrument => Questionable)
+                )],
+            },
+            Leaf {
+                name: b"*INST",
+                default: false,
+                handler: &InstCommand {},
+            },
+        ],
+    };
+    let mut dev = TestDevice::new();
+
+    // Disabled child bits are not summarized
+    let res = util::test_execute_str(
+        &TREE,
+        b"*inst 2;stat:ques:inst:cond?;:stat:ques:cond?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"2;0\n");
+
+    // Enabling the set bit propagates to the summary bit of the parent
+    let res = util::test_execute_str(
+        &TREE,
+        b"stat:ques:inst:enab 2;:stat:ques:cond?;event?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"8192;8192\n");
+
+    // Summary follows child condition changes
+    let res = util::test_execute_str(&TREE, b"*inst 0;stat:ques:cond?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+    let res =
+        util::test_execute_str(&TREE, b"stat:ques:enab 8192;*inst 2;*stb?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"8\n");
+}
+
+#[test]
+fn test_stat_preset_custom_register() {
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[scpi_status!(
+            scpi_summary_register!(b"INSTrument", util::Instrument => Questionable)
+        )],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"stat:inst:enab 255;enab?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"255\n");
+
+    // Device-defined registers are preset as well
+    let res = util::test_execute_str(&TREE, b"stat:pres;inst:enab?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+}
+
+#[test]
+fn test_stat_oper_instrument() {
+    use scpi_contrib::scpi1999::status::instrument::OperationInstrumentSummary;
+
+    struct IsumCommand;
+
+    impl Command<TestDevice> for IsumCommand {
+        cmd_nquery!();
+
+        fn event(
+            &self,
+            device: &mut TestDevice,
+            _context: &mut Context,
+            mut params: Parameters,
+        ) -> Result<()> {
+            let instrument: u16 = params.next_data()?;
+            let condition: u16 = params.next_data()?;
+            match instrument {
+                1 => {
+                    device
+                        .get_register_mut::<OperationInstrumentSummary<1>>()
+                        .set_condition(condition);
+                    device.update_instrument_summary::<1>();
+                }
+                2 => {
+                    device
+                        .get_register_mut::<OperationInstrumentSummary<2>>()
+                        .set_condition(condition);
+                    device.update_instrument_summary::<2>();
+                }
+                _ => return Err(ErrorCode::IllegalParameterValue.into()),
+            }
+            Ok(())
+        }
+    }
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            scpi_status!(queue_enable, instruments: [b"ISUMmary1" => 1, b"ISUMmary2" => 2]),
+            Leaf {
+                name: b"*ISUM",
+                default: false,
+                handler: &IsumCommand,
+            },
+        ],
+    };
+    let mut dev = TestDevice::new();
+
+    // Enabling a logical instrument register summarizes it in INSTrument
+    let res = util::test_execute_str(
+        &TREE,
+        b"*isum 2,16;stat:oper:inst:isum2:enab 16;:stat:oper:inst:cond?;:stat:oper:cond?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"4;0\n");
+
+    // Enabling INSTrument summarizes it in OPERation
+    let res =
+        util::test_execute_str(&TREE, b"stat:oper:inst:enab 6;:stat:oper:cond?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"8192\n");
+
+    // Both instruments are summarized
+    let res = util::test_execute_str(
+        &TREE,
+        b"*isum 1,1;stat:oper:inst:isum:enab 1;:stat:oper:inst:cond?;isum1:cond?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"6;1\n");
+
+    // OPERation summary is cleared once no instrument is summarized
+    let res = util::test_execute_str(
+        &TREE,
+        b"*isum 1,0;*isum 2,0;stat:oper:inst:cond?;:stat:oper:cond?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(res.as_slice(), b"0;0\n");
+
+    // Logical instrument registers are preset
+    let res = util::test_execute_str(&TREE, b"stat:pres;oper:inst:isum2:enab?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+}
+
+#[test]
+fn test_form_data() {
+    use scpi_contrib::scpi1999::format::{FormBorderCommand, FormDataCommand};
+
+    struct RealCommand;
+
+    impl Command<TestDevice> for RealCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut TestDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.data(1.5f32).data(2u8).finish()
+        }
+    }
+
+    struct TraceCommand;
+
+    impl Command<TestDevice> for TraceCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut TestDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.data(RealBlock(&[1.0, 2.0])).finish()
+        }
+    }
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            Branch {
+                name: b"FORMat",
+                default: false,
+                sub: &[
+                    Leaf {
+                        name: b"DATA",
+                        default: true,
+                        handler: &FormDataCommand,
+                    },
+                    Leaf {
+                        name: b"BORDer",
+                        default: false,
+                        handler: &FormBorderCommand,
+                    },
+                ],
+            },
+            Leaf {
+                name: b"*REAL",
+                default: false,
+                handler: &RealCommand,
+            },
+            Leaf {
+                name: b"*TRACe",
+                default: false,
+                handler: &TraceCommand,
+            },
+        ],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"form?;*real?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"ASC,0;1.5,2\n");
+
+    // Reals are sent as binary blocks, integers are not affected
+    let res = util::test_execute_str(&TREE, b"form real,32;form?;*real?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"REAL,32;#14\x3f\xc0\x00\x00,2\n");
+
+    let res = util::test_execute_str(&TREE, b"form:data real;:form?;*real?", &mut dev).unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"REAL,64;#18\x3f\xf8\x00\x00\x00\x00\x00\x00,2\n"
+    );
+
+    let res = util::test_execute_str(&TREE, b"form:bord?;*trac?", &mut dev).unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"NORM;#18\x3f\x80\x00\x00\x40\x00\x00\x00\n"
+    );
+    let res = util::test_execute_str(
+        &TREE,
+        b"form:bord swap;bord?;:form real,32;*real?;*trac?",
+        &mut dev,
+    )
+    .unwrap();
+    assert_eq!(
+        res.as_slice(),
+        b"SWAP;#14\x00\x00\xc0\x3f,2;#18\x00\x00\x80\x3f\x00\x00\x00\x40\n"
+    );
+
+    let res = util::test_execute_str(&TREE, b"form real,16", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+
+    // Number of significant digits
+    let res = util::test_execute_str(&TREE, b"form asc,3;form?;*real?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"ASC,3;1.50E+00,2\n");
+    let res = util::test_execute_str(&TREE, b"form asc,99;form?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"ASC,17\n");
+    let res = util::test_execute_str(&TREE, b"form asc,0;form?;*real?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"ASC,0;1.5,2\n");
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_sens_range_auto() {
+    use scpi_contrib::scpi1999::sense::range::SensRangeAutoCommand;
+    use scpi_contrib::scpi1999::util::Auto;
+    use util::Voltage;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[Branch {
+            name: b"SENSe",
+            default: true,
+            sub: &[Branch {
+                name: b"VOLTage",
+                default: false,
+                sub: &[Branch {
+                    name: b"RANGe",
+                    default: false,
+                    sub: &[Leaf {
+                        name: b"AUTO",
+                        default: false,
+                        handler: &SensRangeAutoCommand::<Voltage>::new(),
+                    }],
+                }],
+            }],
+        }],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"sens:volt:rang:auto?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+
+    let _res = util::test_execute_str(&TREE, b"volt:rang:auto on", &mut dev).unwrap();
+    assert!(dev.auto_range.auto_enabled());
+    let res = util::test_execute_str(&TREE, b"volt:rang:auto?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1\n");
+
+    // ONCE turns off after autorange has been executed by a measurement
+    let _res = util::test_execute_str(&TREE, b"sens:volt:rang:auto once", &mut dev).unwrap();
+    assert!(matches!(dev.auto_range, Auto::Once));
+    dev.auto_range.auto_once();
+    let res = util::test_execute_str(&TREE, b"volt:rang:auto?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0\n");
+
+    let res = util::test_execute_str(&TREE, b"volt:rang:auto maybe", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::IllegalParameterValue);
+}
+
+#[cfg(feature = "unproven")]
+#[test]
+fn test_sens_nplc() {
+    use scpi_contrib::scpi1999::sense::nplc::SensNplcCommand;
+    use util::Voltage;
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[Branch {
+            name: b"SENSe",
+            default: true,
+            sub: &[Branch {
+                name: b"VOLTage",
+                default: false,
+                sub: &[Leaf {
+                    name: b"NPLCycles",
+                    default: false,
+                    handler: &SensNplcCommand::<Voltage>::new(),
+                }],
+            }],
+        }],
+    };
+    let mut dev = TestDevice::new();
+
+    let _res = util::test_execute_str(&TREE, b"SENS:VOLT:NPLC 10", &mut dev).unwrap();
+    assert_eq!(dev.nplc, 10.0);
+    let res = util::test_execute_str(&TREE, b"SENS:VOLT:NPLC?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"10.0\n");
+
+    let _res = util::test_execute_str(&TREE, b"volt:nplc def", &mut dev).unwrap();
+    assert_eq!(dev.nplc, 1.0);
+    let res = util::test_execute_str(&TREE, b"volt:nplc? max;nplc? min", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"100.0;0.02\n");
+
+    let res = util::test_execute_str(&TREE, b"volt:nplc 1000", &mut dev).unwrap_err();
+    assert_eq!(res, ErrorCode::DataOutOfRange);
+}
+
+#[test]
+fn test_form_border_device() {
+    use scpi::parser::response::ByteOrder;
+    use scpi_contrib::scpi1999::format::{FormBorderDeviceCommand, Format};
+
+    struct TraceCommand;
+
+    impl Command<TestDevice> for TraceCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut TestDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.data(RealBlock(&[1.0f32])).finish()
+        }
+    }
+
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            Branch {
+                name: b"FORMat",
+                default: false,
+                sub: &[Leaf {
+                    name: b"BORDer",
+                    default: false,
+                    handler: &FormBorderDeviceCommand,
+                }],
+            },
+            Leaf {
+                name: b"*TRACe",
+                default: false,
+                handler: &TraceCommand,
+            },
+        ],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"form:bord?;*trac?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"NORM;#14\x3f\x80\x00\x00\n");
+
+    let res = util::test_execute_str(&TREE, b"form:bord swap;*trac?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"#14\x00\x00\x80\x3f\n");
+    assert_eq!(dev.byte_order, ByteOrder::Swapped);
+
+    // Setting outlives the context
+    let res = util::test_execute_str(&TREE, b"form:bord?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"SWAP\n");
+    let mut context = Context::default();
+    dev.restore_format(&mut context);
+    let mut res = Vec::new();
+    TREE.run(b"*trac?", &mut dev, &mut context, &mut res)
+        .unwrap();
+    assert_eq!(res.as_slice(), b"#14\x00\x00\x80\x3f\n");
+}
+
 #[test]
 fn test_stat_questionable() {
     let mut dev = TestDevice::new();
@@ -447,3 +1822,43 @@ fn test_stat_questionable() {
     .unwrap();
     assert_eq!(res.as_slice(), b"0;32767;0\n");
 }
+
+#[test]
+fn test_mandatory_tree() {
+    const TREE: Node<TestDevice> = scpi_mandatory!(b"GPA-Robotics", b"T800-101", b"0", b"0";
+        ieee488_sav!(),
+        ieee488_rcl!()
+    );
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"*IDN?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"GPA-Robotics,T800-101,0,0\n");
+
+    let res = util::test_execute_str(&TREE, b"*CLS;*ESE 1;*ESE?;*TST?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"1;0\n");
+
+    let res = util::test_execute_str(&TREE, b"STAT:PRES;:SYST:ERR?;VERS?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"0,\"No error\";1999.0\n");
+
+    // Device-specific nodes are spliced into the root
+    let res = util::test_execute_str(&TREE, b"*SAV 0;*RCL 0", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"");
+}
+
+#[test]
+fn test_idn_device() {
+    const TREE: Node<TestDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[ieee488_idn!()],
+    };
+    let mut dev = TestDevice::new();
+
+    let res = util::test_execute_str(&TREE, b"*IDN?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"GPA-Robotics,T800-101,0,0\n");
+
+    // Serial number read at runtime
+    dev.serial = b"SN1234";
+    let res = util::test_execute_str(&TREE, b"*IDN?", &mut dev).unwrap();
+    assert_eq!(res.as_slice(), b"GPA-Robotics,T800-101,SN1234,0\n");
+}
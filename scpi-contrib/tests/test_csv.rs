@@ -53,6 +53,22 @@ impl Command<util::TestDevice> for NumCommand {
     }
 }
 
+struct FreqCommand;
+impl Command<util::TestDevice> for FreqCommand {
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        _device: &mut util::TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let x: NumericValue<f32> = params.next_data()?;
+        response.data(x.resolve(1.0, 1e6, 1e3)?).finish()
+    }
+}
+
 struct ChannelListCommand;
 
 impl Command<util::TestDevice> for ChannelListCommand {
@@ -155,6 +171,11 @@ const IEEE488_TREE: Node<util::TestDevice> = Branch {
             default: false,
             handler: &NumCommand,
         },
+        Leaf {
+            name: b"*FREQ",
+            default: false,
+            handler: &FreqCommand,
+        },
         Leaf {
             name: b"*CHLIST",
             default: false,
@@ -0,0 +1,78 @@
+#![cfg(feature = "unit")]
+use scpi::{
+    option::ScpiEnum,
+    units::{
+        uom::si::{
+            electric_current::milliampere,
+            electric_potential::{microvolt, volt},
+            electrical_resistance::ohm,
+            power::{milliwatt, watt},
+        },
+        ElectricCurrent, ElectricPotential, ElectricalResistance, Power,
+    },
+};
+use scpi_contrib::scpi1999::unit::{CurrentUnit, PowerUnit, VoltageUnit};
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-4, "{a} != {b}");
+}
+
+#[test]
+fn test_power() {
+    assert_close(PowerUnit::Dbm.convert(Power::new::<milliwatt>(1.0)), 0.0);
+    assert_close(PowerUnit::Dbm.convert(Power::new::<watt>(1.0)), 30.0);
+    assert_close(PowerUnit::DbW.convert(Power::new::<watt>(0.1)), -10.0);
+    assert_close(PowerUnit::DbuW.convert(Power::new::<milliwatt>(1.0)), 30.0);
+    assert_close(PowerUnit::Watt.convert(Power::new::<watt>(2.5)), 2.5);
+
+    // 1 V RMS over 50 Ohm is 20 mW
+    let dbm = PowerUnit::Dbm.convert_voltage(
+        ElectricPotential::new::<volt>(1.0),
+        ElectricalResistance::new::<ohm>(50.0),
+    );
+    assert_close(dbm, 13.0103);
+
+    assert_eq!(
+        PowerUnit::Dbm.convert(Power::new::<watt>(0.0)),
+        f32::NEG_INFINITY
+    );
+    assert!(PowerUnit::Dbm.convert(Power::new::<watt>(-1.0)).is_nan());
+}
+
+#[test]
+fn test_voltage() {
+    assert_close(
+        VoltageUnit::DbV.convert(ElectricPotential::new::<volt>(10.0)),
+        20.0,
+    );
+    assert_close(
+        VoltageUnit::DbuV.convert(ElectricPotential::new::<microvolt>(1.0)),
+        0.0,
+    );
+    let dbuv = VoltageUnit::DbuV.convert_power(
+        Power::new::<milliwatt>(20.0),
+        ElectricalResistance::new::<ohm>(50.0),
+    );
+    assert_close(dbuv, 120.0);
+    assert!(VoltageUnit::DbmV
+        .convert_power(
+            Power::new::<watt>(-1.0),
+            ElectricalResistance::new::<ohm>(50.0)
+        )
+        .is_nan());
+}
+
+#[test]
+fn test_current() {
+    assert_close(
+        CurrentUnit::DbmA.convert(ElectricCurrent::new::<milliampere>(10.0)),
+        20.0,
+    );
+}
+
+#[test]
+fn test_mnemonic() {
+    assert_eq!(PowerUnit::from_mnemonic(b"dbmw"), Some(PowerUnit::Dbm));
+    assert_eq!(VoltageUnit::from_mnemonic(b"DBUV"), Some(VoltageUnit::DbuV));
+    assert_eq!(CurrentUnit::from_mnemonic(b"a"), Some(CurrentUnit::Ampere));
+}
@@ -1,9 +1,19 @@
 use std::{collections::VecDeque, path::Path};
 
-use scpi::{error::Result, tree::prelude::*};
+use scpi::{error::Result, parser::response::ByteOrder, tree::prelude::*};
 use serde::Deserialize;
 
-use scpi_contrib::{ieee488::prelude::*, scpi1999::prelude::*};
+use scpi_contrib::{
+    ieee488::prelude::*,
+    scpi1999::{
+        prelude::*,
+        status::{
+            instrument::{OperationInstrument, OperationInstrumentSummary},
+            questionable::QuestionableBits,
+        },
+        EventRegisterName,
+    },
+};
 
 // #[macro_export]
 // macro_rules! check_esr {
@@ -18,6 +28,30 @@ use scpi_contrib::{ieee488::prelude::*, scpi1999::prelude::*};
 //     };
 // }
 
+/// `SYSTem:COMMunicate` settings
+#[cfg(feature = "unproven")]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TestComm {
+    /// GPIB address
+    pub gpib_address: u8,
+    /// Serial baud rate
+    pub baud_rate: u32,
+    /// LAN IPv4 address
+    pub lan_address: [u8; 4],
+}
+
+/// `CALibration` state
+#[cfg(feature = "unproven")]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TestCalibration {
+    /// Current calibration step
+    pub step: u32,
+    /// Value given for each step
+    pub values: [f64; 2],
+    /// Values stored by `CALibration:SAVE`
+    pub saved: Option<[f64; 2]>,
+}
+
 pub(crate) struct TestDevice {
     /// Event Status Register
     pub esr: u8,
@@ -29,8 +63,96 @@ pub(crate) struct TestDevice {
     pub operation: EventRegister,
     /// QUEStionable:ENABle register
     pub questionable: EventRegister,
+    /// QUEStionable:INSTrument register
+    pub instrument: EventRegister,
+    /// OPERation:INSTrument register
+    pub oper_instrument: EventRegister,
+    /// OPERation:INSTrument:ISUMmary<N> registers of logical instrument 1 and 2
+    pub oper_isum: [EventRegister; 2],
+    /// STATus:QUEue:ENABle mask
+    pub queue_enable: u8,
     /// Error queue
-    pub errors: VecDeque<Error>,
+    pub errors: VecDeque<(Error, Option<Vec<u8>>)>,
+    /// Extended error prefix
+    pub error_prefix: Option<&'static [u8]>,
+    /// SCPI version
+    pub scpi_version: (u16, u8),
+    /// `*SAV` registers, only saves ESE
+    pub states: [Option<u8>; 4],
+    /// Self-test result code
+    pub tst_code: i16,
+    /// Serial number reported by `*IDN?`
+    pub serial: &'static [u8],
+    /// Number of `*WAI` barriers executed
+    pub waits: usize,
+    /// Number of pending operations, `None` if they never finish
+    pub pending: Option<usize>,
+    /// Power-on status clear flag
+    pub psc: bool,
+    /// Options reported by `*OPT?`
+    pub options: &'static [&'static [u8]],
+    /// Macro definitions
+    #[cfg(feature = "alloc")]
+    pub macros: scpi_contrib::ieee488::macros::MacroTable,
+    /// Command tree listed by `SYSTem:HELP:HEADers?`
+    #[cfg(feature = "alloc")]
+    pub help_tree: Option<&'static Node<'static, TestDevice>>,
+    /// Byte order set by `FORMat:BORDer`
+    pub byte_order: ByteOrder,
+    /// Advertised instrument classes
+    #[cfg(feature = "unproven")]
+    pub capabilities: &'static [&'static [u8]],
+    /// Sensor functions which are on, one bit per [TestFunction]
+    #[cfg(feature = "unproven")]
+    pub functions: u8,
+    /// Closed channels, one bit per channel
+    #[cfg(feature = "unproven")]
+    pub closed: u32,
+    /// Trigger system initiated
+    #[cfg(feature = "unproven")]
+    pub initiated: bool,
+    /// Continuous initiation on
+    #[cfg(feature = "unproven")]
+    pub continuous: bool,
+    /// Trigger source and number of triggers accepted
+    #[cfg(feature = "unproven")]
+    pub trigger: (
+        scpi_contrib::scpi1999::trigger::sequence::TriggerSource,
+        u32,
+    ),
+    /// `SYSTem:COMMunicate` settings
+    #[cfg(feature = "unproven")]
+    pub comm: TestComm,
+    /// `SYSTem:LFRequency` setting
+    #[cfg(feature = "unproven")]
+    pub line_frequency: scpi::units::Frequency,
+    /// `SENSe:VOLTage:RANGe:AUTO` setting
+    #[cfg(feature = "unproven")]
+    pub auto_range: scpi_contrib::scpi1999::util::Auto,
+    /// `SENSe:VOLTage:NPLCycles` setting
+    #[cfg(feature = "unproven")]
+    pub nplc: f32,
+    /// Output enabled and output interlock open
+    #[cfg(feature = "unproven")]
+    pub output: (bool, bool),
+    /// `SOURce:VOLTage` setting
+    #[cfg(all(feature = "unproven", feature = "unit"))]
+    pub voltage: scpi::units::ElectricPotential,
+    /// Display text and state
+    #[cfg(feature = "unproven")]
+    pub display: (String, bool),
+    /// Selected logical instrument
+    #[cfg(feature = "unproven")]
+    pub selected_instrument: usize,
+    /// Last diagnostic test and result
+    #[cfg(feature = "unproven")]
+    pub diagnostic: Option<(TestDiagnostic, i16)>,
+    /// `CALibration` state
+    #[cfg(feature = "unproven")]
+    pub calibration: TestCalibration,
+    /// Mass memory files, directories end with `/`
+    #[cfg(feature = "unproven")]
+    pub files: std::collections::BTreeMap<String, Vec<u8>>,
 }
 
 impl TestDevice {
@@ -41,7 +163,72 @@ impl TestDevice {
             sre: 0,
             operation: Default::default(),
             questionable: Default::default(),
+            instrument: Default::default(),
+            oper_instrument: Default::default(),
+            oper_isum: Default::default(),
+            queue_enable: 0xff,
             errors: Default::default(),
+            error_prefix: None,
+            scpi_version: (1999, 0),
+            states: [None; 4],
+            tst_code: 0,
+            serial: b"0",
+            waits: 0,
+            pending: Some(0),
+            psc: true,
+            options: &[],
+            #[cfg(feature = "alloc")]
+            macros: scpi_contrib::ieee488::macros::MacroTable::new(32),
+            #[cfg(feature = "alloc")]
+            help_tree: None,
+            byte_order: ByteOrder::Normal,
+            #[cfg(feature = "unproven")]
+            capabilities: &[],
+            #[cfg(feature = "unproven")]
+            functions: 0,
+            #[cfg(feature = "unproven")]
+            closed: 0,
+            #[cfg(feature = "unproven")]
+            initiated: false,
+            #[cfg(feature = "unproven")]
+            continuous: false,
+            #[cfg(feature = "unproven")]
+            trigger: (
+                scpi_contrib::scpi1999::trigger::sequence::TriggerSource::Immediate,
+                0,
+            ),
+            #[cfg(feature = "unproven")]
+            comm: TestComm {
+                gpib_address: 0,
+                baud_rate: 9600,
+                lan_address: [0; 4],
+            },
+            #[cfg(feature = "unproven")]
+            line_frequency: scpi::units::Frequency::new::<scpi::units::uom::si::frequency::hertz>(
+                50.0,
+            ),
+            #[cfg(feature = "unproven")]
+            auto_range: Default::default(),
+            #[cfg(feature = "unproven")]
+            nplc: 1.0,
+            #[cfg(feature = "unproven")]
+            output: (false, false),
+            #[cfg(all(feature = "unproven", feature = "unit"))]
+            voltage: Default::default(),
+            #[cfg(feature = "unproven")]
+            display: (String::new(), true),
+            #[cfg(feature = "unproven")]
+            selected_instrument: 1,
+            #[cfg(feature = "unproven")]
+            diagnostic: None,
+            #[cfg(feature = "unproven")]
+            calibration: TestCalibration {
+                step: 0,
+                values: [0.0; 2],
+                saved: None,
+            },
+            #[cfg(feature = "unproven")]
+            files: Default::default(),
         }
     }
 }
@@ -52,7 +239,531 @@ impl Device for TestDevice {
     }
 }
 
-impl ScpiDevice for TestDevice {}
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::system::capability::Capability for TestDevice {
+    fn capabilities(&self) -> &[&'static [u8]] {
+        self.capabilities
+    }
+}
+
+/// Only 50 Hz and 60 Hz are supported
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::system::lfrequency::LineFrequency for TestDevice {
+    fn set_line_frequency(&mut self, frequency: scpi::units::Frequency) -> Result<()> {
+        let hz = frequency.get::<scpi::units::uom::si::frequency::hertz>();
+        if hz == 50.0 || hz == 60.0 {
+            self.line_frequency = frequency;
+            Ok(())
+        } else {
+            Err(ErrorCode::IllegalParameterValue.into())
+        }
+    }
+
+    fn line_frequency(&self) -> scpi::units::Frequency {
+        self.line_frequency
+    }
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::trigger::initiate::Initiate for TestDevice {
+    fn initiate(&mut self) -> Result<()> {
+        self.initiated = true;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.initiated = false;
+        Ok(())
+    }
+
+    fn continuous(&self) -> bool {
+        self.continuous
+    }
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::trigger::initiate::Continuous for TestDevice {
+    fn set_continuous(&mut self, on: bool) -> Result<()> {
+        self.continuous = on;
+        self.initiated |= on;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::trigger::sequence::Trigger for TestDevice {
+    fn set_trigger_source(
+        &mut self,
+        source: scpi_contrib::scpi1999::trigger::sequence::TriggerSource,
+    ) -> Result<()> {
+        self.trigger.0 = source;
+        Ok(())
+    }
+
+    fn trigger_source(&self) -> scpi_contrib::scpi1999::trigger::sequence::TriggerSource {
+        self.trigger.0
+    }
+
+    fn trigger(&mut self) -> Result<()> {
+        self.trigger.1 += 1;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::system::communicate::GpibCommunicate for TestDevice {
+    fn gpib_address(&self) -> u8 {
+        self.comm.gpib_address
+    }
+
+    fn set_gpib_address(&mut self, addr: u8) -> Result<()> {
+        self.comm.gpib_address = addr;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::system::communicate::SerialCommunicate for TestDevice {
+    fn serial_baud(&self) -> u32 {
+        self.comm.baud_rate
+    }
+
+    fn set_serial_baud(&mut self, baud: u32) -> Result<()> {
+        if [9600, 115200].contains(&baud) {
+            self.comm.baud_rate = baud;
+            Ok(())
+        } else {
+            Err(ErrorCode::IllegalParameterValue.into())
+        }
+    }
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::system::communicate::LanCommunicate for TestDevice {
+    fn lan_address(&self) -> [u8; 4] {
+        self.comm.lan_address
+    }
+
+    fn set_lan_address(&mut self, addr: [u8; 4]) -> Result<()> {
+        self.comm.lan_address = addr;
+        Ok(())
+    }
+}
+
+/// Switch with 32 one-dimensional channels
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::route::Route for TestDevice {
+    fn route_close(
+        &mut self,
+        channel: scpi::parser::expression::channel_list::ChannelEntry,
+    ) -> Result<()> {
+        use scpi::parser::expression::channel_list::ChannelEntry;
+        match channel {
+            ChannelEntry::Single(ch) if ch < 32 => {
+                self.closed |= 1 << ch;
+                Ok(())
+            }
+            _ => Err(ErrorCode::IllegalParameterValue.into()),
+        }
+    }
+
+    fn route_open(
+        &mut self,
+        channel: scpi::parser::expression::channel_list::ChannelEntry,
+    ) -> Result<()> {
+        use scpi::parser::expression::channel_list::ChannelEntry;
+        match channel {
+            ChannelEntry::Single(ch) if ch < 32 => {
+                self.closed &= !(1 << ch);
+                Ok(())
+            }
+            _ => Err(ErrorCode::IllegalParameterValue.into()),
+        }
+    }
+
+    fn closed_channels(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..32).filter(|ch| self.closed & (1 << ch) != 0)
+    }
+}
+
+/// Two calibration steps
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::calibration::Calibration for TestDevice {
+    fn set_calibration_step(&mut self, step: u32) -> Result<()> {
+        if step >= 2 {
+            return Err(ErrorCode::IllegalParameterValue.into());
+        }
+        self.calibration.step = step;
+        Ok(())
+    }
+
+    fn calibration_step(&self) -> u32 {
+        self.calibration.step
+    }
+
+    fn set_calibration_value(&mut self, value: f64) -> Result<()> {
+        self.calibration.values[self.calibration.step as usize] = value;
+        Ok(())
+    }
+
+    fn save_calibration(&mut self) -> Result<()> {
+        self.calibration.saved = Some(self.calibration.values);
+        Ok(())
+    }
+}
+
+/// 64 bytes of mass memory
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::mmemory::MassMemory for TestDevice {
+    fn memory_usage(&self) -> (usize, usize) {
+        let used: usize = self.files.values().map(Vec::len).sum();
+        (used, 64 - used)
+    }
+
+    fn files(&self, directory: Option<&str>) -> Result<impl Iterator<Item = (&str, usize)> + '_> {
+        let prefix = match directory {
+            Some(directory) => format!("{directory}/"),
+            None => String::new(),
+        };
+        if !prefix.is_empty() && !self.files.contains_key(&prefix) {
+            return Err(ErrorCode::FileNameNotFound.into());
+        }
+        // Only list entries directly inside the directory
+        Ok(self.files.iter().filter_map(move |(name, data)| {
+            let name = name.strip_prefix(prefix.as_str())?;
+            (!name.is_empty() && !name.trim_end_matches('/').contains('/'))
+                .then_some((name, data.len()))
+        }))
+    }
+
+    fn file_size(&self, name: &str) -> Result<usize> {
+        self.files
+            .get(name)
+            .map(Vec::len)
+            .ok_or(ErrorCode::FileNameNotFound.into())
+    }
+
+    fn read_file(
+        &mut self,
+        name: &str,
+        out: &mut dyn scpi::parser::response::Formatter,
+    ) -> Result<()> {
+        let data = self.files.get(name).ok_or(ErrorCode::FileNameNotFound)?;
+        out.push_str(data)
+    }
+
+    fn write_file(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let (_, free) = self.memory_usage();
+        let old = self.files.get(name).map_or(0, Vec::len);
+        if data.len() > free + old {
+            return Err(ErrorCode::MassStorageError.into());
+        }
+        self.files.insert(name.into(), data.into());
+        Ok(())
+    }
+
+    fn delete_file(&mut self, name: &str) -> Result<()> {
+        self.files
+            .remove(name)
+            .map(|_| ())
+            .ok_or(ErrorCode::FileNameNotFound.into())
+    }
+
+    fn make_directory(&mut self, name: &str) -> Result<()> {
+        self.files.insert(format!("{name}/"), Vec::new());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "unproven")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scpi_derive::ScpiEnum)]
+pub(crate) enum TestDiagnostic {
+    #[scpi(mnemonic = b"RAM")]
+    Ram,
+    #[scpi(mnemonic = b"ROM")]
+    Rom,
+}
+
+/// RAM test passes, ROM test fails with code 3
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::diagnostic::Diagnostic for TestDevice {
+    type Test = TestDiagnostic;
+
+    fn run_test(&mut self, test: Self::Test) -> Result<i16> {
+        let code = match test {
+            TestDiagnostic::Ram => 0,
+            TestDiagnostic::Rom => 3,
+        };
+        self.diagnostic = Some((test, code));
+        Ok(code)
+    }
+
+    fn test_result(&self) -> Option<(Self::Test, i16)> {
+        self.diagnostic
+    }
+}
+
+#[cfg(feature = "unproven")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TestFunction {
+    VoltageDc = 0,
+    CurrentDc = 1,
+    Resistance = 2,
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::sense::function::SensorFunction for TestFunction {
+    const FUNCTIONS: &'static [Self] = &[Self::VoltageDc, Self::CurrentDc, Self::Resistance];
+
+    fn function(&self) -> &'static [u8] {
+        match self {
+            Self::VoltageDc => b"VOLTage:DC",
+            Self::CurrentDc => b"CURRent:DC",
+            Self::Resistance => b"RESistance",
+        }
+    }
+}
+
+/// Concurrent sensing of any combination of functions
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::sense::Sense for TestDevice {
+    type Function = TestFunction;
+
+    fn get_function_on(&self) -> Option<Self::Function> {
+        self.functions_on().next()
+    }
+
+    fn set_function_on(&mut self, function: Self::Function) -> Result<()> {
+        self.functions |= 1 << function as u8;
+        Ok(())
+    }
+
+    fn set_function_off(&mut self, function: Self::Function) -> Result<()> {
+        self.functions &= !(1 << function as u8);
+        Ok(())
+    }
+
+    fn functions_on(&self) -> impl Iterator<Item = Self::Function> + '_ {
+        use scpi_contrib::scpi1999::sense::function::SensorFunction;
+        TestFunction::FUNCTIONS
+            .iter()
+            .copied()
+            .filter(|f| self.functions & (1 << *f as u8) != 0)
+    }
+}
+
+impl scpi_contrib::scpi1999::format::Format for TestDevice {
+    fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    fn set_byte_order(&mut self, byte_order: ByteOrder) {
+        self.byte_order = byte_order;
+    }
+}
+
+/// Output cannot be enabled while the interlock is open
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::output::Output for TestDevice {
+    fn set_output(&mut self, on: bool) -> Result<()> {
+        if on && self.output.1 {
+            return Err(ErrorCode::SettingsConflict.into());
+        }
+        self.output.0 = on;
+        Ok(())
+    }
+
+    fn output(&self) -> bool {
+        self.output.0
+    }
+}
+
+/// Voltage source of 0-10 V, default 5 V
+#[cfg(all(feature = "unproven", feature = "unit"))]
+impl scpi_contrib::scpi1999::source::Source<scpi::units::ElectricPotential> for TestDevice {
+    fn set_source(&mut self, value: scpi::units::ElectricPotential) -> Result<()> {
+        self.voltage = value;
+        Ok(())
+    }
+
+    fn get_source(&self) -> scpi::units::ElectricPotential {
+        self.voltage
+    }
+
+    fn source_minimum(&self) -> scpi::units::ElectricPotential {
+        Default::default()
+    }
+
+    fn source_maximum(&self) -> scpi::units::ElectricPotential {
+        scpi::units::ElectricPotential::new::<scpi::units::uom::si::electric_potential::volt>(10.0)
+    }
+
+    fn source_default(&self) -> scpi::units::ElectricPotential {
+        scpi::units::ElectricPotential::new::<scpi::units::uom::si::electric_potential::volt>(5.0)
+    }
+
+    fn source_step(&self) -> Option<scpi::units::ElectricPotential> {
+        Some(scpi::units::ElectricPotential::new::<
+            scpi::units::uom::si::electric_potential::volt,
+        >(0.1))
+    }
+}
+
+/// Display fits 16 characters
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::display::Display for TestDevice {
+    fn set_display_text(&mut self, text: &str) -> Result<()> {
+        if text.len() > 16 {
+            return Err(ErrorCode::TooMuchData.into());
+        }
+        self.display.0 = text.into();
+        Ok(())
+    }
+
+    fn display_text(&self) -> &str {
+        &self.display.0
+    }
+
+    fn set_display_state(&mut self, on: bool) -> Result<()> {
+        self.display.1 = on;
+        Ok(())
+    }
+
+    fn display_state(&self) -> bool {
+        self.display.1
+    }
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::instrument::InstrumentSelect for TestDevice {
+    fn instruments(&self) -> &[&'static [u8]] {
+        &[b"PSU", b"DMM"]
+    }
+
+    fn select_instrument(&mut self, n: usize) -> Result<()> {
+        self.selected_instrument = n;
+        Ok(())
+    }
+
+    fn selected_instrument(&self) -> usize {
+        self.selected_instrument
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl scpi_contrib::ieee488::macros::MacroStore for TestDevice {
+    fn define_macro(&mut self, label: &[u8], contents: &[u8]) -> Result<()> {
+        self.macros.define_macro(label, contents)
+    }
+
+    fn get_macro(&self, label: &[u8]) -> Option<&[u8]> {
+        self.macros.get_macro(label)
+    }
+
+    fn macro_labels(&self) -> Vec<&[u8]> {
+        self.macros.macro_labels()
+    }
+
+    fn remove_macro(&mut self, label: &[u8]) -> Result<()> {
+        self.macros.remove_macro(label)
+    }
+
+    fn purge_macros(&mut self) {
+        self.macros.purge_macros()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl scpi_contrib::scpi1999::system::help::HelpHeaders for TestDevice {
+    fn command_tree(&self) -> &'static Node<'static, Self> {
+        self.help_tree.expect("No help tree")
+    }
+}
+
+/// Marker for the voltage sensor function
+#[cfg(feature = "unproven")]
+pub(crate) struct Voltage;
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::sense::range::RangeAuto<Voltage> for TestDevice {
+    fn set_auto_range(&mut self, auto: scpi_contrib::scpi1999::util::Auto) {
+        self.auto_range = auto;
+    }
+
+    fn auto_range(&self) -> scpi_contrib::scpi1999::util::Auto {
+        self.auto_range
+    }
+}
+
+#[cfg(feature = "unproven")]
+impl scpi_contrib::scpi1999::sense::nplc::Nplc<Voltage> for TestDevice {
+    fn set_nplc(&mut self, nplc: f32) -> Result<()> {
+        self.nplc = nplc;
+        Ok(())
+    }
+
+    fn nplc(&self) -> f32 {
+        self.nplc
+    }
+
+    fn nplc_minimum(&self) -> f32 {
+        0.02
+    }
+
+    fn nplc_maximum(&self) -> f32 {
+        100.0
+    }
+
+    fn nplc_default(&self) -> f32 {
+        1.0
+    }
+}
+
+/// Learn string is `*ESE <ese>;*SRE <sre>`, `SYST:SET` accepts a two byte block of ESE and SRE.
+impl scpi_contrib::ieee488::lrn::CommonLrn for TestDevice {
+    fn learn_string(&self, out: &mut dyn Formatter) -> Result<()> {
+        out.push_str(b"*ESE ")?;
+        self.ese.format_response_data(out)?;
+        out.push_str(b";*SRE ")?;
+        self.sre.format_response_data(out)
+    }
+
+    fn apply_set(&mut self, data: &[u8]) -> Result<()> {
+        match data {
+            [ese, sre] => {
+                self.ese = *ese;
+                self.sre = *sre;
+                Ok(())
+            }
+            _ => Err(ErrorCode::InvalidFormat.into()),
+        }
+    }
+}
+
+impl ScpiDevice for TestDevice {
+    fn error_extended_prefix(&self) -> Option<&'static [u8]> {
+        self.error_prefix
+    }
+
+    fn scpi_version(&self) -> (u16, u8) {
+        self.scpi_version
+    }
+
+    fn queue_enable(&self) -> u8 {
+        self.queue_enable
+    }
+
+    fn set_queue_enable(&mut self, mask: u8) -> Result<()> {
+        self.queue_enable = mask;
+        Ok(())
+    }
+
+    fn custom_registers(&mut self) -> impl Iterator<Item = &mut EventRegister> + '_ {
+        core::iter::once(&mut self.instrument)
+            .chain(core::iter::once(&mut self.oper_instrument))
+            .chain(self.oper_isum.iter_mut())
+    }
+}
 
 impl IEEE4882 for TestDevice {
     fn stb(&self) -> u8 {
@@ -105,6 +816,43 @@ impl IEEE4882 for TestDevice {
         Ok(())
     }
 
+    fn tst_code(&mut self) -> Result<i16> {
+        Ok(self.tst_code)
+    }
+
+    /// Fails unless calibration data has been saved
+    #[cfg(feature = "unproven")]
+    fn calibrate(&mut self) -> Result<i16> {
+        // Cannot calibrate while operations are running
+        if self.pending_operations() {
+            return Err(ErrorCode::ExecutionError.into());
+        }
+        self.calibration
+            .saved
+            .map(|_| 0)
+            .ok_or(ErrorCode::CalibrationMemoryLost.into())
+    }
+
+    fn wait(&mut self) -> Result<()> {
+        self.waits += 1;
+        // Times out if the operations never finish
+        match self.pending {
+            Some(_) => {
+                self.pending = Some(0);
+                Ok(())
+            }
+            None => Err(ErrorCode::ExecutionError.into()),
+        }
+    }
+
+    fn pending_operations(&self) -> bool {
+        self.pending != Some(0)
+    }
+
+    fn identification(&self) -> (&[u8], &[u8], &[u8], &[u8]) {
+        (b"GPA-Robotics", b"T800-101", self.serial, b"0")
+    }
+
     fn rst(&mut self) -> Result<()> {
         Ok(())
     }
@@ -116,15 +864,42 @@ impl IEEE4882 for TestDevice {
     fn opc(&mut self) -> Result<()> {
         self.scpi_opc()
     }
+
+    fn state_slots(&self) -> Option<usize> {
+        Some(self.states.len())
+    }
+
+    fn save_state(&mut self, slot: usize) -> Result<()> {
+        self.states[slot] = Some(self.ese);
+        Ok(())
+    }
+
+    fn recall_state(&mut self, slot: usize) -> Result<()> {
+        self.ese = self.states[slot].ok_or(ErrorCode::ExecutionError)?;
+        Ok(())
+    }
+
+    fn psc(&self) -> bool {
+        self.psc
+    }
+
+    fn set_psc(&mut self, on: bool) -> Result<()> {
+        self.psc = on;
+        Ok(())
+    }
+
+    fn options(&self) -> &[&'static [u8]] {
+        self.options
+    }
 }
 
 impl ErrorQueue for TestDevice {
     fn push_back_error(&mut self, err: Error) {
-        self.errors.push_back(err);
+        self.errors.push_back((err, None));
     }
 
     fn pop_front_error(&mut self) -> Option<Error> {
-        self.errors.pop_front()
+        self.errors.pop_front().map(|(err, _)| err)
     }
 
     fn num_errors(&self) -> usize {
@@ -134,6 +909,16 @@ impl ErrorQueue for TestDevice {
     fn clear_errors(&mut self) {
         self.errors.clear()
     }
+
+    #[cfg(feature = "alloc")]
+    fn push_back_error_message(&mut self, err: Error, msg: Vec<u8>) {
+        self.errors.push_back((err, Some(msg)));
+    }
+
+    #[cfg(feature = "alloc")]
+    fn pop_front_error_message(&mut self) -> Option<(Error, Option<Vec<u8>>)> {
+        self.errors.pop_front()
+    }
 }
 
 impl GetEventRegister<Questionable> for TestDevice {
@@ -156,6 +941,47 @@ impl GetEventRegister<Operation> for TestDevice {
     }
 }
 
+/// `STATus:QUEStionable:INSTrument` register
+pub(crate) struct Instrument;
+
+impl EventRegisterName for Instrument {
+    type BitFlags = ();
+}
+
+impl SummaryRegister<Questionable> for Instrument {
+    const SUMMARY_BIT: u16 = QuestionableBits::InstrumentSummary as u16;
+}
+
+impl GetEventRegister<Instrument> for TestDevice {
+    fn register(&self) -> &EventRegister {
+        &self.instrument
+    }
+
+    fn register_mut(&mut self) -> &mut EventRegister {
+        &mut self.instrument
+    }
+}
+
+impl GetEventRegister<OperationInstrument> for TestDevice {
+    fn register(&self) -> &EventRegister {
+        &self.oper_instrument
+    }
+
+    fn register_mut(&mut self) -> &mut EventRegister {
+        &mut self.oper_instrument
+    }
+}
+
+impl<const N: u16> GetEventRegister<OperationInstrumentSummary<N>> for TestDevice {
+    fn register(&self) -> &EventRegister {
+        &self.oper_isum[N as usize - 1]
+    }
+
+    fn register_mut(&mut self) -> &mut EventRegister {
+        &mut self.oper_isum[N as usize - 1]
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 struct Record {
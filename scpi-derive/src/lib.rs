@@ -14,6 +14,9 @@ use syn::{parse_macro_input, Data, DeriveInput, LitByteStr, LitInt};
 /// Derive the necessary logic to convert a enum to and from a mnemonic.
 ///
 /// For each variant we look into the attributes looking for entry of the form #[scpi(mnemonic=b""")]
+/// and any number of #[scpi(alias=b"")] which are also accepted when parsing.
+///
+/// One variant may be marked with #[scpi(default)] to also generate an `impl Default` returning it.
 #[proc_macro_derive(ScpiEnum, attributes(scpi))]
 pub fn derive_scpi_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree.
@@ -29,57 +32,121 @@ pub fn derive_scpi_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 
     let mut from_mnemonic_matches = Vec::new();
     let mut to_mnemonic_matches = Vec::new();
+    let mut default_variant: Option<proc_macro2::TokenStream> = None;
+    let mut mnemonic_checks = Vec::new();
 
     // Iter over the enum variants
     for variant in variants {
         let variant_name = &variant.ident;
 
+        let mut mnemonic: Option<LitByteStr> = None;
+        let mut aliases: Vec<LitByteStr> = Vec::new();
+        let mut is_default = false;
+
         // Iter over the attributes (#[repr]) of the variant
         for attr in variant.attrs.iter() {
             // We are interested only in 'scpi' attributes
             if attr.path().is_ident("scpi") {
-                attr.parse_nested_meta(|meta| {
-
+                let res = attr.parse_nested_meta(|meta| {
                     // For scpi attributes we look for a mnemonic name value pair as literal byte string
                     if meta.path.is_ident("mnemonic") {
-                        let mnemonic: LitByteStr = meta.value()?.parse()?;
-
-                        // We build a token stream to implement the enum creation from a mnemonic
-                        let x = match &variant.fields {
-                            syn::Fields::Unnamed(x) if x.unnamed.len() == 1 => quote! {
-                                x if scpi::parser::mnemonic_match(#mnemonic, x) => Some(#name::#variant_name(Default::default()))
-                            },
-                            syn::Fields::Unit => quote! {
-                                x if scpi::parser::mnemonic_match(#mnemonic, x) => Some(#name::#variant_name)
-                            },
-                            _ => quote_spanned! {
-                                variant_name.span() => compile_error!("Variant must be unit or single unnamed field implementing default")
-                            },
-                        };
-                        from_mnemonic_matches.push(x);
-
-                        // We build a token stream to implement the enum conversion to a mnemonic
-                        let mnemonic_return = LitByteStr::new(&mnemonic.value(), variant_name.span());
-
-                        let x2 = match &variant.fields {
-                            syn::Fields::Unnamed(x) if x.unnamed.len() == 1 => quote! {
-                                #name::#variant_name(..) => #mnemonic_return
-                            },
-                            syn::Fields::Unit => quote! {
-                                #name::#variant_name => #mnemonic_return
-                            },
-                            _ => quote_spanned! {
-                                variant_name.span() => compile_error!("Variant must be unit or single unnamed field implementing default")
-                            },
-                        };
-                        to_mnemonic_matches.push(x2);
-                    };
+                        let lit: LitByteStr = meta.value()?.parse()?;
+                        validate_mnemonic(&lit)?;
+                        mnemonic_checks.push(mnemonic_check(&lit));
+                        mnemonic = Some(lit);
+                    }
+                    // Additional mnemonics accepted when parsing but never returned by `mnemonic()`
+                    else if meta.path.is_ident("alias") {
+                        let lit: LitByteStr = meta.value()?.parse()?;
+                        validate_mnemonic(&lit)?;
+                        mnemonic_checks.push(mnemonic_check(&lit));
+                        aliases.push(lit);
+                    }
+                    // Variant returned by a generated `Default` impl
+                    else if meta.path.is_ident("default") {
+                        is_default = true;
+                    }
                     Ok(())
-                }).unwrap()
+                });
+                if let Err(err) = res {
+                    return err.to_compile_error().into();
+                }
             }
         }
+
+        if is_default {
+            if default_variant.is_some() {
+                return quote_spanned! {
+                    variant_name.span() => compile_error!("Only one variant may be marked as default");
+                }
+                .into();
+            }
+            default_variant = Some(match &variant.fields {
+                syn::Fields::Unnamed(x) if x.unnamed.len() == 1 => quote! {
+                    #name::#variant_name(Default::default())
+                },
+                syn::Fields::Unit => quote! {
+                    #name::#variant_name
+                },
+                _ => quote_spanned! {
+                    variant_name.span() => compile_error!("Variant must be unit or single unnamed field implementing default")
+                },
+            });
+        }
+
+        let mnemonic = match mnemonic {
+            Some(mnemonic) => mnemonic,
+            None if !aliases.is_empty() => {
+                return quote_spanned! {
+                    variant_name.span() => compile_error!("Alias requires a primary mnemonic");
+                }
+                .into()
+            }
+            None => continue,
+        };
+
+        // We build a token stream to implement the enum creation from a mnemonic (or any of its aliases)
+        let x = match &variant.fields {
+            syn::Fields::Unnamed(x) if x.unnamed.len() == 1 => quote! {
+                x if scpi::parser::mnemonic_match(#mnemonic, x) #(|| scpi::parser::mnemonic_match(#aliases, x))* => Some(#name::#variant_name(Default::default()))
+            },
+            syn::Fields::Unit => quote! {
+                x if scpi::parser::mnemonic_match(#mnemonic, x) #(|| scpi::parser::mnemonic_match(#aliases, x))* => Some(#name::#variant_name)
+            },
+            _ => quote_spanned! {
+                variant_name.span() => compile_error!("Variant must be unit or single unnamed field implementing default")
+            },
+        };
+        from_mnemonic_matches.push(x);
+
+        // We build a token stream to implement the enum conversion to a mnemonic
+        let mnemonic_return = LitByteStr::new(&mnemonic.value(), variant_name.span());
+
+        let x2 = match &variant.fields {
+            syn::Fields::Unnamed(x) if x.unnamed.len() == 1 => quote! {
+                #name::#variant_name(..) => #mnemonic_return
+            },
+            syn::Fields::Unit => quote! {
+                #name::#variant_name => #mnemonic_return
+            },
+            _ => quote_spanned! {
+                variant_name.span() => compile_error!("Variant must be unit or single unnamed field implementing default")
+            },
+        };
+        to_mnemonic_matches.push(x2);
     }
 
+    // Only generate a Default impl if asked to, the user may derive or implement it themselves
+    let default_impl = default_variant.map(|variant| {
+        quote! {
+            impl Default for #name {
+                fn default() -> Self {
+                    #variant
+                }
+            }
+        }
+    });
+
     // Generated the impl from the collected token streams
     let expanded = quote! {
         // The generated impl.
@@ -110,12 +177,37 @@ pub fn derive_scpi_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStre
                 }
             }
         }
+
+        #default_impl
+
+        #(#mnemonic_checks)*
     };
 
     // Hand the output tokens back to the compiler.
     proc_macro::TokenStream::from(expanded)
 }
 
+/// Reject empty mnemonics, the remaining rules are checked at compile time by
+/// `scpi::parser::validate_mnemonic` (which cannot be called here as `scpi` depends on this crate),
+/// see [mnemonic_check].
+fn validate_mnemonic(lit: &LitByteStr) -> syn::Result<()> {
+    if lit.value().is_empty() {
+        return Err(syn::Error::new_spanned(lit, "Mnemonic must not be empty"));
+    }
+    Ok(())
+}
+
+/// Const assertion that a mnemonic is of the form `SHORTlong[<N>]` and not longer than 12 characters.
+/// See IEEE 488.2-1992 7.6.1
+fn mnemonic_check(lit: &LitByteStr) -> proc_macro2::TokenStream {
+    quote_spanned! {
+        lit.span() => const _: () = assert!(
+            scpi::parser::validate_mnemonic(#lit),
+            "Mnemonic must be of the form `SHORTlong` with at most 12 alphanumeric characters or '_'"
+        );
+    }
+}
+
 /// Internal macro for scpi crate use only.
 #[cfg(feature = "_private")]
 #[proc_macro_derive(ScpiError, attributes(error))]
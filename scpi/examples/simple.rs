@@ -55,8 +55,8 @@ fn main() {
         let res = MYTREE.run(&command, &mut device, &mut context, &mut response);
 
         // Print response
-        if let Ok(_) = res {
-            stdout.write(&response).unwrap();
+        if res.is_ok() {
+            stdout.write_all(&response).unwrap();
         }
     }
 }
@@ -8,7 +8,7 @@ use scpi::parser::expression::channel_list::{ChannelList};
 
 fuzz_target!(|data: &[u8]| {
     // fuzzed code goes here
-    if let Some(mut channels) = ChannelList::new(data) {
+    if let Ok(mut channels) = ChannelList::new(data) {
         while let Some(Ok(_)) = channels.next() { }
     }
 });
\ No newline at end of file
@@ -74,6 +74,11 @@ impl Error {
     pub fn esr_mask(&self) -> u8 {
         self.0.esr_mask()
     }
+
+    /// Returns the class of this event/error, see [ErrorCode::category].
+    pub fn category(&self) -> ErrorCategory {
+        self.0.category()
+    }
 }
 
 impl PartialEq<ErrorCode> for Error {
@@ -993,23 +998,90 @@ impl ErrorCode {
      * Returns a bitmask for the appropriate bit in the ESR for this event/error.
      */
     pub fn esr_mask(&self) -> u8 {
+        self.category().esr_mask()
+    }
+
+    /// Returns the class of this event/error, derived from the code range.
+    ///
+    /// ```
+    /// # use scpi::error::{ErrorCode, ErrorCategory};
+    /// assert_eq!(ErrorCode::DataOutOfRange.category(), ErrorCategory::Execution);
+    /// assert_eq!(ErrorCode::Custom(1, b"Test").category(), ErrorCategory::DeviceSpecific);
+    /// ```
+    pub fn category(&self) -> ErrorCategory {
         match self.get_code() {
-            -99..=0 => 0u8,        //No bit
-            -199..=-100 => 0x20u8, //bit 5
-            -299..=-200 => 0x10u8, //bit 4
-            -399..=-300 => 0x08u8, //bit 3
-            -499..=-400 => 0x04u8, //bit 2
-            -599..=-500 => 0x80u8, //bit 7
-            -699..=-600 => 0x40u8, //bit 6
-            -799..=-700 => 0x02u8, //bit 1
-            -899..=-800 => 0x01u8, //bit 0
-            _ => 0x08u8,           //bit 3
+            -99..=0 => ErrorCategory::NoError,
+            -199..=-100 => ErrorCategory::Command,
+            -299..=-200 => ErrorCategory::Execution,
+            -399..=-300 => ErrorCategory::DeviceSpecific,
+            -499..=-400 => ErrorCategory::Query,
+            -599..=-500 => ErrorCategory::PowerOn,
+            -699..=-600 => ErrorCategory::UserRequest,
+            -799..=-700 => ErrorCategory::RequestControl,
+            -899..=-800 => ErrorCategory::OperationComplete,
+            _ => ErrorCategory::DeviceSpecific,
         }
     }
 
     pub fn extended(self, msg: &'static [u8]) -> Error {
         Error::new(self).extended(msg)
     }
+
+    /// Get the standard error with the given code, Eg. when decoding a `SYSTem:ERRor?` response.
+    ///
+    /// Returns [None] for codes without a standard error, including device-specific codes used by
+    /// [ErrorCode::Custom] as the message cannot be recovered from the code alone.
+    ///
+    /// ```
+    /// # use scpi::error::ErrorCode;
+    /// assert_eq!(ErrorCode::from_code(-222), Some(ErrorCode::DataOutOfRange));
+    /// assert_eq!(ErrorCode::from_code(1), None);
+    /// ```
+    pub fn from_code(code: i16) -> Option<Self> {
+        Self::get_error(code)
+    }
+}
+
+/// Class of an event/error, see [ErrorCode::category].
+///
+/// Each class (except [ErrorCategory::NoError]) sets a bit in the ESR, see [ErrorCategory::esr_mask].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ErrorCategory {
+    /// No error (`0`) or reserved (`-99..=-1`)
+    NoError,
+    /// Command error (`-100..=-199`)
+    Command,
+    /// Execution error (`-200..=-299`)
+    Execution,
+    /// Device-specific error (`-300..=-399` and positive codes)
+    DeviceSpecific,
+    /// Query error (`-400..=-499`)
+    Query,
+    /// Power on event (`-500..=-599`)
+    PowerOn,
+    /// User request event (`-600..=-699`)
+    UserRequest,
+    /// Request control event (`-700..=-799`)
+    RequestControl,
+    /// Operation complete event (`-800..=-899`)
+    OperationComplete,
+}
+
+impl ErrorCategory {
+    /// Returns a bitmask for the bit in the ESR set by this class of events/errors.
+    pub fn esr_mask(&self) -> u8 {
+        match self {
+            ErrorCategory::NoError => 0u8,              //No bit
+            ErrorCategory::Command => 0x20u8,           //bit 5
+            ErrorCategory::Execution => 0x10u8,         //bit 4
+            ErrorCategory::DeviceSpecific => 0x08u8,    //bit 3
+            ErrorCategory::Query => 0x04u8,             //bit 2
+            ErrorCategory::PowerOn => 0x80u8,           //bit 7
+            ErrorCategory::UserRequest => 0x40u8,       //bit 6
+            ErrorCategory::RequestControl => 0x02u8,    //bit 1
+            ErrorCategory::OperationComplete => 0x01u8, //bit 0
+        }
+    }
 }
 
 /// Generic error queue trait
@@ -1032,11 +1104,117 @@ pub trait ErrorQueue {
     fn is_empty(&self) -> bool {
         self.num_errors() == 0
     }
+
+    /// Add a error with a runtime extended message to the queue, Eg. from [alloc::format!].
+    /// The message replaces any extended message of the error itself.
+    ///
+    /// Queues which cannot store messages (the default) add the error without it,
+    /// see [MessageErrorQueue] for one which can.
+    #[cfg(feature = "alloc")]
+    fn push_back_error_message(&mut self, err: Error, _msg: alloc::vec::Vec<u8>) {
+        self.push_back_error(err)
+    }
+
+    /// Get a error and its runtime extended message (if any) from the queue,
+    /// see [Self::push_back_error_message].
+    #[cfg(feature = "alloc")]
+    fn pop_front_error_message(&mut self) -> Option<(Error, Option<alloc::vec::Vec<u8>>)> {
+        self.pop_front_error().map(|err| (err, None))
+    }
 }
 
 /// Error queue based on a alloc-less [arrayvec::ArrayVec].
+///
+/// Same behaviour as the [ErrorQueue] implementation for [arrayvec::ArrayVec] but also keeps
+/// track of whether any error has been dropped since the queue was last cleared.
+///
+/// The queued errors are readable through [Deref](core::ops::Deref) and it converts to and from
+/// an [arrayvec::ArrayVec] with [From].
+/// ```
+/// # use scpi::error::{ArrayErrorQueue, ErrorCode, ErrorQueue};
+/// let mut errors = ArrayErrorQueue::<4>::new();
+/// errors.push_back_error(ErrorCode::Custom(1, b"One").into());
+/// assert_eq!(errors.len(), 1);
+/// let errors: arrayvec::ArrayVec<_, 4> = errors.into();
+/// assert_eq!(errors.len(), 1);
+/// ```
+#[cfg(feature = "arrayvec")]
+#[derive(Debug, Clone, Default)]
+pub struct ArrayErrorQueue<const CAP: usize> {
+    queue: arrayvec::ArrayVec<Error, CAP>,
+    overflowed: bool,
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const CAP: usize> ArrayErrorQueue<CAP> {
+    /// Create a new empty queue
+    pub const fn new() -> Self {
+        Self {
+            queue: arrayvec::ArrayVec::new_const(),
+            overflowed: false,
+        }
+    }
+
+    /// Maximum number of errors in queue, including the [ErrorCode::QueueOverflow] error.
+    pub fn capacity(&self) -> usize {
+        CAP
+    }
+
+    /// Returns true if a error has been dropped because the queue was full.
+    /// Cleared by [ErrorQueue::clear_errors].
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const CAP: usize> core::ops::Deref for ArrayErrorQueue<CAP> {
+    type Target = arrayvec::ArrayVec<Error, CAP>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.queue
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const CAP: usize> From<arrayvec::ArrayVec<Error, CAP>> for ArrayErrorQueue<CAP> {
+    fn from(queue: arrayvec::ArrayVec<Error, CAP>) -> Self {
+        Self {
+            queue,
+            overflowed: false,
+        }
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const CAP: usize> From<ArrayErrorQueue<CAP>> for arrayvec::ArrayVec<Error, CAP> {
+    fn from(queue: ArrayErrorQueue<CAP>) -> Self {
+        queue.queue
+    }
+}
+
 #[cfg(feature = "arrayvec")]
-pub type ArrayErrorQueue<const CAP: usize> = arrayvec::ArrayVec<Error, CAP>;
+impl<const CAP: usize> ErrorQueue for ArrayErrorQueue<CAP> {
+    fn push_back_error(&mut self, err: Error) {
+        if self.queue.is_full() {
+            self.overflowed = true;
+        }
+        self.queue.push_back_error(err)
+    }
+
+    fn pop_front_error(&mut self) -> Option<Error> {
+        self.queue.pop_front_error()
+    }
+
+    fn num_errors(&self) -> usize {
+        self.queue.num_errors()
+    }
+
+    fn clear_errors(&mut self) {
+        self.overflowed = false;
+        self.queue.clear_errors()
+    }
+}
 
 #[cfg(feature = "arrayvec")]
 impl<const CAP: usize> ErrorQueue for arrayvec::ArrayVec<Error, CAP> {
@@ -1089,6 +1267,303 @@ impl ErrorQueue for alloc::vec::Vec<Error> {
     }
 }
 
+/// Error queue based on [alloc::vec::Vec] which also stores runtime extended messages,
+/// see [ErrorQueue::push_back_error_message].
+///
+/// The [Error] type only holds `&'static` extended messages so that it can be copied freely,
+/// runtime messages are instead owned by the queue until read.
+#[cfg(feature = "alloc")]
+pub type MessageErrorQueue = alloc::vec::Vec<(Error, Option<alloc::vec::Vec<u8>>)>;
+
+#[cfg(feature = "alloc")]
+impl ErrorQueue for alloc::vec::Vec<(Error, Option<alloc::vec::Vec<u8>>)> {
+    fn push_back_error(&mut self, err: Error) {
+        self.push((err, None))
+    }
+
+    fn pop_front_error(&mut self) -> Option<Error> {
+        self.pop_front_error_message().map(|(err, _)| err)
+    }
+
+    fn num_errors(&self) -> usize {
+        self.len()
+    }
+
+    fn clear_errors(&mut self) {
+        self.clear()
+    }
+
+    fn push_back_error_message(&mut self, err: Error, msg: alloc::vec::Vec<u8>) {
+        self.push((err, Some(msg)))
+    }
+
+    fn pop_front_error_message(&mut self) -> Option<(Error, Option<alloc::vec::Vec<u8>>)> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+}
+
+/// Error queue adapter which merges consecutive identical errors into one entry.
+///
+/// When a pushed error equals the most recently queued one, a repeat counter is incremented
+/// instead of queueing it again. This keeps a flood of the same error from filling the queue
+/// and hiding other errors. Errors pushed with a runtime message are always queued.
+///
+/// If enabled by [Self::report_count], [ErrorQueue::pop_front_error_message] reports the number of
+/// repeats in the extended message, e.g. `-222,"Data out of range;repeated 5 times"`.
+///
+/// ```
+/// # use scpi::error::{DedupErrorQueue, Error, ErrorCode, ErrorQueue};
+/// let mut errors = DedupErrorQueue::new(Vec::<Error>::new());
+/// for _ in 0..5 {
+///     errors.push_back_error(ErrorCode::DataOutOfRange.into());
+/// }
+/// assert_eq!(errors.num_errors(), 1);
+/// assert_eq!(errors.repeats(), 5);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DedupErrorQueue<Q> {
+    queue: Q,
+    /// Last queued error if it may be merged with new ones
+    last: Option<Error>,
+    /// Number of repeats of the last queued error
+    repeats: usize,
+    /// Number of repeats of the other queued errors
+    #[cfg(feature = "alloc")]
+    counts: alloc::collections::VecDeque<usize>,
+    report_count: bool,
+}
+
+impl<Q> DedupErrorQueue<Q>
+where
+    Q: ErrorQueue,
+{
+    /// Wrap a queue
+    pub fn new(queue: Q) -> Self {
+        Self {
+            queue,
+            last: None,
+            repeats: 0,
+            #[cfg(feature = "alloc")]
+            counts: Default::default(),
+            report_count: false,
+        }
+    }
+
+    /// Report the number of repeats in the extended message of popped errors.
+    pub fn report_count(mut self, enable: bool) -> Self {
+        self.report_count = enable;
+        self
+    }
+
+    /// Number of times the most recently queued error has been pushed, 0 if none.
+    pub fn repeats(&self) -> usize {
+        if self.queue.is_empty() {
+            0
+        } else {
+            self.repeats
+        }
+    }
+
+    /// Get the wrapped queue
+    pub fn into_inner(self) -> Q {
+        self.queue
+    }
+
+    /// Queue a new entry, returns false if the queue overflowed.
+    fn push_entry(&mut self, push: impl FnOnce(&mut Q)) -> bool {
+        let len = self.queue.num_errors();
+        push(&mut self.queue);
+        if self.queue.num_errors() > len {
+            #[cfg(feature = "alloc")]
+            if len > 0 {
+                self.counts.push_back(self.repeats);
+            }
+            self.repeats = 1;
+            true
+        } else {
+            // Last error was replaced by a QueueOverflow error
+            self.repeats = 1;
+            false
+        }
+    }
+
+    /// Returns the number of repeats of the front error.
+    fn pop_repeats(&mut self) -> usize {
+        if self.queue.num_errors() <= 1 {
+            self.last = None;
+            core::mem::take(&mut self.repeats)
+        } else {
+            #[cfg(feature = "alloc")]
+            return self.counts.pop_front().unwrap_or(1);
+            #[cfg(not(feature = "alloc"))]
+            1
+        }
+    }
+}
+
+impl<Q> ErrorQueue for DedupErrorQueue<Q>
+where
+    Q: ErrorQueue,
+{
+    fn push_back_error(&mut self, err: Error) {
+        if self.last == Some(err) {
+            self.repeats += 1;
+        } else if self.push_entry(|queue| queue.push_back_error(err)) {
+            self.last = Some(err);
+        } else {
+            self.last = None;
+        }
+    }
+
+    fn pop_front_error(&mut self) -> Option<Error> {
+        self.pop_repeats();
+        self.queue.pop_front_error()
+    }
+
+    fn num_errors(&self) -> usize {
+        self.queue.num_errors()
+    }
+
+    fn clear_errors(&mut self) {
+        self.last = None;
+        self.repeats = 0;
+        #[cfg(feature = "alloc")]
+        self.counts.clear();
+        self.queue.clear_errors()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn push_back_error_message(&mut self, err: Error, msg: alloc::vec::Vec<u8>) {
+        self.push_entry(|queue| queue.push_back_error_message(err, msg));
+        self.last = None;
+    }
+
+    #[cfg(feature = "alloc")]
+    fn pop_front_error_message(&mut self) -> Option<(Error, Option<alloc::vec::Vec<u8>>)> {
+        let repeats = self.pop_repeats();
+        let (err, msg) = self.queue.pop_front_error_message()?;
+        if self.report_count && repeats > 1 {
+            let mut msg = msg
+                .or_else(|| err.get_extended().map(<[u8]>::to_vec))
+                .unwrap_or_default();
+            if !msg.is_empty() {
+                msg.push(b';');
+            }
+            msg.extend_from_slice(alloc::format!("repeated {repeats} times").as_bytes());
+            Some((err, Some(msg)))
+        } else {
+            Some((err, msg))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test_dedup_error_queue {
+    use super::*;
+
+    #[test]
+    fn test_dedup() {
+        let mut errors = DedupErrorQueue::new(VecErrorQueue::new());
+        for _ in 0..5 {
+            errors.push_back_error(ErrorCode::DataOutOfRange.into());
+        }
+        errors.push_back_error(ErrorCode::ExecutionError.into());
+        errors.push_back_error(ErrorCode::DataOutOfRange.into());
+        assert_eq!(errors.num_errors(), 3);
+        assert_eq!(
+            errors.pop_front_error(),
+            Some(Error::new(ErrorCode::DataOutOfRange))
+        );
+        assert_eq!(
+            errors.pop_front_error(),
+            Some(Error::new(ErrorCode::ExecutionError))
+        );
+        assert_eq!(errors.repeats(), 1);
+        assert_eq!(
+            errors.pop_front_error(),
+            Some(Error::new(ErrorCode::DataOutOfRange))
+        );
+        assert_eq!(errors.repeats(), 0);
+
+        // Popped errors are not merged with new ones
+        errors.push_back_error(ErrorCode::DataOutOfRange.into());
+        assert_eq!(errors.repeats(), 1);
+    }
+
+    #[test]
+    fn test_report_count() {
+        let mut errors = DedupErrorQueue::new(MessageErrorQueue::new()).report_count(true);
+        for _ in 0..5 {
+            errors.push_back_error(Error::new(ErrorCode::DataOutOfRange).extended(b"VOLT"));
+        }
+        errors.push_back_error(ErrorCode::ExecutionError.into());
+        errors.push_back_error(ErrorCode::ExecutionError.into());
+        errors.push_back_error_message(ErrorCode::ExecutionError.into(), b"msg".to_vec());
+        assert_eq!(errors.num_errors(), 3);
+        assert_eq!(
+            errors.pop_front_error_message(),
+            Some((
+                Error::new(ErrorCode::DataOutOfRange).extended(b"VOLT"),
+                Some(b"VOLT;repeated 5 times".to_vec())
+            ))
+        );
+        assert_eq!(
+            errors.pop_front_error_message(),
+            Some((
+                Error::new(ErrorCode::ExecutionError),
+                Some(b"repeated 2 times".to_vec())
+            ))
+        );
+        assert_eq!(
+            errors.pop_front_error_message(),
+            Some((Error::new(ErrorCode::ExecutionError), Some(b"msg".to_vec())))
+        );
+        assert_eq!(errors.pop_front_error_message(), None);
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test_message_error_queue {
+    use super::*;
+
+    #[test]
+    fn test_message() {
+        let mut queue = MessageErrorQueue::new();
+        queue.push_back_error(Error::new(ErrorCode::CommandError));
+        queue.push_back_error_message(
+            Error::new(ErrorCode::FileNameNotFound),
+            alloc::format!("{}.csv", 42).into_bytes(),
+        );
+        assert_eq!(queue.num_errors(), 2);
+        assert_eq!(
+            queue.pop_front_error_message(),
+            Some((Error::new(ErrorCode::CommandError), None))
+        );
+        assert_eq!(
+            queue.pop_front_error_message(),
+            Some((
+                Error::new(ErrorCode::FileNameNotFound),
+                Some(b"42.csv".to_vec())
+            ))
+        );
+        assert_eq!(queue.pop_front_error_message(), None);
+    }
+
+    #[test]
+    fn test_default_drops_message() {
+        let mut queue = VecErrorQueue::new();
+        queue.push_back_error_message(Error::new(ErrorCode::ExecutionError), b"msg".to_vec());
+        assert_eq!(
+            queue.pop_front_error_message(),
+            Some((Error::new(ErrorCode::ExecutionError), None))
+        );
+    }
+}
+
 #[cfg(all(test, feature = "arrayvec"))]
 mod test_arrayvec_error_queue {
     use super::*;
@@ -1137,4 +1612,43 @@ mod test_arrayvec_error_queue {
             Some(Error::new(ErrorCode::QueueOverflow))
         );
     }
+
+    #[test]
+    fn test_queue_overflowed() {
+        let mut errors = ArrayErrorQueue::<2>::new();
+        assert_eq!(errors.capacity(), 2);
+        errors.push_back_error(ErrorCode::Custom(1, b"One").into());
+        errors.push_back_error(ErrorCode::Custom(2, b"Two").into());
+        assert!(!errors.overflowed());
+        errors.push_back_error(ErrorCode::Custom(3, b"Three").into());
+        assert!(errors.overflowed());
+        assert_eq!(errors.num_errors(), 2);
+
+        // Flag remains set until queue is cleared
+        errors.pop_front_error();
+        errors.pop_front_error();
+        assert!(errors.overflowed());
+        errors.clear_errors();
+        assert!(!errors.overflowed());
+    }
+
+    #[test]
+    fn test_dedup_overflow() {
+        let mut errors = DedupErrorQueue::new(ArrayErrorQueue::<2>::new());
+        errors.push_back_error(ErrorCode::Custom(1, b"One").into());
+        errors.push_back_error(ErrorCode::Custom(2, b"Two").into());
+        errors.push_back_error(ErrorCode::Custom(2, b"Two").into());
+        assert_eq!(errors.repeats(), 2);
+        errors.push_back_error(ErrorCode::Custom(3, b"Three").into());
+        errors.push_back_error(ErrorCode::Custom(3, b"Three").into());
+        assert_eq!(errors.num_errors(), 2);
+        assert_eq!(
+            errors.pop_front_error(),
+            Some(Error::new(ErrorCode::Custom(1, b"One")))
+        );
+        assert_eq!(
+            errors.pop_front_error(),
+            Some(Error::new(ErrorCode::QueueOverflow))
+        );
+    }
 }
@@ -68,6 +68,7 @@ extern crate alloc;
 extern crate std as alloc;
 
 use crate::error::Error;
+use crate::parser::response::{ByteOrder, MessageTerminator, NumericFormat};
 use core::any::Any;
 
 pub mod error;
@@ -142,8 +143,32 @@ pub struct Context<'a> {
     /// For example: User authentication information if the call comes from an authenticated interface
     /// or port number if the call comes from a serial port.
     pub user: &'a dyn Any,
+
+    /// Format of real numeric response data
+    numeric_format: NumericFormat,
+    /// Byte order of binary numeric response data
+    byte_order: ByteOrder,
+    /// Terminator of response messages
+    message_terminator: MessageTerminator,
+    /// Continue with the next program message unit after an error
+    error_recovery: bool,
+
+    /// Mnemonics of the header currently being executed
+    #[cfg(feature = "header-path")]
+    header_path: arrayvec::ArrayVec<&'static [u8], HEADER_PATH_DEPTH>,
+    /// Length of `header_path` when the current relative branch was entered
+    #[cfg(feature = "header-path")]
+    header_path_base: usize,
+    /// Path and base of the current relative branch while a common command is executed
+    #[cfg(feature = "header-path")]
+    header_path_saved: Option<(arrayvec::ArrayVec<&'static [u8], HEADER_PATH_DEPTH>, usize)>,
 }
 
+/// Maximum number of mnemonics recorded by [Context::header_path].
+/// Mnemonics deeper than this are not recorded.
+#[cfg(feature = "header-path")]
+pub const HEADER_PATH_DEPTH: usize = 8;
+
 impl<'a> Default for Context<'a> {
     fn default() -> Self {
         Self::new()
@@ -153,15 +178,25 @@ impl<'a> Default for Context<'a> {
 impl<'a> Context<'a> {
     /// Create a new context
     pub fn new() -> Self {
-        Context {
-            mav: false,
-            user: &(),
-        }
+        Self::new_with_user(&())
     }
 
     // Create a new context with user data
     pub fn new_with_user(user: &'a dyn Any) -> Self {
-        Context { mav: false, user }
+        Context {
+            mav: false,
+            user,
+            numeric_format: NumericFormat::Ascii,
+            byte_order: ByteOrder::Normal,
+            message_terminator: MessageTerminator::Lf,
+            error_recovery: false,
+            #[cfg(feature = "header-path")]
+            header_path: arrayvec::ArrayVec::new(),
+            #[cfg(feature = "header-path")]
+            header_path_base: 0,
+            #[cfg(feature = "header-path")]
+            header_path_saved: None,
+        }
     }
 
     /// Get user context data.
@@ -175,6 +210,105 @@ impl<'a> Context<'a> {
     pub fn mav(&self) -> bool {
         self.mav
     }
+
+    /// Format of real numeric response data, see [NumericFormat].
+    pub fn numeric_format(&self) -> NumericFormat {
+        self.numeric_format
+    }
+
+    /// Set format of real numeric response data for following queries, Eg. by `FORMat[:DATA]`.
+    pub fn set_numeric_format(&mut self, numeric_format: NumericFormat) {
+        self.numeric_format = numeric_format;
+    }
+
+    /// Byte order of binary numeric response data, see [ByteOrder].
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Set byte order of binary numeric response data for following queries, Eg. by `FORMat:BORDer`.
+    pub fn set_byte_order(&mut self, byte_order: ByteOrder) {
+        self.byte_order = byte_order;
+    }
+
+    /// Terminator of response messages, see [MessageTerminator].
+    pub fn message_terminator(&self) -> MessageTerminator {
+        self.message_terminator
+    }
+
+    /// Set terminator of following response messages (`\n` by default).
+    ///
+    /// Useful when bridging to a transport with different line conventions, note that anything but
+    /// [MessageTerminator::Lf] is not IEEE 488.2 compliant.
+    pub fn set_message_terminator(&mut self, message_terminator: MessageTerminator) {
+        self.message_terminator = message_terminator;
+    }
+
+    /// Returns true if error recovery is enabled, see [Self::set_error_recovery].
+    pub fn error_recovery(&self) -> bool {
+        self.error_recovery
+    }
+
+    /// Enable or disable error recovery (disabled by default).
+    ///
+    /// When enabled, a program message unit which fails is reported to [Device::handle_error] and skipped,
+    /// execution then continues with the next unit of the message instead of aborting the whole message.
+    /// Any response already written by a failed unit is kept.
+    /// Useful for devices on noisy links, note that this is not strictly IEEE 488.2 compliant.
+    pub fn set_error_recovery(&mut self, error_recovery: bool) {
+        self.error_recovery = error_recovery;
+    }
+
+    /// Returns the mnemonics (as named in the command tree) of the header currently being executed.
+    ///
+    /// For example `SENSe`, `VOLTage` and `DC` when executing `SENS:VOLT:DC?`.
+    /// Default nodes which were not explicitly given in the header are not included.
+    /// At most [HEADER_PATH_DEPTH] mnemonics are recorded.
+    #[cfg(feature = "header-path")]
+    pub fn header_path(&self) -> &[&'static [u8]] {
+        self.header_path.as_slice()
+    }
+
+    /// Start a new absolute (`:header` or `*header`) or relative (`header`) path
+    pub(crate) fn header_path_reset(&mut self, _relative: bool) {
+        #[cfg(feature = "header-path")]
+        if _relative {
+            if let Some((path, base)) = self.header_path_saved.take() {
+                self.header_path = path;
+                self.header_path_base = base;
+            }
+            self.header_path.truncate(self.header_path_base);
+        } else {
+            self.header_path.clear();
+            self.header_path_base = 0;
+            self.header_path_saved = None;
+        }
+    }
+
+    /// Start a common command (`*header`) path.
+    /// Common commands do not change the current relative branch, it's restored by the next relative header.
+    pub(crate) fn header_path_common(&mut self) {
+        #[cfg(feature = "header-path")]
+        {
+            let path = core::mem::take(&mut self.header_path);
+            self.header_path_saved
+                .get_or_insert((path, self.header_path_base));
+        }
+    }
+
+    /// Mark current path as the base of following relative headers
+    pub(crate) fn header_path_enter_branch(&mut self) {
+        #[cfg(feature = "header-path")]
+        {
+            self.header_path_base = self.header_path.len();
+        }
+    }
+
+    /// Record a matched mnemonic
+    pub(crate) fn header_path_push(&mut self, _mnemonic: &'static [u8]) {
+        #[cfg(feature = "header-path")]
+        let _ = self.header_path.try_push(_mnemonic);
+    }
 }
 
 #[cfg(test)]
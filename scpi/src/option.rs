@@ -21,6 +21,37 @@
 /// assert_eq!(MyEnum::Binary.short_form(), b"BIN");
 /// ```
 ///
+/// A variant may also accept additional mnemonics with `#[scpi(alias = b"...")]`.
+/// Aliases are only used when parsing, [ScpiEnum::mnemonic] always returns the primary mnemonic.
+/// ```
+/// # use crate::scpi::option::ScpiEnum;
+/// #[derive(Copy, Clone, PartialEq, Debug, scpi_derive::ScpiEnum)]
+/// enum Source {
+///     #[scpi(mnemonic = b"IMMediate", alias = b"IMM0")]
+///     Immediate,
+///     #[scpi(mnemonic = b"EXTernal")]
+///     External,
+/// }
+///
+/// assert_eq!(Source::from_mnemonic(b"IMM0"), Some(Source::Immediate));
+/// assert_eq!(Source::Immediate.mnemonic(), b"IMMediate");
+/// ```
+///
+/// Mark one variant with `#[scpi(default)]` to also generate an `impl Default` returning it.
+/// Single unnamed field variants use the field's default value.
+/// ```
+/// # use crate::scpi::option::ScpiEnum;
+/// #[derive(Copy, Clone, PartialEq, Debug, scpi_derive::ScpiEnum)]
+/// enum Source {
+///     #[scpi(mnemonic = b"IMMediate", default)]
+///     Immediate,
+///     #[scpi(mnemonic = b"EXTernal")]
+///     External,
+/// }
+///
+/// assert_eq!(Source::default(), Source::Immediate);
+/// ```
+///
 ///
 ///
 pub trait ScpiEnum
@@ -66,6 +97,27 @@ mod tests {
         Ascii2,
         #[scpi(mnemonic = b"L125")]
         L125,
+        #[scpi(mnemonic = b"D0")]
+        D0,
+        #[scpi(mnemonic = b"PORT10")]
+        Port10,
+    }
+
+    #[derive(Copy, Clone, PartialEq, Debug, scpi_derive::ScpiEnum)]
+    enum MyAliasEnum {
+        #[scpi(mnemonic = b"IMMediate")]
+        #[scpi(alias = b"NOW", alias = b"LEGacy2")]
+        Immediate,
+        #[scpi(mnemonic = b"BUS")]
+        Bus,
+    }
+
+    #[derive(Copy, Clone, PartialEq, Debug, scpi_derive::ScpiEnum)]
+    enum MyDefaultEnum {
+        #[scpi(mnemonic = b"IMMediate")]
+        Immediate,
+        #[scpi(mnemonic = b"EXTernal", default)]
+        External(u8),
     }
 
     #[test]
@@ -84,6 +136,40 @@ mod tests {
         assert_eq!(MyEnum::from_mnemonic(b"ascii1"), Some(MyEnum::Ascii1));
         assert_eq!(MyEnum::from_mnemonic(b"ascii2"), Some(MyEnum::Ascii2));
         assert_eq!(MyEnum::from_mnemonic(b"bin1"), Some(MyEnum::Binary));
+        assert_eq!(MyEnum::from_mnemonic(b"d0"), Some(MyEnum::D0));
+        assert_eq!(MyEnum::from_mnemonic(b"d1"), None);
+        assert_eq!(MyEnum::from_mnemonic(b"port10"), Some(MyEnum::Port10));
+        assert_eq!(MyEnum::from_mnemonic(b"port1"), None);
+    }
+
+    #[test]
+    fn test_enum_alias() {
+        assert_eq!(
+            MyAliasEnum::from_mnemonic(b"imm"),
+            Some(MyAliasEnum::Immediate)
+        );
+        assert_eq!(
+            MyAliasEnum::from_mnemonic(b"now"),
+            Some(MyAliasEnum::Immediate)
+        );
+        assert_eq!(
+            MyAliasEnum::from_mnemonic(b"LEG2"),
+            Some(MyAliasEnum::Immediate)
+        );
+        assert_eq!(MyAliasEnum::from_mnemonic(b"LEG"), None);
+        assert_eq!(MyAliasEnum::from_mnemonic(b"bus"), Some(MyAliasEnum::Bus));
+        // Only the primary mnemonic is returned
+        assert_eq!(MyAliasEnum::Immediate.mnemonic(), b"IMMediate");
+        assert_eq!(MyAliasEnum::Immediate.short_form(), b"IMM");
+    }
+
+    #[test]
+    fn test_enum_default() {
+        assert_eq!(MyDefaultEnum::default(), MyDefaultEnum::External(0));
+        assert_eq!(
+            MyDefaultEnum::from_mnemonic(b"imm"),
+            Some(MyDefaultEnum::Immediate)
+        );
     }
 
     #[test]
@@ -60,6 +60,92 @@ pub enum Token<'a> {
     PathName(&'a [u8]),
 }
 
+/// Channel list entry, see [ChannelList::entries].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChannelEntry<'a> {
+    /// A single one-dimensional channel. Example: `1`
+    Single(u32),
+    /// A range of one-dimensional channels, inclusive. Example: `3:5`
+    Range(u32, u32),
+    /// A multi-dimensional (or negative) channel spec. Example: `1!2`
+    Spec(ChannelSpec<'a>),
+    /// A range of multi-dimensional (or negative) channel specs. Example: `1!1:2!3`
+    SpecRange(ChannelSpec<'a>, ChannelSpec<'a>),
+    /// A module-channel, see [Token::ModuleChannel]
+    ModuleChannel(&'a [u8], &'a [u8]),
+    /// A character pathname, see [Token::PathName]
+    PathName(&'a [u8]),
+}
+
+impl<'a> ChannelSpec<'a> {
+    /// Returns the channel number if this is a one-dimensional non-negative spec.
+    fn single(&self) -> Option<u32> {
+        if self.dimension() == 1 {
+            self.into_iter().next()?.ok()?.try_into().ok()
+        } else {
+            None
+        }
+    }
+}
+
+impl ChannelEntry<'_> {
+    /// Iterate over the channels of a [ChannelEntry::Single] or [ChannelEntry::Range] entry,
+    /// ranges are expanded in either direction. Returns [None] for any other entry.
+    ///
+    /// ```
+    /// # use scpi::parser::expression::channel_list::ChannelEntry;
+    /// assert!(ChannelEntry::Range(5, 3).channels().unwrap().eq([5, 4, 3]));
+    /// assert!(ChannelEntry::PathName(b"POTATO").channels().is_none());
+    /// ```
+    pub fn channels(&self) -> Option<impl Iterator<Item = u32>> {
+        let (next, end) = match *self {
+            ChannelEntry::Single(x) => (x, x),
+            ChannelEntry::Range(a, b) => (a, b),
+            _ => return None,
+        };
+        Some(ChannelRangeIterator {
+            next: Some(next),
+            end,
+        })
+    }
+}
+
+impl<'a> From<Token<'a>> for ChannelEntry<'a> {
+    fn from(value: Token<'a>) -> Self {
+        match value {
+            Token::ChannelSpec(spec) => spec
+                .single()
+                .map_or(ChannelEntry::Spec(spec), ChannelEntry::Single),
+            Token::ChannelRange(begin, end) => match (begin.single(), end.single()) {
+                (Some(a), Some(b)) => ChannelEntry::Range(a, b),
+                _ => ChannelEntry::SpecRange(begin, end),
+            },
+            Token::ModuleChannel(module, list) => ChannelEntry::ModuleChannel(module, list),
+            Token::PathName(s) => ChannelEntry::PathName(s),
+        }
+    }
+}
+
+/// Iterates over a inclusive range of channels in either direction.
+struct ChannelRangeIterator {
+    next: Option<u32>,
+    end: u32,
+}
+
+impl Iterator for ChannelRangeIterator {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.next?;
+        self.next = match x.cmp(&self.end) {
+            core::cmp::Ordering::Less => Some(x + 1),
+            core::cmp::Ordering::Greater => Some(x - 1),
+            core::cmp::Ordering::Equal => None,
+        };
+        Some(x)
+    }
+}
+
 /// Iterates over a channel spec, returning a result for each dimension.
 /// If the iterator encounters a badly formatted value, an error will be returned.
 /// Example: `"1!2!3"` would iterate as `Ok(1),Ok(2),Ok(3)`.
@@ -198,31 +284,96 @@ impl<'a> ChannelList<'a> {
     /// Create a new channel-list tokenizer
     ///
     /// # Returns
-    /// `Some(Tokenizer)` - Expression is a channel-list (starts with '@')
-    /// `None` - Expression is not a channel-list
-    pub fn new(expr: &'a [u8]) -> Option<Self> {
-        let mut iter = expr.iter();
-        if let Some(x) = iter.next() {
-            if *x == b'@' {
-                Some(ChannelList {
-                    chars: iter.clone(),
+    /// `Ok(Tokenizer)` - Expression is a well-formed channel-list (starts with '@'), see [Self::validate]
+    /// `Err(InvalidExpression)` - Expression is not a channel-list
+    /// `Err(_)` - Expression is a malformed channel-list
+    pub fn new(expr: &'a [u8]) -> Result<Self, ErrorCode> {
+        match expr.split_first() {
+            Some((b'@', rest)) => {
+                let list = ChannelList {
+                    chars: rest.iter(),
                     first: true,
-                })
-            } else {
-                None
+                };
+                list.validate()?;
+                Ok(list)
+            }
+            _ => Err(ErrorCode::InvalidExpression),
+        }
+    }
+
+    /// Check that all entries in channel list are well-formed.
+    ///
+    /// Called by [Self::new], so that errors are reported before a command starts iterating over the channels.
+    pub fn validate(&self) -> Result<(), ErrorCode> {
+        for token in self.clone() {
+            match token? {
+                Token::ChannelSpec(spec) => {
+                    for x in spec {
+                        x?;
+                    }
+                }
+                Token::ChannelRange(begin, end) => {
+                    for x in begin.into_iter().chain(end) {
+                        x?;
+                    }
+                }
+                Token::ModuleChannel(..) | Token::PathName(..) => {}
             }
-        } else {
-            None
         }
+        Ok(())
+    }
+
+    /// Iterate over the entries in channel list.
+    /// Iteration ends after the first malformed entry, which is returned as an error.
+    ///
+    /// ```
+    /// # use scpi::parser::expression::channel_list::{ChannelList, ChannelEntry};
+    /// let list = ChannelList::new(b"@1,3:5,7").unwrap();
+    /// let mut entries = list.entries();
+    /// assert_eq!(entries.next(), Some(Ok(ChannelEntry::Single(1))));
+    /// assert_eq!(entries.next(), Some(Ok(ChannelEntry::Range(3, 5))));
+    /// assert_eq!(entries.next(), Some(Ok(ChannelEntry::Single(7))));
+    /// assert_eq!(entries.next(), None);
+    /// ```
+    pub fn entries(&self) -> impl Iterator<Item = Result<ChannelEntry<'a>, ErrorCode>> {
+        let mut failed = false;
+        self.clone().map_while(move |token| {
+            if failed {
+                return None;
+            }
+            failed = token.is_err();
+            Some(token.map(ChannelEntry::from))
+        })
+    }
+
+    /// Iterate over all one-dimensional channels in channel list, ranges are expanded in either direction.
+    /// Other entries are ignored, a malformed entry is returned as an error as in [Self::entries].
+    ///
+    /// ```
+    /// # use scpi::parser::expression::channel_list::ChannelList;
+    /// let list = ChannelList::new(b"@1,5:3,7").unwrap();
+    /// assert!(list.channels().eq([Ok(1), Ok(5), Ok(4), Ok(3), Ok(7)]));
+    /// ```
+    pub fn channels(&self) -> impl Iterator<Item = Result<u32, ErrorCode>> + 'a {
+        self.entries().flat_map(|entry| {
+            let (channels, err) = match entry {
+                Ok(entry) => (entry.channels(), None),
+                Err(err) => (None, Some(Err(err))),
+            };
+            channels.into_iter().flatten().map(Ok).chain(err)
+        })
     }
 
     fn read_channel_spec(&mut self) -> Result<(&'a [u8], usize), ErrorCode> {
         let mut dim = 1usize;
         // Read full spec
         let s = self.chars.as_slice();
-        while self.chars.clone().next().map_or(false, |ch| {
-            ch.is_ascii_digit() || *ch == b'-' || *ch == b'+' || *ch == b'!'
-        }) {
+        while self
+            .chars
+            .clone()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_digit() || *ch == b'-' || *ch == b'+' || *ch == b'!')
+        {
             if let Some(x) = self.chars.next() {
                 if *x == b'!' {
                     dim += 1;
@@ -269,7 +420,7 @@ impl<'a> ChannelList<'a> {
         // Read pathname
         let s = self.chars.as_slice();
 
-        if let crate::parser::tokenizer::Token::StringProgramData(s) =
+        if let crate::parser::tokenizer::Token::StringProgramData(s, _) =
             crate::parser::tokenizer::Tokenizer::new(s).read_string_data(x, true)?
         {
             self.chars.nth(s.len() + 1); //Forward iterator characters
@@ -351,4 +502,59 @@ mod tests {
         assert_eq!(expr.next(), Some(Ok(Token::PathName(b"POTATO"))));
         assert_eq!(expr.next(), None);
     }
+
+    #[test]
+    fn test_channel_entries() {
+        let list = ChannelList::new(b"@1,3:5,7,1!2,-1,'POTATO'").unwrap();
+        assert_eq!(list.validate(), Ok(()));
+        let mut entries = list.entries();
+        assert_eq!(entries.next(), Some(Ok(ChannelEntry::Single(1))));
+        assert_eq!(entries.next(), Some(Ok(ChannelEntry::Range(3, 5))));
+        assert_eq!(entries.next(), Some(Ok(ChannelEntry::Single(7))));
+        assert_eq!(
+            entries.next(),
+            Some(Ok(ChannelEntry::Spec(ChannelSpec(b"1!2", 2))))
+        );
+        assert_eq!(
+            entries.next(),
+            Some(Ok(ChannelEntry::Spec(ChannelSpec(b"-1", 1))))
+        );
+        assert_eq!(entries.next(), Some(Ok(ChannelEntry::PathName(b"POTATO"))));
+        assert_eq!(entries.next(), None);
+
+        assert!(list.channels().eq([1, 3, 4, 5, 7].map(Ok)));
+
+        // Unvalidated list
+        let list = ChannelList {
+            chars: b"1,x,2".iter(),
+            first: true,
+        };
+        assert!(list.entries().eq([
+            Ok(ChannelEntry::Single(1)),
+            Err(ErrorCode::InvalidExpression)
+        ]));
+        assert!(list
+            .channels()
+            .eq([Ok(1), Err(ErrorCode::InvalidExpression)]));
+    }
+
+    #[test]
+    fn test_channel_validate() {
+        assert_eq!(
+            ChannelList::new(b"@1,2:").err(),
+            Some(ErrorCode::InvalidExpression)
+        );
+        assert_eq!(
+            ChannelList::new(b"@1,1!2:3").err(),
+            Some(ErrorCode::InvalidExpression)
+        );
+        assert_eq!(
+            ChannelList::new(b"@1,1!-").err(),
+            Some(ErrorCode::ExpressionError)
+        );
+        assert_eq!(
+            ChannelList::new(b"1,2").err(),
+            Some(ErrorCode::InvalidExpression)
+        );
+    }
 }
@@ -3,7 +3,10 @@
 //! A numeric list is a an expression format for compactly expressing numbers and ranges of
 //! numbers in a single parameter.
 
+use core::convert::TryFrom;
+
 use crate::error::{Error, ErrorCode};
+use crate::parser::tokenizer::util;
 
 type Number<'a> = crate::parser::tokenizer::Token<'a>;
 
@@ -28,12 +31,39 @@ impl<'a> NumericList<'a> {
         }
     }
 
+    /// Iterate over the values of the list converted into `T`.
+    ///
+    /// Ranges (`begin:end`) are expanded into each value between `begin` and `end` (inclusive),
+    /// see [FromNumericEntry::next_in_range].
+    ///
+    /// ```
+    /// # use scpi::parser::expression::numeric_list::NumericList;
+    /// let list = NumericList::new(b"1,2.5,3");
+    /// let values: Result<Vec<f32>, _> = list.iter().collect();
+    /// assert_eq!(values, Ok(vec![1.0, 2.5, 3.0]));
+    ///
+    /// let list = NumericList::new(b"1:3,5");
+    /// let values: Result<Vec<i32>, _> = list.iter().collect();
+    /// assert_eq!(values, Ok(vec![1, 2, 3, 5]));
+    /// ```
+    pub fn iter<T>(&self) -> NumericValues<'a, T>
+    where
+        T: FromNumericEntry,
+    {
+        NumericValues {
+            list: self.clone(),
+            range: None,
+        }
+    }
+
     fn read_numeric_data(&mut self) -> Result<Token<'a>, ErrorCode> {
         let begin: Number = self.tokenizer.read_nrf()?;
+        util::skip_ws(&mut self.tokenizer.chars);
         if let Some(c) = self.tokenizer.chars.clone().next() {
             //&& *c == b':' {
             if *c == b':' {
                 self.tokenizer.chars.next();
+                util::skip_ws(&mut self.tokenizer.chars);
                 let end = self.tokenizer.read_nrf()?;
                 return Ok(Token::NumericRange(begin, end));
             }
@@ -48,11 +78,13 @@ impl<'a> Iterator for NumericList<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         //TODO: This has to be tokenizer abuse or something...
+        util::skip_ws(&mut self.tokenizer.chars);
         let char = self.tokenizer.chars.clone().next()?;
 
         Some(match char {
             b',' if !self.first => {
                 self.tokenizer.chars.next().unwrap();
+                util::skip_ws(&mut self.tokenizer.chars);
                 self.read_numeric_data().map_err(|err| {
                     Error::new(ErrorCode::InvalidExpression).extended(err.get_message())
                 })
@@ -68,6 +100,70 @@ impl<'a> Iterator for NumericList<'a> {
     }
 }
 
+/// A type which can be converted from the entries of a [NumericList]
+pub trait FromNumericEntry: Sized + for<'a> TryFrom<Number<'a>, Error = Error> {
+    /// Returns the value following `self` in a range ending at `end`, or [None] if `self` is `end`.
+    ///
+    /// Ranges are not allowed by default and return an [ErrorCode::InvalidExpression] error.
+    fn next_in_range(&self, _end: &Self) -> Result<Option<Self>, Error> {
+        Err(Error::new(ErrorCode::InvalidExpression).extended(b"Range not allowed"))
+    }
+}
+
+impl FromNumericEntry for f32 {}
+impl FromNumericEntry for f64 {}
+
+macro_rules! impl_from_numeric_entry {
+    ($($typ:ty),*) => {
+        $(
+            /// Ranges are expanded in steps of one, in either direction.
+            impl FromNumericEntry for $typ {
+                fn next_in_range(&self, end: &Self) -> Result<Option<Self>, Error> {
+                    Ok(match self.cmp(end) {
+                        core::cmp::Ordering::Less => Some(self + 1),
+                        core::cmp::Ordering::Greater => Some(self - 1),
+                        core::cmp::Ordering::Equal => None,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_from_numeric_entry!(u8, i8, u16, i16, u32, i32, u64, i64, usize, isize);
+
+/// Iterator over the values of a [NumericList], see [NumericList::iter].
+#[derive(Clone)]
+pub struct NumericValues<'a, T> {
+    list: NumericList<'a>,
+    /// Next value and end of the range currently being expanded
+    range: Option<(T, T)>,
+}
+
+impl<'a, T> Iterator for NumericValues<'a, T>
+where
+    T: FromNumericEntry,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((value, end)) = self.range.take() {
+            return Some(value.next_in_range(&end).map(|next| {
+                self.range = next.map(|next| (next, end));
+                value
+            }));
+        }
+
+        Some(self.list.next()?.and_then(|token| match token {
+            Token::Numeric(number) => T::try_from(number),
+            Token::NumericRange(begin, end) => {
+                self.range = Some((T::try_from(begin)?, T::try_from(end)?));
+                self.next().unwrap()
+            }
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +206,35 @@ mod tests {
         assert_eq!(expr.next(), None);
     }
 
+    #[test]
+    fn test_numeric_values() {
+        let list = NumericList::new(b"1,2.5,3");
+        let values: Result<std::vec::Vec<f32>, Error> = list.iter().collect();
+        assert_eq!(values, Ok(std::vec![1.0, 2.5, 3.0]));
+
+        // Whitespace and descending ranges
+        let list = NumericList::new(b" 1 , 5 : 3,-1");
+        let values: Result<std::vec::Vec<i8>, Error> = list.iter().collect();
+        assert_eq!(values, Ok(std::vec![1, 5, 4, 3, -1]));
+
+        // Empty list
+        assert_eq!(NumericList::new(b"").iter::<f32>().next(), None);
+
+        // Ranges of reals are not allowed
+        let mut values = NumericList::new(b"1.0:2.0").iter::<f32>();
+        assert_eq!(
+            values.next(),
+            Some(Err(
+                Error::new(ErrorCode::InvalidExpression).extended(b"Range not allowed")
+            ))
+        );
+
+        // Conversion errors are propagated
+        let mut values = NumericList::new(b"1,256").iter::<u8>();
+        assert_eq!(values.next(), Some(Ok(1)));
+        assert_eq!(values.next(), Some(Err(ErrorCode::DataOutOfRange.into())));
+    }
+
     #[test]
     fn test_numeric_leading() {
         let mut expr = NumericList::new(b",1,2:5");
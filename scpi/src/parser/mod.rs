@@ -7,7 +7,10 @@ pub mod response;
 pub mod suffix;
 pub mod tokenizer;
 
-pub use tokenizer::util::{mnemonic_compare, mnemonic_match};
+#[cfg(feature = "alloc")]
+pub use tokenizer::tokenize_all;
+pub use tokenizer::util::{mnemonic_compare, mnemonic_match, validate_mnemonic};
+pub use tokenizer::{tokenize, validate_syntax};
 
 /// Wrappers to format and discriminate SCPI types
 pub mod format {
@@ -25,6 +28,10 @@ pub mod format {
     pub struct Octal<V>(pub V);
 
     /// Arbitrary data
+    ///
+    /// The payload is a slice of the program message and is not copied, a large block
+    /// (Eg. a firmware image written to flash) can be processed with [`chunks`](slice::chunks)
+    /// without any intermediate buffer.
     #[derive(Debug, PartialEq, Eq, Copy, Clone)]
     pub struct Arbitrary<'a>(pub &'a [u8]);
 
@@ -35,4 +42,54 @@ pub mod format {
     /// Character data
     #[derive(Debug, PartialEq, Eq, Copy, Clone)]
     pub struct Character<'a>(pub &'a [u8]);
+
+    /// Pre-formatted response data, emitted verbatim without quoting or escaping.
+    ///
+    /// The caller is responsible for the bytes forming valid response data,
+    /// Eg. when proxying a response from a sub-instrument.
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    pub struct Raw<'a>(pub &'a [u8]);
+
+    /// Single character data, e.g. a polarity selection `P` or `N`.
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    pub struct SingleChar(pub u8);
+
+    /// List of response data, formatted as comma separated data.
+    ///
+    /// Needed because `&[u8]` is formatted as string data.
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    pub struct List<'a, T>(pub &'a [T]);
+
+    /// Integer parameter where non-decimal data (`#H`, `#Q`, `#B`) is interpreted as a bit pattern.
+    ///
+    /// A signed target accepts any non-decimal value which fits the unsigned type of the same width,
+    /// reinterpreting it as two's complement, i.e. `#HFFFFFFFF` is `-1` as a `Bitmask<i32>`.
+    /// Decimal data is converted with the normal range checks.
+    ///
+    /// Useful for register masks.
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    pub struct Bitmask<T>(pub T);
+
+    /// Single precision reals formatted as a definite length arbitrary block of IEEE 754 values,
+    /// in the byte order of the [crate::Context] (see [super::response::ByteOrder]).
+    ///
+    /// Example: `RealBlock(&[1.0, 2.0])` is formatted as `#18<8 bytes>`.
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    pub struct RealBlock<'a>(pub &'a [f32]);
+
+    /// Double precision version of [RealBlock].
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    pub struct RealBlock64<'a>(pub &'a [f64]);
+
+    /// Real number formatted in fixed point notation with the given number of digits after the decimal point.
+    ///
+    /// Example: `Fixed(3.14159, 2)` is formatted as `3.14`.
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    pub struct Fixed<T>(pub T, pub usize);
+
+    /// Real number formatted in scientific notation with the given number of digits after the decimal point.
+    ///
+    /// Example: `Scientific(314.159, 2)` is formatted as `3.14E+02`.
+    #[derive(Debug, PartialEq, Copy, Clone)]
+    pub struct Scientific<T>(pub T, pub usize);
 }
@@ -36,6 +36,7 @@ macro_rules! parser_unreachable {
     };
 }
 
+#[allow(unused_imports)]
 pub(crate) use parser_unreachable;
 
 /// Parameter iterator for a command
@@ -78,6 +79,48 @@ impl<'a, 'b> Parameters<'a, 'b> {
         }
     }
 
+    /// Look at the next data token without consuming it.
+    /// If no data token is found, [None] is returned.
+    ///
+    /// Useful when a command accepts different kinds of data in the same position,
+    /// Eg. a channel list or a scalar value.
+    ///
+    /// A preceding data separator is consumed, in which case a data token must follow or
+    /// a error with [ErrorCode::MissingParameter] is returned.
+    pub fn peek_token(&mut self) -> Result<Option<&Token<'b>>, Error> {
+        if let Some(Ok(Token::ProgramDataSeparator)) = self.0.peek() {
+            self.0.next();
+            match self.0.peek() {
+                Some(Ok(t)) if t.is_data() => {}
+                Some(Err(err)) => return Err((*err).into()),
+                _ => return Err(ErrorCode::MissingParameter.into()),
+            }
+        }
+        match self.0.peek() {
+            Some(Ok(t)) if t.is_data() => Ok(Some(t)),
+            Some(Err(err)) => Err((*err).into()),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check that all parameters have been consumed.
+    /// If any data remains a error with [ErrorCode::ParameterNotAllowed] is returned.
+    ///
+    /// Useful for strict arity checking before executing a command with optional trailing parameters,
+    /// Eg. `params.finish()?` before applying any settings.
+    /// The tree also returns [ErrorCode::ParameterNotAllowed] if a handler leaves data unconsumed, but only
+    /// after the handler has returned successfully. A error returned from the handler is reported once and
+    /// the remaining tokens of the message unit are not checked again.
+    pub fn finish(self) -> Result<(), Error> {
+        match self.0.peek() {
+            Some(Ok(t)) if t.is_data() || *t == Token::ProgramDataSeparator => {
+                Err(ErrorCode::ParameterNotAllowed.into())
+            }
+            Some(Err(err)) => Err((*err).into()),
+            _ => Ok(()),
+        }
+    }
+
     /// Get next data token.
     /// If no data is found a error with [ErrorCode::MissingParameter] is returned instead.
     pub fn next_token(&mut self) -> Result<Token<'a>, Error> {
@@ -113,6 +156,144 @@ impl<'a, 'b> Parameters<'a, 'b> {
             None => Ok(None),
         }
     }
+
+    /// Same as [`Self::next_optional_data`] but returns `default` if no data is found.
+    ///
+    /// If the data conversion fails a corresponding error is returned, the default is only used for missing data.
+    pub fn next_or<T>(&mut self, default: T) -> Result<T, Error>
+    where
+        T: TryFrom<Token<'a>, Error = Error>,
+    {
+        Ok(self.next_optional_data()?.unwrap_or(default))
+    }
+
+    /// Same as [`Self::next_or`] but the default is computed by `f` if no data is found.
+    pub fn next_or_else<T, F>(&mut self, f: F) -> Result<T, Error>
+    where
+        T: TryFrom<Token<'a>, Error = Error>,
+        F: FnOnce() -> T,
+    {
+        Ok(self.next_optional_data()?.unwrap_or_else(f))
+    }
+
+    /// Get the next `N` data tokens and convert them into an array of type T, Eg. `<x>,<y>` coordinates.
+    /// If fewer than `N` data tokens are found a error with [ErrorCode::MissingParameter] is returned instead.
+    ///
+    /// If the data conversion fails a corresponding error is returned.
+    pub fn next_array<T, const N: usize>(&mut self) -> Result<[T; N], Error>
+    where
+        T: TryFrom<Token<'a>, Error = Error>,
+    {
+        let mut array: [Option<T>; N] = core::array::from_fn(|_| None);
+        for elem in array.iter_mut() {
+            *elem = Some(self.next_data()?);
+        }
+        Ok(array.map(|elem| elem.unwrap()))
+    }
+
+    /// Same as [`Self::next_array`] but returns [None] if no data is found, Eg. for a trailing optional group.
+    ///
+    /// A partial group still returns a error with [ErrorCode::MissingParameter].
+    pub fn next_optional_array<T, const N: usize>(&mut self) -> Result<Option<[T; N]>, Error>
+    where
+        T: TryFrom<Token<'a>, Error = Error>,
+    {
+        if N == 0 || self.peek_token()?.is_none() {
+            return Ok(None);
+        }
+        self.next_array().map(Some)
+    }
+
+    /// Get the next data token as either a channel list or a scalar of type T,
+    /// Eg. the `[<expected value>[,<resolution>]][,(@<channel_list>)]` parameters of measurement commands.
+    /// If no data is found a error with [ErrorCode::MissingParameter] is returned instead.
+    ///
+    /// Expression data starting with `@` is parsed as a [ChannelList](channel_list::ChannelList),
+    /// anything else is converted into T.
+    /// A channel list must be the last parameter, any data following it returns a error with
+    /// [ErrorCode::ParameterNotAllowed].
+    pub fn next_scalar_or_channel<T>(&mut self) -> Result<ScalarOrChannel<'a, T>, Error>
+    where
+        T: TryFrom<Token<'a>, Error = Error>,
+    {
+        match self.next_token()? {
+            tok @ Token::ExpressionProgramData(s) if s.starts_with(b"@") => {
+                let list = tok.try_into()?;
+                if self.peek_token()?.is_some() {
+                    return Err(ErrorCode::ParameterNotAllowed.into());
+                }
+                Ok(ScalarOrChannel::Channel(list))
+            }
+            tok => Ok(ScalarOrChannel::Scalar(tok.try_into()?)),
+        }
+    }
+
+    /// Get next range data `<start>:<stop>` and convert both sides into type T.
+    /// Same as `next_data::<RangeValue<T>>()`, see [RangeValue].
+    pub fn next_range_data<T>(&mut self) -> Result<RangeValue<T>, Error>
+    where
+        T: TryFrom<Token<'a>, Error = Error>,
+    {
+        self.next_data()
+    }
+}
+
+/// A range of two program data elements separated by a colon, Eg. `1e3:1e6` or `MIN:MAX`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct RangeValue<T> {
+    /// Start of range (left of the colon)
+    pub start: T,
+    /// Stop of range (right of the colon)
+    pub stop: T,
+}
+
+/// Convert range data into a [RangeValue] with both sides converted into type T.
+///
+/// # Returns
+/// * `Ok(RangeValue)` - If data is range data and both sides convert into T.
+/// * `Err(NumericDataError)` - If data is not range data.
+/// * `Err(SyntaxError)` - If token is not data.
+impl<'a, T> TryFrom<Token<'a>> for RangeValue<T>
+where
+    T: TryFrom<Token<'a>, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: Token<'a>) -> Result<Self, Self::Error> {
+        fn side<'a, T>(s: &'a [u8]) -> Result<T, Error>
+        where
+            T: TryFrom<Token<'a>, Error = Error>,
+        {
+            match Tokenizer::new_params(s).next() {
+                Some(Ok(tok)) => tok.try_into(),
+                Some(Err(err)) => Err(err.into()),
+                None => parser_unreachable!(),
+            }
+        }
+
+        match value {
+            Token::ProgramDataRange(start, stop) => Ok(RangeValue {
+                start: side(start)?,
+                stop: side(stop)?,
+            }),
+            t => {
+                if t.is_data() {
+                    Err(ErrorCode::NumericDataError.into())
+                } else {
+                    parser_unreachable!()
+                }
+            }
+        }
+    }
+}
+
+/// Data which is either a scalar value or a channel list, see [Parameters::next_scalar_or_channel].
+#[derive(Clone)]
+pub enum ScalarOrChannel<'a, T> {
+    /// Scalar value, Eg. a expected value or resolution
+    Scalar(T),
+    /// Channel list, always the last parameter
+    Channel(channel_list::ChannelList<'a>),
 }
 
 /// Convert string data data into a slice (&\[u8\]).
@@ -126,7 +307,7 @@ impl<'a> TryFrom<Token<'a>> for &'a [u8] {
 
     fn try_from(value: Token<'a>) -> Result<&'a [u8], Self::Error> {
         match value {
-            Token::StringProgramData(s) => Ok(s),
+            Token::StringProgramData(s, _) => Ok(s),
             t => {
                 if t.is_data() {
                     Err(ErrorCode::DataTypeError.into())
@@ -141,7 +322,7 @@ impl<'a> TryFrom<Token<'a>> for &'a [u8] {
 /// Convert string data data into a boolean.
 ///
 /// # Returns
-/// * `Ok(bool)` - If data is character data matching `ON|OFF` or numeric `1|0`.
+/// * `Ok(bool)` - If data is character data matching `ON|OFF|TRUE|FALSE` or numeric `1|0`.
 /// * `Err(IllegalParameterValue)` - If data is character data or numeric but is not a boolean
 /// * `Err(DataTypeError)` - If data is not a character data or numeric.
 /// * `Err(SyntaxError)` - If token is not data.
@@ -155,9 +336,10 @@ impl<'a> TryFrom<Token<'a>> for bool {
                 Ok(<isize>::try_from(value)? != 0)
             }
             Token::CharacterProgramData(s) => {
-                if s.eq_ignore_ascii_case(b"ON") {
+                // TRUE/FALSE are full keywords without a short form
+                if s.eq_ignore_ascii_case(b"ON") || s.eq_ignore_ascii_case(b"TRUE") {
                     Ok(true)
-                } else if s.eq_ignore_ascii_case(b"OFF") {
+                } else if s.eq_ignore_ascii_case(b"OFF") || s.eq_ignore_ascii_case(b"FALSE") {
                     Ok(false)
                 } else {
                     Err(ErrorCode::IllegalParameterValue.into())
@@ -186,7 +368,7 @@ impl<'a> TryFrom<Token<'a>> for &'a str {
 
     fn try_from(value: Token<'a>) -> Result<&'a str, Self::Error> {
         match value {
-            Token::StringProgramData(s) | Token::ArbitraryBlockData(s) => {
+            Token::StringProgramData(s, _) | Token::ArbitraryBlockData(s) => {
                 str::from_utf8(s).map_err(|_| ErrorCode::StringDataError.into())
             }
             t => {
@@ -246,6 +428,31 @@ impl<'a> TryFrom<Token<'a>> for format::Character<'a> {
     }
 }
 
+/// Convert character data of exactly one character into a [format::SingleChar].
+///
+/// # Returns
+/// * `Ok(SingleChar)` - If data is character data of length one.
+/// * `Err(IllegalParameterValue)` - If character data is longer than one character.
+/// * `Err(DataTypeError)` - If data is not character data.
+/// * `Err(SyntaxError)` - If token is not data
+impl<'a> TryFrom<Token<'a>> for format::SingleChar {
+    type Error = Error;
+
+    fn try_from(value: Token<'a>) -> Result<format::SingleChar, Self::Error> {
+        match value {
+            Token::CharacterProgramData([c]) => Ok(format::SingleChar(*c)),
+            Token::CharacterProgramData(_) => Err(ErrorCode::IllegalParameterValue.into()),
+            t => {
+                if t.is_data() {
+                    Err(ErrorCode::DataTypeError.into())
+                } else {
+                    parser_unreachable!()
+                }
+            }
+        }
+    }
+}
+
 impl<'a> TryFrom<Token<'a>> for numeric_list::NumericList<'a> {
     type Error = Error;
 
@@ -268,9 +475,12 @@ impl<'a> TryFrom<Token<'a>> for channel_list::ChannelList<'a> {
 
     fn try_from(value: Token<'a>) -> Result<channel_list::ChannelList<'a>, Self::Error> {
         match value {
-            Token::ExpressionProgramData(s) => channel_list::ChannelList::new(s).ok_or_else(|| {
-                Error::new(ErrorCode::InvalidExpression).extended(b"Invalid channel list")
-            }),
+            Token::ExpressionProgramData(s) if s.starts_with(b"@") => {
+                Ok(channel_list::ChannelList::new(s)?)
+            }
+            Token::ExpressionProgramData(_) => {
+                Err(Error::new(ErrorCode::InvalidExpression).extended(b"Invalid channel list"))
+            }
             t => {
                 if t.is_data() {
                     Err(ErrorCode::DataTypeError.into())
@@ -306,7 +516,7 @@ macro_rules! impl_tryfrom_float {
 
             fn try_from(value: Token) -> Result<Self, Self::Error> {
                 match value {
-                    Token::DecimalNumericProgramData(value) => lexical_core::parse::<$from>(value)
+                    Token::DecimalNumericProgramData(value) => util::parse_nrf::<$from>(value)
                         .map_err(|e| match e {
                             lexical_core::Error::InvalidDigit(_) => {
                                 ErrorCode::InvalidCharacterInNumber.into()
@@ -353,10 +563,10 @@ macro_rules! impl_tryfrom_integer {
 
             fn try_from(value: Token) -> Result<Self, Self::Error> {
                 match value {
-                    Token::DecimalNumericProgramData(value) => lexical_core::parse::<$from>(value)
+                    Token::DecimalNumericProgramData(value) => util::parse_nrf::<$from>(value)
                         .or_else(|e| {
                             if matches!(e, lexical_core::Error::InvalidDigit(_)) {
-                                let value = lexical_core::parse::<$intermediate>(value)?;
+                                let value = util::parse_nrf::<$intermediate>(value)?;
 
                                 if !value.is_normal() {
                                     Err(lexical_core::Error::Overflow(0).into())
@@ -388,6 +598,9 @@ macro_rules! impl_tryfrom_integer {
                     Token::NonDecimalNumericProgramData(value) => {
                         <$from>::try_from(value).map_err(|_| ErrorCode::DataOutOfRange.into())
                     }
+                    Token::WideNonDecimalNumericProgramData(value) => {
+                        <$from>::try_from(value).map_err(|_| ErrorCode::DataOutOfRange.into())
+                    }
                     Token::CharacterProgramData(s) => match s {
                         //Check for special float values
                         ref x if util::mnemonic_compare(b"MAXimum", x) => Ok(<$from>::MAX),
@@ -412,6 +625,8 @@ macro_rules! impl_tryfrom_integer {
 
 // Need to fallback to floating point if numeric is not NR1 formatted.
 // Use double precision on larger types to avoid rounding errors.
+impl_tryfrom_integer!(i128, f64);
+impl_tryfrom_integer!(u128, f64);
 impl_tryfrom_integer!(usize, f64);
 impl_tryfrom_integer!(isize, f64);
 impl_tryfrom_integer!(i64, f64);
@@ -422,3 +637,37 @@ impl_tryfrom_integer!(i16, f32);
 impl_tryfrom_integer!(u16, f32);
 impl_tryfrom_integer!(i8, f32);
 impl_tryfrom_integer!(u8, f32);
+
+macro_rules! impl_tryfrom_bitmask {
+    ($from:ty, $unsigned:ty) => {
+        impl<'a> TryFrom<Token<'a>> for format::Bitmask<$from> {
+            type Error = Error;
+
+            fn try_from(value: Token) -> Result<Self, Self::Error> {
+                match value {
+                    // Reinterpret bit pattern
+                    Token::NonDecimalNumericProgramData(value) => <$unsigned>::try_from(value)
+                        .map(|value| format::Bitmask(value as $from))
+                        .map_err(|_| ErrorCode::DataOutOfRange.into()),
+                    Token::WideNonDecimalNumericProgramData(value) => <$unsigned>::try_from(value)
+                        .map(|value| format::Bitmask(value as $from))
+                        .map_err(|_| ErrorCode::DataOutOfRange.into()),
+                    t => <$from>::try_from(t).map(format::Bitmask),
+                }
+            }
+        }
+    };
+}
+
+impl_tryfrom_bitmask!(u128, u128);
+impl_tryfrom_bitmask!(i128, u128);
+impl_tryfrom_bitmask!(usize, usize);
+impl_tryfrom_bitmask!(isize, usize);
+impl_tryfrom_bitmask!(u64, u64);
+impl_tryfrom_bitmask!(i64, u64);
+impl_tryfrom_bitmask!(u32, u32);
+impl_tryfrom_bitmask!(i32, u32);
+impl_tryfrom_bitmask!(u16, u16);
+impl_tryfrom_bitmask!(i16, u16);
+impl_tryfrom_bitmask!(u8, u8);
+impl_tryfrom_bitmask!(i8, u8);
@@ -3,7 +3,8 @@ use arrayvec::ArrayVec;
 use crate::error::{ErrorCode, Result};
 
 use super::{
-    Formatter, ResponseUnit, RESPONSE_MESSAGE_TERMINATOR, RESPONSE_MESSAGE_UNIT_SEPARATOR,
+    ByteOrder, Formatter, NumericFormat, ResponseUnit, RESPONSE_MESSAGE_TERMINATOR,
+    RESPONSE_MESSAGE_UNIT_SEPARATOR,
 };
 
 impl<const CAP: usize> Formatter for ArrayVec<u8, CAP> {
@@ -37,7 +38,7 @@ impl<const CAP: usize> Formatter for ArrayVec<u8, CAP> {
         self.push_byte(RESPONSE_MESSAGE_TERMINATOR)
     }
 
-    fn response_unit(&mut self) -> Result<ResponseUnit> {
+    fn response_unit(&mut self) -> Result<ResponseUnit<'_>> {
         if !self.is_empty() {
             self.push_byte(RESPONSE_MESSAGE_UNIT_SEPARATOR)?;
         }
@@ -46,6 +47,10 @@ impl<const CAP: usize> Formatter for ArrayVec<u8, CAP> {
             result: Ok(()),
             has_header: false,
             has_data: false,
+            in_block: false,
+            numeric_format: NumericFormat::Ascii,
+            byte_order: ByteOrder::Normal,
+            block_ended: None,
         })
     }
 }
@@ -53,7 +58,10 @@ impl<const CAP: usize> Formatter for ArrayVec<u8, CAP> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{error::Error, tree::prelude::ResponseData};
+    use crate::{
+        error::{Error, ErrorCode},
+        tree::prelude::{Fixed, List, Raw, RealBlock, RealBlock64, ResponseData, Scientific},
+    };
 
     #[test]
     fn test_vecarray() {
@@ -87,6 +95,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_run_overflow() {
+        use crate::{cmd_qonly, tests::fixture_device, tree::prelude::*};
+
+        struct OverflowDevice;
+        fixture_device!(OverflowDevice);
+
+        struct LongCommand;
+        impl Command<OverflowDevice> for LongCommand {
+            cmd_qonly!();
+
+            fn query(
+                &self,
+                _device: &mut OverflowDevice,
+                _context: &mut Context,
+                _params: Parameters,
+                mut response: ResponseUnit,
+            ) -> Result<()> {
+                response.data(&b"0123456789"[..]).finish()
+            }
+        }
+
+        /// Fails to allocate memory of its own
+        struct AllocCommand;
+        impl Command<OverflowDevice> for AllocCommand {
+            cmd_qonly!();
+
+            fn query(
+                &self,
+                _device: &mut OverflowDevice,
+                _context: &mut Context,
+                _params: Parameters,
+                _response: ResponseUnit,
+            ) -> Result<()> {
+                Err(ErrorCode::OutOfMemory.into())
+            }
+        }
+
+        const TREE: Node<OverflowDevice> = Node::root(&[
+            Node::leaf(b"LONG", &LongCommand),
+            Node::leaf(b"ALLOC", &AllocCommand),
+        ]);
+
+        let mut context = Context::new();
+        let mut array = ArrayVec::<u8, 16>::new();
+        TREE.run(b"LONG?", &mut OverflowDevice, &mut context, &mut array)
+            .unwrap();
+        assert_eq!(array.as_slice(), b"\"0123456789\"\n");
+
+        // Second response does not fit, nothing is left in the buffer
+        let mut array = ArrayVec::<u8, 16>::new();
+        let res = TREE.run(
+            b"LONG?;LONG?",
+            &mut OverflowDevice,
+            &mut context,
+            &mut array,
+        );
+        assert_eq!(res, Err(ErrorCode::QueryDeadlocked.into()));
+        assert!(array.is_empty());
+
+        // A single response larger than the buffer
+        let mut array = ArrayVec::<u8, 8>::new();
+        let res = TREE.run(b"LONG?", &mut OverflowDevice, &mut context, &mut array);
+        assert_eq!(res, Err(ErrorCode::QueryDeadlocked.into()));
+        assert!(array.is_empty());
+
+        // Out of memory errors from the handler itself are not a deadlock
+        let mut array = ArrayVec::<u8, 16>::new();
+        let res = TREE.run(
+            b"LONG?;ALLOC?",
+            &mut OverflowDevice,
+            &mut context,
+            &mut array,
+        );
+        assert_eq!(res, Err(ErrorCode::OutOfMemory.into()));
+        assert_eq!(array.as_slice(), b"\"0123456789\";");
+    }
+
     #[test]
     fn test_f32() {
         let mut array = ArrayVec::<u8, 32>::new();
@@ -98,4 +184,254 @@ mod tests {
         // See SCPI-99 7.2.1.4 and 7.2.1.5
         assert_eq!(array.as_slice(), b"9.9E+37,-9.9E+37,9.91E+37");
     }
+
+    #[test]
+    fn test_list() {
+        let empty: [u8; 0] = [];
+        let mut array = ArrayVec::<u8, 32>::new();
+        List(&empty[..]).format_response_data(&mut array).unwrap();
+        assert_eq!(array.as_slice(), b"");
+
+        let mut array = ArrayVec::<u8, 32>::new();
+        List(&[1u8][..]).format_response_data(&mut array).unwrap();
+        assert_eq!(array.as_slice(), b"1");
+
+        // Separators between response data are only inserted once
+        let mut array = ArrayVec::<u8, 32>::new();
+        array
+            .response_unit()
+            .unwrap()
+            .header(b"MULTI")
+            .data(List(&[1u8, 2, 3][..]))
+            .data([4i16, 5])
+            .finish()
+            .unwrap();
+        assert_eq!(array.as_slice(), b"MULTI 1,2,3,4,5");
+    }
+
+    #[test]
+    fn test_option() {
+        let mut array = ArrayVec::<u8, 32>::new();
+        array
+            .response_unit()
+            .unwrap()
+            .data(1u8)
+            .data(None::<u8>)
+            .data(2u8)
+            .data(Some(3u8))
+            .data([None, Some(4u8), None])
+            .finish()
+            .unwrap();
+        assert_eq!(array.as_slice(), b"1,2,3,4");
+
+        // No separator after header without data
+        let mut array = ArrayVec::<u8, 32>::new();
+        array
+            .response_unit()
+            .unwrap()
+            .header(b"OPT")
+            .data(None::<u8>)
+            .data(Some(None::<u8>))
+            .finish()
+            .unwrap();
+        assert_eq!(array.as_slice(), b"OPT");
+    }
+
+    #[test]
+    fn test_result() {
+        let mut array = ArrayVec::<u8, 32>::new();
+        array
+            .response_unit()
+            .unwrap()
+            .data(List(&[
+                Ok(1u8),
+                Err(Error::new(ErrorCode::ExecutionError)),
+                Ok(3u8),
+            ]))
+            .finish()
+            .unwrap();
+        assert_eq!(array.as_slice(), b"1,9.91E+37,3");
+    }
+
+    #[test]
+    fn test_indefinite_block() {
+        let mut array = ArrayVec::<u8, 32>::new();
+        array.message_start().unwrap();
+        array.response_unit().unwrap().data(1u8).finish().unwrap();
+        array
+            .response_unit()
+            .unwrap()
+            .header(b"TRACE")
+            .data(2u8)
+            .begin_indefinite_block()
+            .block_data(b"a")
+            .block_data(b"bc")
+            .finish()
+            .unwrap();
+        // The block ends the message
+        assert_eq!(array.as_slice(), b"1;TRACE 2,#0abc\n");
+    }
+
+    #[test]
+    fn test_data_after_indefinite_block() {
+        let mut array = ArrayVec::<u8, 32>::new();
+        let res = array
+            .response_unit()
+            .unwrap()
+            .begin_indefinite_block()
+            .block_data(b"abc")
+            .data(1u8)
+            .finish();
+        assert_eq!(
+            res,
+            Err(ErrorCode::QueryUnterminatedAfterIndefiniteResponse.into())
+        );
+        assert_eq!(array.as_slice(), b"#0abc\n");
+    }
+
+    #[test]
+    fn test_raw() {
+        let mut array = ArrayVec::<u8, 32>::new();
+        array
+            .response_unit()
+            .unwrap()
+            .data(Raw(b"1,2,3"))
+            .data(&b"1,2"[..])
+            .finish()
+            .unwrap();
+        assert_eq!(array.as_slice(), b"1,2,3,\"1,2\"");
+    }
+
+    #[test]
+    fn test_block() {
+        let mut array = ArrayVec::<u8, 32>::new();
+        array
+            .response_unit()
+            .unwrap()
+            .data(1u8)
+            .block(3, |fmt| {
+                fmt.push_str(b"a")?;
+                fmt.push_str(b"bc")
+            })
+            .finish()
+            .unwrap();
+        assert_eq!(array.as_slice(), b"1,#13abc");
+
+        // Length mismatch
+        let mut array = ArrayVec::<u8, 32>::new();
+        let res = array
+            .response_unit()
+            .unwrap()
+            .block(4, |fmt| fmt.push_str(b"abc"))
+            .finish();
+        assert_eq!(res, Err(ErrorCode::ExecutionError.into()));
+    }
+
+    #[test]
+    fn test_real_notation() {
+        fn format(data: impl ResponseData) -> ArrayVec<u8, 64> {
+            let mut array = ArrayVec::<u8, 64>::new();
+            data.format_response_data(&mut array).unwrap();
+            array
+        }
+        assert_eq!(format(Fixed(1.23456_f32, 2)).as_slice(), b"1.23");
+        assert_eq!(format(Fixed(-1.23456_f64, 4)).as_slice(), b"-1.2346");
+        assert_eq!(format(Fixed(9.996_f32, 2)).as_slice(), b"10.00");
+        assert_eq!(format(Fixed(0.000123_f64, 5)).as_slice(), b"0.00012");
+        assert_eq!(format(Fixed(-0.001_f64, 2)).as_slice(), b"0.00");
+        assert_eq!(format(Fixed(0.5_f64, 0)).as_slice(), b"1");
+        assert_eq!(format(Fixed(1.5e10_f64, 1)).as_slice(), b"15000000000.0");
+        assert_eq!(format(Scientific(314.159_f32, 2)).as_slice(), b"3.14E+02");
+        assert_eq!(format(Scientific(-1.0e-7_f64, 3)).as_slice(), b"-1.000E-07");
+        assert_eq!(format(Scientific(9.99_f64, 1)).as_slice(), b"1.0E+01");
+        assert_eq!(format(Scientific(0.0_f64, 2)).as_slice(), b"0.00E+00");
+        assert_eq!(format(Scientific(2.5e300_f64, 0)).as_slice(), b"3E+300");
+        assert_eq!(format(Fixed(f32::NAN, 2)).as_slice(), b"9.91E+37");
+        assert_eq!(
+            format(Scientific(f64::NEG_INFINITY, 2)).as_slice(),
+            b"-9.9E+37"
+        );
+    }
+
+    #[test]
+    fn test_real_block() {
+        let mut array = ArrayVec::<u8, 32>::new();
+        RealBlock(&[1.0f32, 2.0])
+            .format_response_data(&mut array)
+            .unwrap();
+        assert_eq!(array.as_slice(), b"#18\x3f\x80\x00\x00\x40\x00\x00\x00");
+
+        let mut array = ArrayVec::<u8, 32>::new();
+        RealBlock64(&[-2.0f64])
+            .format_response_data(&mut array)
+            .unwrap();
+        assert_eq!(array.as_slice(), b"#18\xc0\x00\x00\x00\x00\x00\x00\x00");
+
+        let mut array = ArrayVec::<u8, 32>::new();
+        RealBlock(&[]).format_response_data(&mut array).unwrap();
+        assert_eq!(array.as_slice(), b"#10");
+
+        // Byte order and format of context is applied to response data
+        let mut array = ArrayVec::<u8, 32>::new();
+        array
+            .response_unit()
+            .unwrap()
+            .with_data_format(NumericFormat::Real32, ByteOrder::Swapped)
+            .data(RealBlock(&[1.0f32]))
+            .data(2.0f64)
+            .data(3u8)
+            .finish()
+            .unwrap();
+        assert_eq!(
+            array.as_slice(),
+            b"#14\x00\x00\x80\x3f,#14\x00\x00\x00\x40,3"
+        );
+
+        // A list of reals is a single block
+        let mut array = ArrayVec::<u8, 32>::new();
+        array
+            .response_unit()
+            .unwrap()
+            .with_data_format(NumericFormat::Real32, ByteOrder::Normal)
+            .data([1.0f32, 2.0])
+            .data(List(&[Some(-2.0f64), None]))
+            .data([1u8, 2])
+            .finish()
+            .unwrap();
+        assert_eq!(
+            array.as_slice(),
+            b"#18\x3f\x80\x00\x00\x40\x00\x00\x00,#14\xc0\x00\x00\x00,1,2"
+        );
+    }
+
+    #[test]
+    fn test_ascii_numeric_format() {
+        let format = |numeric_format| {
+            let mut array = ArrayVec::<u8, 64>::new();
+            array
+                .response_unit()
+                .unwrap()
+                .with_data_format(numeric_format, ByteOrder::Normal)
+                .data(1234.5678f64)
+                .data(-0.5f32)
+                .data(f64::NAN)
+                .data(7u8)
+                .finish()
+                .unwrap();
+            array
+        };
+        assert_eq!(
+            format(NumericFormat::Ascii).as_slice(),
+            b"1234.5678,-0.5,9.91E+37,7"
+        );
+        assert_eq!(format(NumericFormat::Nr1).as_slice(), b"1235,-1,9.91E+37,7");
+        assert_eq!(
+            format(NumericFormat::Nr2(2)).as_slice(),
+            b"1234.57,-0.50,9.91E+37,7"
+        );
+        assert_eq!(
+            format(NumericFormat::Nr3(3)).as_slice(),
+            b"1.235E+03,-5.000E-01,9.91E+37,7"
+        );
+    }
 }
@@ -0,0 +1,149 @@
+use crate::error::Result;
+
+use super::{
+    ByteOrder, Formatter, NumericFormat, ResponseUnit, RESPONSE_MESSAGE_TERMINATOR,
+    RESPONSE_MESSAGE_UNIT_SEPARATOR,
+};
+
+/// A formatter which discards the response but keeps track of its length and CRC-32 checksum.
+///
+/// Useful for transport layers which need to know the size (or checksum) of a response before
+/// sending it, Eg. for content-length framing, without having to store the whole response.
+/// Run the command once against a `CountingFormatter` to measure and again against a real formatter to emit it.
+///
+/// Note that [Formatter::as_slice] always returns an empty slice as no data is stored.
+///
+/// ```
+/// # use scpi::parser::response::{CountingFormatter, Formatter};
+/// let mut fmt = CountingFormatter::new();
+/// fmt.push_str(b"123456789").unwrap();
+/// assert_eq!(fmt.len(), 9);
+/// assert_eq!(fmt.checksum(), 0xCBF43926);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountingFormatter {
+    len: usize,
+    crc: u32,
+}
+
+impl CountingFormatter {
+    /// CRC-32 (IEEE 802.3) polynomial, reversed
+    const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+    pub const fn new() -> Self {
+        Self { len: 0, crc: !0 }
+    }
+
+    /// CRC-32 (IEEE 802.3) checksum of all bytes pushed since creation or last [Formatter::clear]
+    pub fn checksum(&self) -> u32 {
+        !self.crc
+    }
+
+    fn update(&mut self, b: u8) {
+        self.crc ^= b as u32;
+        for _ in 0..8 {
+            let mask = (self.crc & 1).wrapping_neg();
+            self.crc = (self.crc >> 1) ^ (Self::POLYNOMIAL & mask);
+        }
+    }
+}
+
+impl Default for CountingFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for CountingFormatter {
+    fn push_str(&mut self, s: &[u8]) -> Result<()> {
+        for b in s {
+            self.update(*b);
+        }
+        self.len += s.len();
+        Ok(())
+    }
+
+    fn push_byte(&mut self, b: u8) -> Result<()> {
+        self.update(b);
+        self.len += 1;
+        Ok(())
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &[]
+    }
+
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn message_start(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn message_end(&mut self) -> Result<()> {
+        self.push_byte(RESPONSE_MESSAGE_TERMINATOR)
+    }
+
+    fn response_unit(&mut self) -> Result<ResponseUnit<'_>> {
+        if !self.is_empty() {
+            self.push_byte(RESPONSE_MESSAGE_UNIT_SEPARATOR)?;
+        }
+        Ok(ResponseUnit {
+            fmt: self,
+            result: Ok(()),
+            has_header: false,
+            has_data: false,
+            in_block: false,
+            numeric_format: NumericFormat::Ascii,
+            byte_order: ByteOrder::Normal,
+            block_ended: None,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "arrayvec"))]
+mod tests {
+    use super::*;
+    use crate::tree::prelude::{Arbitrary, List};
+    use arrayvec::ArrayVec;
+
+    fn respond(fmt: &mut dyn Formatter) {
+        fmt.message_start().unwrap();
+        fmt.response_unit()
+            .unwrap()
+            .header(b"MEAS")
+            .data(&b"potato"[..])
+            .data(List(&[1.5f32, -2.0][..]))
+            .finish()
+            .unwrap();
+        fmt.response_unit()
+            .unwrap()
+            .data(Arbitrary(b"\x00\x01\x02"))
+            .finish()
+            .unwrap();
+        fmt.message_end().unwrap();
+    }
+
+    #[test]
+    fn test_counting() {
+        let mut array = ArrayVec::<u8, 64>::new();
+        respond(&mut array);
+
+        let mut counter = CountingFormatter::new();
+        respond(&mut counter);
+        assert_eq!(counter.len(), array.len());
+
+        let mut reference = CountingFormatter::new();
+        reference.push_str(&array).unwrap();
+        assert_eq!(counter.checksum(), reference.checksum());
+
+        counter.clear();
+        assert!(counter.is_empty());
+        assert_eq!(counter.checksum(), 0);
+    }
+}
@@ -0,0 +1,94 @@
+use core::fmt;
+
+use crate::error::{Error, ErrorCode, Result};
+
+use super::Formatter;
+
+/// Adapter implementing [core::fmt::Write] for a [Formatter] so responses can be built with [write!].
+///
+/// Only ASCII is accepted, writing anything else fails with an [ErrorCode::ExecutionError].
+/// The inherent [FmtWriter::write_fmt] returns the error of the underlying formatter instead of [fmt::Error].
+///
+/// ```
+/// use scpi::{error::Result, parser::response::{FmtWriter, ResponseData, Formatter}};
+///
+/// struct Voltage(f32);
+/// impl ResponseData for Voltage {
+///     fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+///         write!(FmtWriter::new(formatter), "{:.3}V", self.0)
+///     }
+/// }
+/// ```
+pub struct FmtWriter<'a, F: Formatter + ?Sized> {
+    fmt: &'a mut F,
+    result: Result<()>,
+}
+
+impl<'a, F: Formatter + ?Sized> FmtWriter<'a, F> {
+    /// Create a writer appending to `fmt`
+    pub fn new(fmt: &'a mut F) -> Self {
+        Self {
+            fmt,
+            result: Ok(()),
+        }
+    }
+
+    /// Write formatted arguments, used by [write!].
+    pub fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<()> {
+        match fmt::Write::write_fmt(self, args) {
+            Ok(()) => Ok(()),
+            // A formatting trait failed without a formatter error
+            Err(fmt::Error) => Err(self
+                .result
+                .err()
+                .unwrap_or_else(|| ErrorCode::ExecutionError.into())),
+        }
+    }
+
+    /// Returns the first error encountered
+    pub fn finish(self) -> Result<()> {
+        self.result
+    }
+}
+
+impl<F: Formatter + ?Sized> fmt::Write for FmtWriter<'_, F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.result = self.result.and_then(|_| {
+            if s.is_ascii() {
+                self.fmt.push_str(s.as_bytes())
+            } else {
+                Err(Error::new(ErrorCode::ExecutionError))
+            }
+        });
+        self.result.map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::FmtWriter;
+    use crate::error::ErrorCode;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_write() {
+        let mut buf = Vec::new();
+        let x = 1.23456f32;
+        write!(FmtWriter::new(&mut buf), "{:.3}", x).unwrap();
+        write!(FmtWriter::new(&mut buf), ",{}", 42).unwrap();
+        assert_eq!(buf, b"1.235,42");
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        let mut buf = Vec::new();
+        let unit = "µV";
+        let mut writer = FmtWriter::new(&mut buf);
+        assert_eq!(
+            write!(writer, "{}{}", 1, unit).unwrap_err(),
+            ErrorCode::ExecutionError
+        );
+        assert!(writer.finish().is_err());
+        assert_eq!(buf, b"1");
+    }
+}
@@ -0,0 +1,142 @@
+use heapless::Vec;
+
+use crate::error::{ErrorCode, Result};
+
+use super::{
+    ByteOrder, Formatter, NumericFormat, ResponseUnit, RESPONSE_MESSAGE_TERMINATOR,
+    RESPONSE_MESSAGE_UNIT_SEPARATOR,
+};
+
+impl<const CAP: usize> Formatter for Vec<u8, CAP> {
+    /// Internal use
+    fn push_str(&mut self, s: &[u8]) -> Result<()> {
+        self.extend_from_slice(s)
+            .map_err(|_| ErrorCode::OutOfMemory.into())
+    }
+
+    fn push_byte(&mut self, b: u8) -> Result<()> {
+        self.push(b).map_err(|_| ErrorCode::OutOfMemory.into())
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        self.as_slice()
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn message_start(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn message_end(&mut self) -> Result<()> {
+        self.push_byte(RESPONSE_MESSAGE_TERMINATOR)
+    }
+
+    fn response_unit(&mut self) -> Result<ResponseUnit<'_>> {
+        if !self.is_empty() {
+            self.push_byte(RESPONSE_MESSAGE_UNIT_SEPARATOR)?;
+        }
+        Ok(ResponseUnit {
+            fmt: self,
+            result: Ok(()),
+            has_header: false,
+            has_data: false,
+            in_block: false,
+            numeric_format: NumericFormat::Ascii,
+            byte_order: ByteOrder::Normal,
+            block_ended: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cmd_qonly,
+        error::{Error, ErrorCode},
+        tests::fixture_device,
+        tree::prelude::*,
+    };
+
+    #[test]
+    fn test_heapless() {
+        let mut vec = Vec::<u8, 16>::new();
+        vec.message_start().unwrap();
+        // First unit
+        vec.response_unit()
+            .unwrap()
+            .data(&b"potato"[..])
+            .data(0u8)
+            .finish()
+            .unwrap();
+        // Second unit
+        vec.response_unit().unwrap().data(42i16).finish().unwrap();
+        vec.message_end().unwrap();
+        assert_eq!(vec.as_slice(), b"\"potato\",0;42\n");
+
+        // Empty vectors are skipped
+        let mut vec = Vec::<u8, 16>::new();
+        vec.message_start().unwrap();
+        vec.response_unit()
+            .unwrap()
+            .data(Vec::<u8, 4>::new())
+            .data(1u8)
+            .finish()
+            .unwrap();
+        vec.message_end().unwrap();
+        assert_eq!(vec.as_slice(), b"1\n");
+    }
+
+    #[test]
+    fn test_outamemory() {
+        let mut vec = Vec::<u8, 1>::new();
+        vec.push_byte(b'x').unwrap();
+        assert_eq!(
+            vec.push_byte(b'x'),
+            Err(Error::from(ErrorCode::OutOfMemory))
+        );
+        assert_eq!(vec.push_str(b"x"), Err(Error::from(ErrorCode::OutOfMemory)));
+    }
+
+    struct HeaplessDevice;
+    fixture_device!(HeaplessDevice);
+
+    struct ListCommand;
+    impl Command<HeaplessDevice> for ListCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut HeaplessDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> crate::error::Result<()> {
+            let list: Vec<u8, 3> = Vec::from_slice(&[1, 2, 3]).unwrap();
+            response.data(list).finish()
+        }
+    }
+
+    #[test]
+    fn test_query() {
+        const TREE: Node<HeaplessDevice> = Node::root(&[Node::leaf(b"LIST", &ListCommand)]);
+
+        let mut context = Context::new();
+        let mut response = Vec::<u8, 256>::new();
+        TREE.run(
+            b"LIST?;LIST?",
+            &mut HeaplessDevice,
+            &mut context,
+            &mut response,
+        )
+        .unwrap();
+        assert_eq!(response.as_slice(), b"1,2,3;1,2,3\n");
+    }
+}
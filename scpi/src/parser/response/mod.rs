@@ -1,13 +1,25 @@
 //! Response formatting
 
-use super::format::{Arbitrary, Binary, Character, Expression, Hex, Octal};
+use super::format::{
+    Arbitrary, Binary, Character, Expression, Fixed, Hex, List, Octal, Raw, RealBlock, RealBlock64,
+    Scientific, SingleChar,
+};
+use core::cell::Cell;
+
 use crate::error::{Error, ErrorCode, Result};
 
 #[cfg(feature = "arrayvec")]
 mod arrayformatter;
+mod countingformatter;
+mod fmtwriter;
+#[cfg(feature = "heapless")]
+mod heaplessformatter;
 #[cfg(feature = "alloc")]
 mod vecformatter;
 
+pub use countingformatter::CountingFormatter;
+pub use fmtwriter::FmtWriter;
+
 use lexical_core::FormattedSize;
 use lexical_core::NumberFormatBuilder;
 
@@ -16,9 +28,91 @@ const RESPONSE_HEADER_SEPARATOR: u8 = b' ';
 const RESPONSE_MESSAGE_UNIT_SEPARATOR: u8 = b';';
 const RESPONSE_MESSAGE_TERMINATOR: u8 = b'\n';
 
+/// Format of real numeric response data, selected by Eg. `FORMat[:DATA]`.
+/// See [crate::Context::set_numeric_format].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumericFormat {
+    /// ASCII `<NRf>`, the shortest representation which converts back into the same value
+    #[default]
+    Ascii,
+    /// ASCII `<NR1>`, reals are rounded to an integer
+    Nr1,
+    /// ASCII `<NR2>` with the given number of digits after the decimal point, Eg. `1.50`
+    Nr2(u8),
+    /// ASCII `<NR3>` with the given number of digits after the decimal point, Eg. `1.50E+00`
+    Nr3(u8),
+    /// IEEE 754 single precision in a definite length arbitrary block, `#14<data>`
+    Real32,
+    /// IEEE 754 double precision in a definite length arbitrary block, `#18<data>`
+    Real64,
+}
+
+/// Byte order of binary numeric response data, selected by Eg. `FORMat:BORDer`.
+/// See [crate::Context::set_byte_order].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ByteOrder {
+    /// Big-endian, most significant byte first
+    #[default]
+    Normal,
+    /// Little-endian, least significant byte first
+    Swapped,
+}
+
+/// Terminator of response messages, see [crate::Context::set_message_terminator].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageTerminator {
+    /// `\n` as required by IEEE 488.2
+    #[default]
+    Lf,
+    /// `\r\n`, Eg. for line based terminals
+    CrLf,
+    /// No terminator, Eg. for length framed transports
+    None,
+}
+
+impl MessageTerminator {
+    /// End a response message with this terminator, see [Formatter::message_end].
+    pub fn message_end(self, formatter: &mut dyn Formatter) -> Result<()> {
+        match self {
+            MessageTerminator::Lf => formatter.message_end(),
+            MessageTerminator::CrLf => {
+                formatter.push_byte(b'\r')?;
+                formatter.message_end()
+            }
+            MessageTerminator::None => Ok(()),
+        }
+    }
+}
+
+/// A response which does not fit in the formatter can never be read by the controller,
+/// report a formatter overflow as a [ErrorCode::QueryDeadlocked] error (IEEE 488.2, 6.3.1.7).
+///
+/// Only applied to errors returned by the formatter, see [ResponseUnit::finish].
+pub(crate) fn query_deadlocked(err: Error) -> Error {
+    if err == ErrorCode::OutOfMemory {
+        ErrorCode::QueryDeadlocked.into()
+    } else {
+        err
+    }
+}
+
 /// A type which can be formatted for a SCPI response
 pub trait ResponseData {
     fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()>;
+
+    /// Returns true if the data produces no output.
+    /// Empty data is skipped by [ResponseUnit::data] without inserting a separator.
+    fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the value of real numeric data, [None] for any other data.
+    ///
+    /// Used to format a list of reals as a single block of IEEE 754 values when the numeric format
+    /// is [NumericFormat::Real32] or [NumericFormat::Real64].
+    fn real_value(&self) -> Option<f64> {
+        None
+    }
 }
 
 macro_rules! impl_non_decimal_data {
@@ -61,34 +155,226 @@ impl_integer!(u32);
 impl_integer!(i32);
 impl_integer!(u64);
 impl_integer!(i64);
+impl_integer!(u128);
+impl_integer!(i128);
 impl_integer!(isize);
 impl_integer!(usize);
 
 // Create formatters for floating point
+/// Decimal representation of a finite real number, `value = 0.d1d2d3... * 10^exp`
+struct Decimal {
+    negative: bool,
+    digits: [u8; 32],
+    len: usize,
+    exp: i32,
+}
+
+impl Decimal {
+    /// Parse the shortest representation written by lexical-core
+    fn parse(s: &[u8]) -> Self {
+        let mut dec = Decimal {
+            negative: false,
+            digits: [0; 32],
+            len: 0,
+            exp: 0,
+        };
+        let mut fraction = false;
+        for (i, &c) in s.iter().enumerate() {
+            match c {
+                b'-' => dec.negative = true,
+                b'.' => fraction = true,
+                b'0'..=b'9' => {
+                    let d = c - b'0';
+                    if dec.len == 0 && d == 0 {
+                        // Leading zero
+                        if fraction {
+                            dec.exp -= 1;
+                        }
+                    } else {
+                        if dec.len < dec.digits.len() {
+                            dec.digits[dec.len] = d;
+                            dec.len += 1;
+                        }
+                        if !fraction {
+                            dec.exp += 1;
+                        }
+                    }
+                }
+                b'e' | b'E' => {
+                    dec.exp += lexical_core::parse::<i32>(&s[i + 1..]).unwrap_or(0);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        dec.trim();
+        dec
+    }
+
+    /// Remove trailing zeroes
+    fn trim(&mut self) {
+        while self.len > 0 && self.digits[self.len - 1] == 0 {
+            self.len -= 1;
+        }
+    }
+
+    /// Round (half away from zero) to `n` significant digits
+    fn round(&mut self, n: i32) {
+        if n < 0 {
+            self.len = 0;
+        } else if (n as usize) < self.len {
+            let n = n as usize;
+            let round_up = self.digits[n] >= 5;
+            self.len = n;
+            if round_up {
+                let mut i = n;
+                loop {
+                    if i == 0 {
+                        // Carry out of the most significant digit
+                        self.digits[0] = 1;
+                        self.len = 1;
+                        self.exp += 1;
+                        break;
+                    }
+                    i -= 1;
+                    if self.digits[i] == 9 {
+                        self.digits[i] = 0;
+                    } else {
+                        self.digits[i] += 1;
+                        break;
+                    }
+                }
+            }
+            self.trim();
+        }
+    }
+
+    /// Digit at position `i` counted from the most significant digit
+    fn digit(&self, i: i32) -> u8 {
+        if i >= 0 && (i as usize) < self.len {
+            b'0' + self.digits[i as usize]
+        } else {
+            b'0'
+        }
+    }
+
+    fn push_sign(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        // Do not print negative zero
+        if self.negative && self.len > 0 {
+            formatter.push_byte(b'-')?;
+        }
+        Ok(())
+    }
+
+    fn format_fixed(mut self, decimals: usize, formatter: &mut dyn Formatter) -> Result<()> {
+        let decimals = decimals as i32;
+        self.round(self.exp.saturating_add(decimals));
+        self.push_sign(formatter)?;
+        if self.exp <= 0 {
+            formatter.push_byte(b'0')?;
+        }
+        for i in 0..self.exp {
+            formatter.push_byte(self.digit(i))?;
+        }
+        if decimals > 0 {
+            formatter.push_byte(b'.')?;
+            for i in 0..decimals {
+                formatter.push_byte(self.digit(self.exp + i))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn format_scientific(mut self, decimals: usize, formatter: &mut dyn Formatter) -> Result<()> {
+        let decimals = decimals as i32;
+        self.round(decimals.saturating_add(1));
+        self.push_sign(formatter)?;
+        formatter.push_byte(self.digit(0))?;
+        if decimals > 0 {
+            formatter.push_byte(b'.')?;
+            for i in 1..=decimals {
+                formatter.push_byte(self.digit(i))?;
+            }
+        }
+        let exp = if self.len > 0 { self.exp - 1 } else { 0 };
+        formatter.push_str(if exp < 0 { b"E-" } else { b"E+" })?;
+        if exp.unsigned_abs() < 10 {
+            formatter.push_byte(b'0')?;
+        }
+        exp.unsigned_abs().format_response_data(formatter)
+    }
+}
+
 macro_rules! impl_real {
     ($typ:ty) => {
         impl ResponseData for $typ {
             fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
-                if self.is_nan() {
-                    // NaN is represented by 9.91E+37
-                    formatter.push_str(b"9.91E+37")
-                } else if self.is_infinite() {
-                    // +/- Infinity is represented by +/-9.9E+37
-                    if self.is_sign_negative() {
-                        formatter.push_str(b"-9.9E+37")
-                    } else {
-                        formatter.push_str(b"9.9E+37")
+                match formatter.numeric_format() {
+                    NumericFormat::Real32 => {
+                        RealBlock(&[*self as f32]).format_response_data(formatter)
+                    }
+                    NumericFormat::Real64 => {
+                        RealBlock64(&[*self as f64]).format_response_data(formatter)
+                    }
+                    _ if !self.is_finite() => format_non_finite(*self as f64, formatter),
+                    NumericFormat::Ascii => {
+                        let mut buf = [b'0'; <$typ>::FORMATTED_SIZE_DECIMAL];
+                        let slc = lexical_core::write::<$typ>(*self, &mut buf);
+                        formatter.push_str(slc)
+                    }
+                    NumericFormat::Nr1 => Fixed(*self, 0).format_response_data(formatter),
+                    NumericFormat::Nr2(decimals) => {
+                        Fixed(*self, decimals as usize).format_response_data(formatter)
+                    }
+                    NumericFormat::Nr3(decimals) => {
+                        Scientific(*self, decimals as usize).format_response_data(formatter)
                     }
+                }
+            }
+
+            fn real_value(&self) -> Option<f64> {
+                Some(*self as f64)
+            }
+        }
+
+        impl ResponseData for Fixed<$typ> {
+            fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+                if !self.0.is_finite() {
+                    format_non_finite(self.0 as f64, formatter)
+                } else {
+                    let mut buf = [b'0'; <$typ>::FORMATTED_SIZE_DECIMAL];
+                    let slc = lexical_core::write::<$typ>(self.0, &mut buf);
+                    Decimal::parse(slc).format_fixed(self.1, formatter)
+                }
+            }
+        }
+
+        impl ResponseData for Scientific<$typ> {
+            fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+                if !self.0.is_finite() {
+                    format_non_finite(self.0 as f64, formatter)
                 } else {
                     let mut buf = [b'0'; <$typ>::FORMATTED_SIZE_DECIMAL];
-                    let slc = lexical_core::write::<$typ>(*self, &mut buf);
-                    formatter.push_str(slc)
+                    let slc = lexical_core::write::<$typ>(self.0, &mut buf);
+                    Decimal::parse(slc).format_scientific(self.1, formatter)
                 }
             }
         }
     };
 }
 
+fn format_non_finite(value: f64, formatter: &mut dyn Formatter) -> Result<()> {
+    if value.is_nan() {
+        // NaN is represented by 9.91E+37
+        formatter.push_str(b"9.91E+37")
+    } else if value.is_sign_negative() {
+        // +/- Infinity is represented by +/-9.9E+37
+        formatter.push_str(b"-9.9E+37")
+    } else {
+        formatter.push_str(b"9.9E+37")
+    }
+}
+
 impl_real!(f32);
 impl_real!(f64);
 
@@ -102,27 +388,70 @@ impl ResponseData for bool {
     }
 }
 
+/// Header of a definite length arbitrary block, `#<ndigits><len>`
+fn format_block_header(len: usize, formatter: &mut dyn Formatter) -> Result<()> {
+    let mut buf = [0u8; usize::FORMATTED_SIZE_DECIMAL];
+    let slc = lexical_core::write::<usize>(len, &mut buf);
+    if slc.len() > 9 {
+        Err(ErrorCode::ExecutionError.into())
+    } else {
+        formatter.push_byte(b'#')?;
+        slc.len().format_response_data(formatter)?;
+        formatter.push_str(slc)
+    }
+}
+
 impl<'a> ResponseData for Arbitrary<'a> {
     fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
-        let mut buf = [0u8; usize::FORMATTED_SIZE_DECIMAL];
-        let slc = lexical_core::write::<usize>(self.0.len(), &mut buf);
-        if slc.len() > 9 {
-            Err(ErrorCode::ExecutionError.into())
-        } else {
-            formatter.push_byte(b'#')?;
-            slc.len().format_response_data(formatter)?;
-            formatter.push_str(slc)?;
-            formatter.push_str(self.0)
-        }
+        format_block_header(self.0.len(), formatter)?;
+        formatter.push_str(self.0)
     }
 }
 
+macro_rules! impl_real_block {
+    ($name:ident, $typ:ty) => {
+        impl ResponseData for $name<'_> {
+            fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+                let len = self
+                    .0
+                    .len()
+                    .checked_mul(core::mem::size_of::<$typ>())
+                    .ok_or(ErrorCode::ExecutionError)?;
+                format_block_header(len, formatter)?;
+                let byte_order = formatter.byte_order();
+                for x in self.0 {
+                    match byte_order {
+                        ByteOrder::Normal => formatter.push_str(&x.to_be_bytes())?,
+                        ByteOrder::Swapped => formatter.push_str(&x.to_le_bytes())?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_real_block!(RealBlock, f32);
+impl_real_block!(RealBlock64, f64);
+
 impl<'a> ResponseData for Character<'a> {
     fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
         formatter.push_ascii(self.0)
     }
 }
 
+impl<'a> ResponseData for Raw<'a> {
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        formatter.push_str(self.0)
+    }
+}
+
+impl ResponseData for SingleChar {
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        formatter.push_ascii(&[self.0])
+    }
+}
+
 impl<'a> ResponseData for Expression<'a> {
     fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
         formatter.push_byte(b'(')?;
@@ -131,26 +460,19 @@ impl<'a> ResponseData for Expression<'a> {
     }
 }
 
-impl<'a> ResponseData for &'a str {
+impl ResponseData for &str {
     fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
         Arbitrary(self.as_bytes()).format_response_data(formatter)
     }
 }
 
-impl<'a> ResponseData for &'a [u8] {
+impl ResponseData for &[u8] {
     fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
         if !self.is_ascii() {
             Err(ErrorCode::ExecutionError.into())
         } else {
-            let mut first = true;
             formatter.push_byte(b'"')?;
-            for ss in self.split(|x| *x == b'"') {
-                if !first {
-                    formatter.push_str(br#""""#)?;
-                }
-                formatter.push_ascii(ss)?;
-                first = false;
-            }
+            formatter.push_ascii_escaped(self)?;
             formatter.push_byte(b'"')
         }
     }
@@ -163,9 +485,9 @@ impl ResponseData for Error {
 
         if let Some(ext) = self.get_extended() {
             formatter.push_byte(b'"')?;
-            formatter.push_str(self.get_message())?;
+            formatter.push_ascii_escaped(self.get_message())?;
             formatter.push_byte(b';')?;
-            formatter.push_str(ext)?;
+            formatter.push_ascii_escaped(ext)?;
             formatter.push_byte(b'"')
         } else {
             self.get_message().format_response_data(formatter)
@@ -184,6 +506,143 @@ where
     }
 }
 
+/// Format the non-empty `items` as a single definite length block of IEEE 754 values if the
+/// numeric format is [NumericFormat::Real32] or [NumericFormat::Real64] and all of them are
+/// real numeric data (see [ResponseData::real_value]).
+///
+/// Returns [None] if the list must be formatted element by element instead.
+fn format_real_list<T>(items: &[T], formatter: &mut dyn Formatter) -> Option<Result<()>>
+where
+    T: ResponseData,
+{
+    let size = match formatter.numeric_format() {
+        NumericFormat::Real32 => core::mem::size_of::<f32>(),
+        NumericFormat::Real64 => core::mem::size_of::<f64>(),
+        _ => return None,
+    };
+    let reals = items
+        .iter()
+        .filter(|x| !x.is_empty())
+        .map(|x| x.real_value());
+    if reals.clone().any(|x| x.is_none()) {
+        return None;
+    }
+    Some(format_reals(reals.flatten(), size, formatter))
+}
+
+/// Definite length block of `reals` with `size` bytes each
+fn format_reals<I>(reals: I, size: usize, formatter: &mut dyn Formatter) -> Result<()>
+where
+    I: Iterator<Item = f64> + Clone,
+{
+    let len = reals
+        .clone()
+        .count()
+        .checked_mul(size)
+        .ok_or(ErrorCode::ExecutionError)?;
+    format_block_header(len, formatter)?;
+    let byte_order = formatter.byte_order();
+    for x in reals {
+        match (size, byte_order) {
+            (4, ByteOrder::Normal) => formatter.push_str(&(x as f32).to_be_bytes())?,
+            (4, ByteOrder::Swapped) => formatter.push_str(&(x as f32).to_le_bytes())?,
+            (_, ByteOrder::Normal) => formatter.push_str(&x.to_be_bytes())?,
+            (_, ByteOrder::Swapped) => formatter.push_str(&x.to_le_bytes())?,
+        }
+    }
+    Ok(())
+}
+
+/// Formats each element separated by a data separator, an empty list produces no output.
+///
+/// A list of reals is formatted as a single block with a binary numeric format, see [NumericFormat].
+impl<'a, T> ResponseData for List<'a, T>
+where
+    T: ResponseData,
+{
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        if let Some(res) = format_real_list(self.0, formatter) {
+            return res;
+        }
+        let mut first = true;
+        for x in self.0.iter().filter(|x| !x.is_empty()) {
+            if !first {
+                formatter.data_separator()?;
+            }
+            x.format_response_data(formatter)?;
+            first = false;
+        }
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|x| x.is_empty())
+    }
+}
+
+/// Same as [List]
+impl<T, const N: usize> ResponseData for [T; N]
+where
+    T: ResponseData,
+{
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        List(&self[..]).format_response_data(formatter)
+    }
+
+    fn is_empty(&self) -> bool {
+        List(&self[..]).is_empty()
+    }
+}
+
+/// Formats the inner value if any, [None] produces no output.
+impl<T> ResponseData for Option<T>
+where
+    T: ResponseData,
+{
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        match self {
+            Some(x) => x.format_response_data(formatter),
+            None => Ok(()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.as_ref().is_none_or(|x| x.is_empty())
+    }
+
+    fn real_value(&self) -> Option<f64> {
+        self.as_ref().and_then(|x| x.real_value())
+    }
+}
+
+/// Formats the value if [Ok], a [Err] produces the SCPI NaN value `9.91E+37` instead.
+///
+/// Useful when a single element of a response may fail, Eg. a reading of one channel in a channel
+/// list, without aborting the whole response. The error itself is not reported, it must be queued
+/// separately by the command.
+impl<T> ResponseData for core::result::Result<T, Error>
+where
+    T: ResponseData,
+{
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        match self {
+            Ok(x) => x.format_response_data(formatter),
+            Err(_) => f64::NAN.format_response_data(formatter),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.as_ref().is_ok_and(|x| x.is_empty())
+    }
+
+    fn real_value(&self) -> Option<f64> {
+        match self {
+            Ok(x) => x.real_value(),
+            Err(_) => Some(f64::NAN),
+        }
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl<T> ResponseData for alloc::vec::Vec<T>
 where
@@ -195,6 +654,9 @@ where
         let first = it
             .next()
             .ok_or_else(|| Error::new(ErrorCode::DeviceSpecificError))?;
+        if let Some(res) = format_real_list(self, formatter) {
+            return res;
+        }
         first.format_response_data(formatter)?;
         for func in it {
             formatter.push_byte(b',')?;
@@ -215,8 +677,11 @@ where
         let first = it
             .next()
             .ok_or_else(|| Error::new(ErrorCode::DeviceSpecificError))?;
+        if let Some(res) = format_real_list(self, formatter) {
+            return res;
+        }
         first.format_response_data(formatter)?;
-        while let Some(func) = it.next() {
+        for func in it {
             formatter.push_byte(b',')?;
             func.format_response_data(formatter)?;
         }
@@ -224,12 +689,31 @@ where
     }
 }
 
+/// Same as [List]
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> ResponseData for heapless::Vec<T, N>
+where
+    T: ResponseData,
+{
+    fn format_response_data(&self, formatter: &mut dyn Formatter) -> Result<()> {
+        List(&self[..]).format_response_data(formatter)
+    }
+
+    fn is_empty(&self) -> bool {
+        List(&self[..]).is_empty()
+    }
+}
+
 /// Formats a SCPI response
 ///
 pub trait Formatter {
     /* I/O */
 
     /// Push raw string to output
+    ///
+    /// A formatter with a fixed capacity (Eg. `ArrayVec`) must return [ErrorCode::OutOfMemory]
+    /// if `s` does not fit instead of truncating it, the message then fails with a
+    /// [ErrorCode::QueryDeadlocked] error, see [crate::tree::Node::run].
     fn push_str(&mut self, s: &[u8]) -> Result<()>;
 
     /// Push ascii to output, panics if
@@ -238,7 +722,23 @@ pub trait Formatter {
         self.push_str(s)
     }
 
+    /// Push ascii to output with any double quotes escaped (`"` => `""`),
+    /// used for the contents of string response data.
+    fn push_ascii_escaped(&mut self, s: &[u8]) -> Result<()> {
+        let mut first = true;
+        for ss in s.split(|x| *x == b'"') {
+            if !first {
+                self.push_str(br#""""#)?;
+            }
+            self.push_ascii(ss)?;
+            first = false;
+        }
+        Ok(())
+    }
+
     ///Push single byte to output
+    ///
+    /// Same as [Self::push_str], returns [ErrorCode::OutOfMemory] if the byte does not fit.
     fn push_byte(&mut self, b: u8) -> Result<()>;
 
     /// Get underlying buffer as a byte slice
@@ -250,6 +750,20 @@ pub trait Formatter {
     /// Returns length of buffer
     fn len(&self) -> usize;
 
+    /// Format of real numeric response data, see [NumericFormat].
+    ///
+    /// Formatters always use ASCII, the format of the [crate::Context] is applied by [ResponseUnit::data].
+    fn numeric_format(&self) -> NumericFormat {
+        NumericFormat::Ascii
+    }
+
+    /// Byte order of binary numeric response data, see [ByteOrder].
+    ///
+    /// Formatters always use [ByteOrder::Normal], the byte order of the [crate::Context] is applied by [ResponseUnit::data].
+    fn byte_order(&self) -> ByteOrder {
+        ByteOrder::Normal
+    }
+
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -274,7 +788,23 @@ pub trait Formatter {
         self.push_byte(RESPONSE_HEADER_SEPARATOR)
     }
 
-    fn response_unit(&mut self) -> Result<ResponseUnit>;
+    /// Start a IEEE488.2 indefinite length arbitrary block (`#0`).
+    ///
+    /// The block is terminated by a NL which also ends the response message (see [Self::end_indefinite_block])
+    /// and must therefore be the last data in the response message.
+    fn begin_indefinite_block(&mut self) -> Result<()> {
+        self.push_str(b"#0")
+    }
+
+    /// End a indefinite length arbitrary block started with [Self::begin_indefinite_block].
+    ///
+    /// Writes the terminating NL using [Self::message_end], the response message is then complete and
+    /// no other message terminator (see [MessageTerminator]) must be written.
+    fn end_indefinite_block(&mut self) -> Result<()> {
+        self.message_end()
+    }
+
+    fn response_unit(&mut self) -> Result<ResponseUnit<'_>>;
 }
 
 /// A response unit returned by a query
@@ -283,14 +813,52 @@ pub struct ResponseUnit<'a> {
     result: Result<()>,
     has_header: bool,
     has_data: bool,
+    in_block: bool,
+    numeric_format: NumericFormat,
+    byte_order: ByteOrder,
+    block_ended: Option<&'a Cell<bool>>,
 }
 
 impl<'a> ResponseUnit<'a> {
+    /// Set format and byte order of numeric response data
+    pub(crate) fn with_data_format(
+        self,
+        numeric_format: NumericFormat,
+        byte_order: ByteOrder,
+    ) -> Self {
+        Self {
+            numeric_format,
+            byte_order,
+            ..self
+        }
+    }
+
+    /// Set `block_ended` when an indefinite length block has ended the response message
+    pub(crate) fn with_block_ended(self, block_ended: &'a Cell<bool>) -> Self {
+        Self {
+            block_ended: Some(block_ended),
+            ..self
+        }
+    }
+
+    /// Nothing may follow an indefinite length block, see [Self::begin_indefinite_block]
+    fn check_not_in_block(&mut self) -> bool {
+        if self.in_block {
+            self.result = self.result.and(Err(
+                ErrorCode::QueryUnterminatedAfterIndefiniteResponse.into()
+            ));
+        }
+        !self.in_block
+    }
+
     /// Response header
     ///
     /// **Warning**: Panics if called after [`Self::data`]
     pub fn header(&mut self, header: &[u8]) -> &mut Self {
         debug_assert!(!self.has_data, "Tried to put header after data");
+        if !self.check_not_in_block() {
+            return self;
+        }
         self.result = self.result.and_then(|_| {
             if self.has_header {
                 self.fmt.push_byte(b':')?;
@@ -303,25 +871,177 @@ impl<'a> ResponseUnit<'a> {
 
     /// A piece of data be returned
     ///
-    /// Can be called multiple times.
+    /// Can be called multiple times. Empty data (see [ResponseData::is_empty]) such as [None] is skipped.
     pub fn data<U>(&mut self, data: U) -> &mut Self
     where
         U: ResponseData,
     {
+        if data.is_empty() || !self.check_not_in_block() {
+            return self;
+        }
         self.result = self.result.and_then(|_| {
             if self.has_data {
                 self.fmt.data_separator()?;
             } else if self.has_header {
                 self.fmt.header_separator()?;
             }
-            data.format_response_data(self.fmt)
+            match (self.numeric_format, self.byte_order) {
+                (NumericFormat::Ascii, ByteOrder::Normal) => data.format_response_data(self.fmt),
+                (numeric_format, byte_order) => data.format_response_data(&mut WithDataFormat {
+                    fmt: self.fmt,
+                    numeric_format,
+                    byte_order,
+                }),
+            }
         });
         self.has_data = true;
         self
     }
 
-    /// Finish the response unit and return any error
+    /// Definite length arbitrary block (`#<n><len><data>`) of `len` bytes written by `f`.
+    ///
+    /// Useful when the block data is not available as a single slice, Eg. when reading a file.
+    /// Returns an `Execution error` if `f` does not write exactly `len` bytes.
+    pub fn block<F>(&mut self, len: usize, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut dyn Formatter) -> Result<()>,
+    {
+        if !self.check_not_in_block() {
+            return self;
+        }
+        self.result = self.result.and_then(|_| {
+            if self.has_data {
+                self.fmt.data_separator()?;
+            } else if self.has_header {
+                self.fmt.header_separator()?;
+            }
+            format_block_header(len, self.fmt)?;
+            let start = self.fmt.len();
+            f(self.fmt)?;
+            if self.fmt.len() - start != len {
+                return Err(ErrorCode::ExecutionError.into());
+            }
+            Ok(())
+        });
+        self.has_data = true;
+        self
+    }
+
+    /// Start an indefinite length arbitrary block (`#0<data><NL>`), useful when the length of the
+    /// data is not known beforehand. Write the block contents with [Self::block_data].
+    ///
+    /// The NL terminating the block also ends the response message, see [Formatter::end_indefinite_block].
+    /// The block must therefore be the last data in the response message, any other data in this unit
+    /// returns a `Query UNTERMINATED after indefinite response` error from [Self::finish], as does any
+    /// following query in the same program message (IEEE 488.2, 6.5.7.5).
+    pub fn begin_indefinite_block(&mut self) -> &mut Self {
+        if !self.check_not_in_block() {
+            return self;
+        }
+        self.result = self.result.and_then(|_| {
+            if self.has_data {
+                self.fmt.data_separator()?;
+            } else if self.has_header {
+                self.fmt.header_separator()?;
+            }
+            self.fmt.begin_indefinite_block()
+        });
+        self.has_data = true;
+        self.in_block = true;
+        self
+    }
+
+    /// Write raw data into an indefinite length arbitrary block.
+    ///
+    /// Can be called multiple times.
+    pub fn block_data(&mut self, data: &[u8]) -> &mut Self {
+        debug_assert!(
+            self.in_block,
+            "Tried to put block data outside indefinite block"
+        );
+        self.result = self.result.and_then(|_| self.fmt.push_str(data));
+        self
+    }
+
+    /// Finish the response unit and return any error.
+    ///
+    /// A response which did not fit in the formatter returns a [ErrorCode::QueryDeadlocked] error.
     pub fn finish(&mut self) -> Result<()> {
+        if self.in_block {
+            // Always terminate the block, even if data was rejected after it
+            let end = self.fmt.end_indefinite_block();
+            self.result = self.result.and(end);
+            self.in_block = false;
+            if let Some(block_ended) = self.block_ended {
+                block_ended.set(true);
+            }
+        }
+        self.result = self.result.map_err(query_deadlocked);
         self.result
     }
 }
+
+/// Formatter overriding the numeric format and byte order of another formatter
+struct WithDataFormat<'a> {
+    fmt: &'a mut dyn Formatter,
+    numeric_format: NumericFormat,
+    byte_order: ByteOrder,
+}
+
+impl Formatter for WithDataFormat<'_> {
+    fn push_str(&mut self, s: &[u8]) -> Result<()> {
+        self.fmt.push_str(s)
+    }
+
+    fn push_byte(&mut self, b: u8) -> Result<()> {
+        self.fmt.push_byte(b)
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        self.fmt.as_slice()
+    }
+
+    fn clear(&mut self) {
+        self.fmt.clear()
+    }
+
+    fn len(&self) -> usize {
+        self.fmt.len()
+    }
+
+    fn numeric_format(&self) -> NumericFormat {
+        self.numeric_format
+    }
+
+    fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    fn message_start(&mut self) -> Result<()> {
+        self.fmt.message_start()
+    }
+
+    fn message_end(&mut self) -> Result<()> {
+        self.fmt.message_end()
+    }
+
+    fn data_separator(&mut self) -> Result<()> {
+        self.fmt.data_separator()
+    }
+
+    fn header_separator(&mut self) -> Result<()> {
+        self.fmt.header_separator()
+    }
+
+    fn begin_indefinite_block(&mut self) -> Result<()> {
+        self.fmt.begin_indefinite_block()
+    }
+
+    fn end_indefinite_block(&mut self) -> Result<()> {
+        self.fmt.end_indefinite_block()
+    }
+
+    fn response_unit(&mut self) -> Result<ResponseUnit<'_>> {
+        self.fmt.response_unit()
+    }
+}
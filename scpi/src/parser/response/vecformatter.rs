@@ -1,7 +1,8 @@
 use crate::error::Result;
 
 use super::{
-    Formatter, ResponseUnit, RESPONSE_MESSAGE_TERMINATOR, RESPONSE_MESSAGE_UNIT_SEPARATOR,
+    ByteOrder, Formatter, NumericFormat, ResponseUnit, RESPONSE_MESSAGE_TERMINATOR,
+    RESPONSE_MESSAGE_UNIT_SEPARATOR,
 };
 
 impl Formatter for alloc::vec::Vec<u8> {
@@ -36,7 +37,7 @@ impl Formatter for alloc::vec::Vec<u8> {
         self.push_byte(RESPONSE_MESSAGE_TERMINATOR)
     }
 
-    fn response_unit(&mut self) -> Result<ResponseUnit> {
+    fn response_unit(&mut self) -> Result<ResponseUnit<'_>> {
         if !self.is_empty() {
             self.push_byte(RESPONSE_MESSAGE_UNIT_SEPARATOR)?;
         }
@@ -45,6 +46,10 @@ impl Formatter for alloc::vec::Vec<u8> {
             result: Ok(()),
             has_header: false,
             has_data: false,
+            in_block: false,
+            numeric_format: NumericFormat::Ascii,
+            byte_order: ByteOrder::Normal,
+            block_ended: None,
         })
     }
 }
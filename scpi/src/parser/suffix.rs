@@ -75,6 +75,339 @@ where
     }
 }
 
+/// Numeric data with an optional suffix left for the command to interpret.
+///
+/// Plain numeric types reject suffixed data with `Suffix not allowed`, wrapping them opts into
+/// accepting a suffix. The value is parsed without the suffix, see [WithSuffix::unit] to interpret it.
+///
+/// ```
+/// # use scpi::parser::{suffix::WithSuffix, tokenizer::Token};
+/// let value = WithSuffix::<f32>::try_from(Token::DecimalNumericSuffixProgramData(b"1", b"MHZ")).unwrap();
+/// assert_eq!(value.value, 1.0);
+/// assert_eq!(value.suffix, Some(&b"MHZ"[..]));
+/// ```
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct WithSuffix<'a, T> {
+    /// Numeric value without suffix
+    pub value: T,
+    /// Suffix, `None` if the data had no suffix
+    pub suffix: Option<&'a [u8]>,
+}
+
+impl<T> WithSuffix<'_, T> {
+    /// Multiplier and base unit of the suffix, see [parse_suffix].
+    ///
+    /// Returns `Ok(None)` if the data had no suffix and an `Illegal parameter value` error if
+    /// the suffix is not recognized.
+    pub fn unit(&self) -> Result<Option<(f64, SuffixUnit)>, Error> {
+        self.suffix
+            .map(|suffix| parse_suffix(suffix).ok_or(ErrorCode::IllegalParameterValue.into()))
+            .transpose()
+    }
+}
+
+impl<'a, T> TryFrom<Token<'a>> for WithSuffix<'a, T>
+where
+    T: TryFrom<Token<'a>, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: Token<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Token::DecimalNumericSuffixProgramData(num, suffix) => Ok(Self {
+                value: T::try_from(Token::DecimalNumericProgramData(num))?,
+                suffix: Some(suffix),
+            }),
+            t => Ok(Self {
+                value: T::try_from(t)?,
+                suffix: None,
+            }),
+        }
+    }
+}
+
+/// Base unit of a suffix, see [parse_suffix].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SuffixUnit {
+    /// `V`
+    Volt,
+    /// `A`
+    Ampere,
+    /// `W`
+    Watt,
+    /// `J`
+    Joule,
+    /// `OHM`
+    Ohm,
+    /// `SIE`
+    Siemens,
+    /// `F`
+    Farad,
+    /// `H`
+    Henry,
+    /// `C`
+    Coulomb,
+    /// `HZ`
+    Hertz,
+    /// `S`
+    Second,
+    /// `RAD`
+    Radian,
+    /// `DEG`
+    Degree,
+    /// `%` or `PCT`, with a multiplier of 0.01
+    #[cfg(feature = "unit-ratio")]
+    Ratio,
+}
+
+/// Base units, longer suffixes must be checked first (Eg. `SIE` before `S`).
+const SUFFIX_UNITS: &[(&[u8], SuffixUnit)] = &[
+    (b"OHM", SuffixUnit::Ohm),
+    (b"SIE", SuffixUnit::Siemens),
+    (b"RAD", SuffixUnit::Radian),
+    (b"DEG", SuffixUnit::Degree),
+    (b"HZ", SuffixUnit::Hertz),
+    (b"V", SuffixUnit::Volt),
+    (b"A", SuffixUnit::Ampere),
+    (b"W", SuffixUnit::Watt),
+    (b"J", SuffixUnit::Joule),
+    (b"F", SuffixUnit::Farad),
+    (b"H", SuffixUnit::Henry),
+    (b"C", SuffixUnit::Coulomb),
+    (b"S", SuffixUnit::Second),
+];
+
+/// Multiplier prefixes, see IEEE 488.2 7.7.3.3.
+/// Note that `M` is milli and `MA` is mega.
+const SUFFIX_MULTIPLIERS: &[(&[u8], f64)] = &[
+    (b"", 1.0),
+    (b"EX", 1e18),
+    (b"PE", 1e15),
+    (b"T", 1e12),
+    (b"G", 1e9),
+    (b"MA", 1e6),
+    (b"K", 1e3),
+    (b"M", 1e-3),
+    (b"U", 1e-6),
+    (b"N", 1e-9),
+    (b"P", 1e-12),
+    (b"F", 1e-15),
+    (b"A", 1e-18),
+];
+
+/// Parse a suffix into a decimal multiplier and a base unit.
+/// Suffixes are case-insensitive.
+///
+/// `MHZ` and `MOHM` are interpreted as mega-hertz and mega-ohm as customary in SCPI.
+/// With the `unit-ratio` feature `%` and `PCT` are a ratio with a multiplier of 0.01.
+///
+/// Returns `None` if the suffix is not recognized.
+///
+/// ```
+/// # use scpi::parser::suffix::{parse_suffix, SuffixUnit};
+/// assert_eq!(parse_suffix(b"MV"), Some((1e-3, SuffixUnit::Volt)));
+/// assert_eq!(parse_suffix(b"KHZ"), Some((1e3, SuffixUnit::Hertz)));
+/// assert_eq!(parse_suffix(b"POTATO"), None);
+/// ```
+pub fn parse_suffix(s: &[u8]) -> Option<(f64, SuffixUnit)> {
+    // Special cases
+    if s.eq_ignore_ascii_case(b"MHZ") {
+        return Some((1e6, SuffixUnit::Hertz));
+    } else if s.eq_ignore_ascii_case(b"MOHM") {
+        return Some((1e6, SuffixUnit::Ohm));
+    }
+    #[cfg(feature = "unit-ratio")]
+    if s == b"%" || s.eq_ignore_ascii_case(b"PCT") {
+        return Some((1e-2, SuffixUnit::Ratio));
+    }
+
+    SUFFIX_UNITS.iter().find_map(|(unit_suffix, unit)| {
+        let split = s.len().checked_sub(unit_suffix.len())?;
+        let (prefix, rest) = s.split_at(split);
+        if !rest.eq_ignore_ascii_case(unit_suffix) {
+            return None;
+        }
+        SUFFIX_MULTIPLIERS
+            .iter()
+            .find(|(multiplier_prefix, _)| prefix.eq_ignore_ascii_case(multiplier_prefix))
+            .map(|(_, multiplier)| (*multiplier, *unit))
+    })
+}
+
+/// Convert numeric data with an optional time suffix into a [core::time::Duration], Eg. `1.5MS` or `500US`.
+/// Data without a suffix is in seconds.
+///
+/// Useful when only durations are needed and the `unit-time` feature (and uom) isn't wanted.
+/// The duration is rounded to the nearest nanosecond.
+///
+/// ```
+/// # use scpi::parser::tokenizer::Token;
+/// use core::time::Duration;
+///
+/// let duration = Duration::try_from(Token::DecimalNumericSuffixProgramData(b"1.5", b"MS")).unwrap();
+/// assert_eq!(duration, Duration::from_micros(1500));
+/// ```
+///
+/// # Returns
+/// * `Err(InvalidSuffix)` - If the suffix is not a time suffix.
+/// * `Err(DataOutOfRange)` - If the duration is negative, not finite or too large.
+/// * `Err(DataTypeError)` - If the data is not numeric.
+impl<'a> TryFrom<Token<'a>> for core::time::Duration {
+    type Error = Error;
+
+    fn try_from(value: Token<'a>) -> Result<Self, Self::Error> {
+        let seconds = match value {
+            Token::DecimalNumericProgramData(_) => f64::try_from(value)?,
+            Token::DecimalNumericSuffixProgramData(num, suffix) => match parse_suffix(suffix) {
+                Some((multiplier, SuffixUnit::Second)) => {
+                    f64::try_from(Token::DecimalNumericProgramData(num))? * multiplier
+                }
+                _ => return Err(ErrorCode::InvalidSuffix.into()),
+            },
+            _ => return Err(ErrorCode::DataTypeError.into()),
+        };
+        if !(0.0..(u64::MAX as f64)).contains(&seconds) {
+            return Err(ErrorCode::DataOutOfRange.into());
+        }
+        let secs = seconds as u64;
+        let nanos = ((seconds - secs as f64) * 1e9 + 0.5) as u32;
+        Ok(core::time::Duration::new(secs, nanos))
+    }
+}
+
+#[cfg(test)]
+mod test_parse_suffix {
+    use super::*;
+
+    #[test]
+    fn test_parse_suffix() {
+        assert_eq!(parse_suffix(b"MV"), Some((1e-3, SuffixUnit::Volt)));
+        assert_eq!(parse_suffix(b"mv"), Some((1e-3, SuffixUnit::Volt)));
+        assert_eq!(parse_suffix(b"V"), Some((1.0, SuffixUnit::Volt)));
+        assert_eq!(parse_suffix(b"KHZ"), Some((1e3, SuffixUnit::Hertz)));
+        assert_eq!(parse_suffix(b"MHZ"), Some((1e6, SuffixUnit::Hertz)));
+        assert_eq!(parse_suffix(b"MAHZ"), Some((1e6, SuffixUnit::Hertz)));
+        assert_eq!(parse_suffix(b"US"), Some((1e-6, SuffixUnit::Second)));
+        assert_eq!(parse_suffix(b"MA"), Some((1e-3, SuffixUnit::Ampere)));
+        assert_eq!(parse_suffix(b"MAA"), Some((1e6, SuffixUnit::Ampere)));
+        assert_eq!(parse_suffix(b"MSIE"), Some((1e-3, SuffixUnit::Siemens)));
+        assert_eq!(parse_suffix(b"MOHM"), Some((1e6, SuffixUnit::Ohm)));
+        assert_eq!(parse_suffix(b"KOHM"), Some((1e3, SuffixUnit::Ohm)));
+        assert_eq!(parse_suffix(b"PF"), Some((1e-12, SuffixUnit::Farad)));
+        // Unknown
+        assert_eq!(parse_suffix(b""), None);
+        assert_eq!(parse_suffix(b"XV"), None);
+        assert_eq!(parse_suffix(b"POTATO"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "unit-ratio")]
+    fn test_parse_suffix_ratio() {
+        assert_eq!(parse_suffix(b"%"), Some((0.01, SuffixUnit::Ratio)));
+        assert_eq!(parse_suffix(b"pct"), Some((0.01, SuffixUnit::Ratio)));
+        assert_eq!(parse_suffix(b"K%"), None);
+    }
+}
+
+#[cfg(test)]
+mod test_with_suffix {
+    use super::*;
+
+    #[test]
+    fn test_with_suffix() {
+        let value =
+            WithSuffix::<f32>::try_from(Token::DecimalNumericSuffixProgramData(b"1", b"MHZ"));
+        assert_eq!(
+            value,
+            Ok(WithSuffix {
+                value: 1.0,
+                suffix: Some(b"MHZ")
+            })
+        );
+        assert_eq!(value.unwrap().unit(), Ok(Some((1e6, SuffixUnit::Hertz))));
+
+        // Suffix is optional
+        let value = WithSuffix::<u8>::try_from(Token::DecimalNumericProgramData(b"5")).unwrap();
+        assert_eq!(value.value, 5);
+        assert_eq!(value.unit(), Ok(None));
+
+        // Unknown suffix
+        let value = WithSuffix::<u8>::try_from(Token::DecimalNumericSuffixProgramData(b"5", b"XV"))
+            .unwrap();
+        assert_eq!(value.unit(), Err(ErrorCode::IllegalParameterValue.into()));
+
+        // Value is still checked
+        assert_eq!(
+            WithSuffix::<u8>::try_from(Token::DecimalNumericSuffixProgramData(b"256", b"V")),
+            Err(ErrorCode::DataOutOfRange.into())
+        );
+        assert_eq!(
+            WithSuffix::<u8>::try_from(Token::StringProgramData(b"5", b'"')),
+            Err(ErrorCode::DataTypeError.into())
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_duration {
+    use super::*;
+    use core::time::Duration;
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericSuffixProgramData(b"1.5", b"MS")),
+            Ok(Duration::from_micros(1500))
+        );
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericSuffixProgramData(b"500", b"us")),
+            Ok(Duration::from_micros(500))
+        );
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericSuffixProgramData(b"2", b"KS")),
+            Ok(Duration::from_secs(2000))
+        );
+        // Seconds by default
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericProgramData(b"2.25")),
+            Ok(Duration::from_millis(2250))
+        );
+        // Rounded to nearest nanosecond
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericSuffixProgramData(b"1.6", b"PS")),
+            Ok(Duration::ZERO)
+        );
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericSuffixProgramData(b"0.3", b"US")),
+            Ok(Duration::from_nanos(300))
+        );
+    }
+
+    #[test]
+    fn test_duration_errors() {
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericSuffixProgramData(b"1", b"V")),
+            Err(ErrorCode::InvalidSuffix.into())
+        );
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericSuffixProgramData(b"1", b"POTATO")),
+            Err(ErrorCode::InvalidSuffix.into())
+        );
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericProgramData(b"-1")),
+            Err(ErrorCode::DataOutOfRange.into())
+        );
+        assert_eq!(
+            Duration::try_from(Token::DecimalNumericProgramData(b"1e30")),
+            Err(ErrorCode::DataOutOfRange.into())
+        );
+        assert_eq!(
+            Duration::try_from(Token::StringProgramData(b"1", b'"')),
+            Err(ErrorCode::DataTypeError.into())
+        );
+    }
+}
+
 #[cfg(feature = "unit-angle")]
 mod angle {
     use super::*;
@@ -260,7 +593,7 @@ mod ratio {
     use uom::si::ratio::{part_per_million, percent, ratio, Ratio};
 
     impl_unit![uom::si::ratio::Conversion<V>, Ratio, ratio;
-        b"PCT" => percent,
+        b"PCT"|b"%" => percent,
         b"PPM" => part_per_million
     ];
 
@@ -315,6 +648,32 @@ mod frequency {
     ];
 }
 
+#[cfg(all(feature = "unit-frequency", feature = "alloc", test))]
+mod test_unit_response {
+    use crate::parser::response::ResponseData;
+    use alloc::vec::Vec;
+    use uom::si::{
+        f32::Frequency,
+        frequency::{hertz, kilohertz},
+    };
+
+    #[test]
+    fn test_frequency_response() {
+        let mut buf = Vec::new();
+        Frequency::new::<hertz>(1000.0)
+            .format_response_data(&mut buf)
+            .unwrap();
+        assert_eq!(buf, b"1000.0");
+
+        // Base unit
+        let mut buf = Vec::new();
+        Frequency::new::<kilohertz>(2.5)
+            .format_response_data(&mut buf)
+            .unwrap();
+        assert_eq!(buf, b"2500.0");
+    }
+}
+
 #[cfg(all(feature = "unit-electric-potential", test))]
 mod test_suffix {
 
@@ -448,6 +807,8 @@ macro_rules! impl_unit {
             }
         }
 
+        /// Formats the value in the SI base unit (without a suffix),
+        /// NaN and infinities are formatted as for plain reals.
         impl<U, V> ResponseData for $unit<U, V> where U: Units<V> + ?Sized, V: Num + Conversion<V> + ResponseData {
             fn format_response_data(
                 &self,
@@ -455,6 +816,10 @@ macro_rules! impl_unit {
             ) -> core::result::Result<(), Error> {
                 self.value.format_response_data(formatter)
             }
+
+            fn real_value(&self) -> Option<f64> {
+                self.value.real_value()
+            }
         }
 
         // impl<U, V, T> NumericValueDefaults for $unit<U, V>
@@ -502,11 +867,35 @@ macro_rules! impl_unit {
                     .try_into();
                 assert_eq!(l, Err(Error::from(ErrorCode::IllegalParameterValue)));
                 // Do not accept incorrect datatype
-                let l: Result<$unit, Error> = Token::StringProgramData(b"STRING").try_into();
+                let l: Result<$unit, Error> = Token::StringProgramData(b"STRING", b'"').try_into();
                 assert_eq!(l, Err(Error::from(ErrorCode::DataTypeError)))
                 // Do not accept
             }
 
+            #[test]
+            fn test_response_data() {
+                extern crate alloc;
+
+                let format = |data: &dyn Fn(&mut dyn Formatter) -> Result<(), Error>| {
+                    let mut buf = alloc::vec::Vec::new();
+                    data(&mut buf).unwrap();
+                    buf
+                };
+                $(
+                $(
+                let l: $unit = Token::DecimalNumericSuffixProgramData(b"2.5", $suffix)
+                    .try_into()
+                    .unwrap();
+                assert_eq!(
+                    format(&|f| l.format_response_data(f)),
+                    format(&|f| l.value.format_response_data(f))
+                );
+                )+
+                )+
+                let nan = $unit::new::<super::$base>(f32::NAN);
+                assert_eq!(format(&|f| nan.format_response_data(f)), b"9.91E+37");
+            }
+
             #[test]
             fn test_suffix_default() {
                 let l: $unit = Token::DecimalNumericProgramData(b"1.0").try_into().unwrap();
@@ -3,6 +3,7 @@
 
 use crate::error::ErrorCode;
 
+use core::ops::Range;
 use core::slice::Iter;
 
 pub use self::token::Token;
@@ -18,6 +19,7 @@ pub struct Tokenizer<'a> {
     pub chars: Iter<'a, u8>,
     in_header: bool,
     in_common: bool,
+    len: usize,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -33,12 +35,25 @@ impl<'a> Tokenizer<'a> {
 
     pub(crate) fn from_byte_iter(iter: Iter<'a, u8>) -> Self {
         Tokenizer {
+            len: iter.as_slice().len(),
             chars: iter,
             in_header: true,
             in_common: false,
         }
     }
 
+    /// Current byte offset into the input.
+    ///
+    /// After a token or error has been returned, this is the offset just after it.
+    pub fn position(&self) -> usize {
+        self.len - self.chars.as_slice().len()
+    }
+
+    /// Yield the byte span of each token (or error) within the input along with it.
+    pub fn spanned(self) -> Spanned<'a> {
+        Spanned(self)
+    }
+
     /// <program mnemonic>
     /// See IEEE 488.2-1992 7.6.1
     /// Must start with a alphabetic character followed by alphanumeric and '_' characters.
@@ -48,9 +63,12 @@ impl<'a> Tokenizer<'a> {
     fn read_mnemonic(&mut self, mut common: bool) -> Result<Token<'a>, ErrorCode> {
         let s = self.chars.as_slice();
         let mut len = 0u8;
-        while self.chars.clone().next().map_or(false, |ch| {
-            ch.is_ascii_alphanumeric() || *ch == b'_' || (*ch == b'*' && common)
-        }) {
+        while self
+            .chars
+            .clone()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphanumeric() || *ch == b'_' || (*ch == b'*' && common))
+        {
             common = false;
             self.chars.next();
             len += 1;
@@ -76,7 +94,7 @@ impl<'a> Tokenizer<'a> {
             .chars
             .clone()
             .next()
-            .map_or(false, |ch| ch.is_ascii_alphanumeric() || *ch == b'_')
+            .is_some_and(|ch| ch.is_ascii_alphanumeric() || *ch == b'_')
         {
             self.chars.next();
             len += 1;
@@ -108,19 +126,22 @@ impl<'a> Tokenizer<'a> {
         } else if !leading_digits {
             return Err(ErrorCode::NumericDataError);
         }
-        //TODO: Lexical-core doesn't like ws around Exponent
-        //util::skip_ws(self.chars);
-        /* Read exponent */
-        if let Some(exponent) = self.chars.clone().next() {
-            if *exponent == b'E' || *exponent == b'e' {
-                self.chars.next().unwrap();
-                //TODO: Lexical-core doesn't like ws around Exponent
-                //util::skip_ws(self.chars);
-                util::skip_sign(&mut self.chars);
-                if !util::skip_digits(&mut self.chars) {
-                    return Err(ErrorCode::NumericDataError);
-                }
+        /* Read exponent, whitespace is allowed around the exponent separator */
+        let mut exponent = self.chars.clone();
+        util::skip_ws(&mut exponent);
+        if let Some(b'E' | b'e') = exponent.clone().next() {
+            let ws = exponent.as_slice().len() != self.chars.as_slice().len();
+            exponent.next().unwrap();
+            let suffix = matches!(exponent.clone().next(), Some(x) if x.is_ascii_alphabetic());
+            util::skip_ws(&mut exponent);
+            util::skip_sign(&mut exponent);
+            if util::skip_digits(&mut exponent) {
+                self.chars = exponent;
+            } else if !(ws && suffix) {
+                // A bare `E` is a missing exponent, not a suffix
+                return Err(ErrorCode::NumericDataError);
             }
+            // Otherwise `E` starts a suffix, Eg. `1 EV`
         }
         Ok(Token::DecimalNumericProgramData(
             &s[0..s.len() - self.chars.as_slice().len()],
@@ -130,13 +151,13 @@ impl<'a> Tokenizer<'a> {
     /// <DECIMAL NUMERIC PROGRAM DATA>
     /// See IEEE 488.2-1992 7.7.2
     ///
-    /// TODO: lexical-core does not accept whitespace between exponent separator and exponent <mantissa>E <exponent>.
+    /// Whitespace around the exponent separator is kept in the token, see [util::parse_nrf].
     pub(crate) fn read_numeric_data(&mut self) -> Result<Token<'a>, ErrorCode> {
         let tok = self.read_nrf()?;
         if let Token::DecimalNumericProgramData(s) = tok {
             util::skip_ws(&mut self.chars);
             if let Some(x) = self.chars.clone().next() {
-                if x.is_ascii_alphabetic() || *x == b'/' {
+                if x.is_ascii_alphabetic() || *x == b'/' || *x == b'%' {
                     return self.read_suffix_data(s);
                 } else {
                     self.skip_ws_to_separator(ErrorCode::InvalidSuffix)?;
@@ -150,14 +171,19 @@ impl<'a> Tokenizer<'a> {
     /// See IEEE 488.2-1992 7.7.3
     /// Reads a suffix and returns it as a string if successful, otherwise it returns an error.
     ///
+    /// `%` is accepted in addition to the characters allowed by IEEE 488.2, Eg. `50%`.
+    ///
     /// Returned errors:
     /// * SuffixTooLong if suffix is longer than 12 characters
     fn read_suffix_data(&mut self, val: &'a [u8]) -> Result<Token<'a>, ErrorCode> {
         let s = self.chars.as_slice();
         let mut len = 0u8;
-        while self.chars.clone().next().map_or(false, |ch| {
-            ch.is_ascii_alphanumeric() || *ch == b'-' || *ch == b'/' || *ch == b'.'
-        }) {
+        while self
+            .chars
+            .clone()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphanumeric() || matches!(ch, b'-' | b'/' | b'.' | b'%'))
+        {
             self.chars.next();
             len += 1;
             if len > 12 {
@@ -176,7 +202,8 @@ impl<'a> Tokenizer<'a> {
 
     /// <NONDECIMAL NUMERIC PROGRAM DATA>
     /// See IEEE 488.2-1992 7.7.4
-    /// Reads a non-decimal numeric
+    /// Reads a non-decimal numeric, values which don't fit in a `u64` are returned as
+    /// [Token::WideNonDecimalNumericProgramData].
     ///
     /// Returned errors:
     fn read_nondecimal_data(&mut self, radix: u8) -> Result<Token<'a>, ErrorCode> {
@@ -184,21 +211,21 @@ impl<'a> Tokenizer<'a> {
         let (n, len) = match radix {
             b'H' | b'h' => {
                 const FORMAT: u128 = lexical_core::NumberFormatBuilder::from_radix(16);
-                lexical_core::parse_partial_with_options::<u64, FORMAT>(
+                lexical_core::parse_partial_with_options::<u128, FORMAT>(
                     self.chars.as_slice(),
                     &options,
                 )
             }
             b'Q' | b'q' => {
                 const FORMAT: u128 = lexical_core::NumberFormatBuilder::from_radix(8);
-                lexical_core::parse_partial_with_options::<u64, FORMAT>(
+                lexical_core::parse_partial_with_options::<u128, FORMAT>(
                     self.chars.as_slice(),
                     &options,
                 )
             }
             b'B' | b'b' => {
                 const FORMAT: u128 = lexical_core::NumberFormatBuilder::from_radix(2);
-                lexical_core::parse_partial_with_options::<u64, FORMAT>(
+                lexical_core::parse_partial_with_options::<u128, FORMAT>(
                     self.chars.as_slice(),
                     &options,
                 )
@@ -214,7 +241,10 @@ impl<'a> Tokenizer<'a> {
         })?;
         if len > 0 {
             self.chars.nth(len - 1).unwrap();
-            let ret = Token::NonDecimalNumericProgramData(n);
+            let ret = match u64::try_from(n) {
+                Ok(n) => Token::NonDecimalNumericProgramData(n),
+                Err(_) => Token::WideNonDecimalNumericProgramData(n),
+            };
             // Skip to next separator
             self.skip_ws_to_separator(ErrorCode::SuffixNotAllowed)?;
             Ok(ret)
@@ -257,6 +287,7 @@ impl<'a> Tokenizer<'a> {
         }
         let ret = Ok(Token::StringProgramData(
             &s[0..s.len() - self.chars.as_slice().len() - 1],
+            x,
         ));
         // Skip to next separator
         self.skip_ws_to_separator(ErrorCode::SuffixNotAllowed)?;
@@ -319,9 +350,9 @@ impl<'a> Tokenizer<'a> {
     pub(crate) fn read_expression_data(&mut self) -> Result<Token<'a>, ErrorCode> {
         self.chars.next();
         let s = self.chars.as_slice();
-        static ILLEGAL_CHARS: &[u8] = &[b'"', b'\'', b';', b'(', b')'];
+        static ILLEGAL_CHARS: &[u8] = b"\"';()";
         //Read until closing ')'
-        while self.chars.clone().next().map_or(false, |ch| *ch != b')') {
+        while self.chars.clone().next().is_some_and(|ch| *ch != b')') {
             let c = self.chars.next().unwrap();
             //Return an error if a unexpected character is encountered
             if ILLEGAL_CHARS.contains(c) || !c.is_ascii() {
@@ -344,7 +375,7 @@ impl<'a> Tokenizer<'a> {
     fn skip_ws_to_separator(&mut self, error: ErrorCode) -> Result<(), ErrorCode> {
         util::skip_ws(&mut self.chars);
         if let Some(c) = self.chars.clone().next() {
-            if *c != b',' && *c != b';' && *c != b'\n' {
+            if *c != b',' && *c != b';' && *c != b'\n' && *c != b':' {
                 return Err(error);
             }
         }
@@ -352,10 +383,34 @@ impl<'a> Tokenizer<'a> {
     }
 }
 
-impl<'a> Iterator for Tokenizer<'a> {
-    type Item = Result<Token<'a>, ErrorCode>;
+impl<'a> Tokenizer<'a> {
+    /// <program data> ':' <program data>
+    /// Combine data followed by a range separator into a [Token::ProgramDataRange], `start` is the
+    /// input before `tok` was read. Any other data is returned as is.
+    ///
+    /// Returned errors:
+    /// * NumericDataError if the range separator is not followed by data
+    fn read_range(&mut self, start: &'a [u8], tok: Token<'a>) -> Result<Token<'a>, ErrorCode> {
+        let mut chars = self.chars.clone();
+        util::skip_ws(&mut chars);
+        if chars.next() != Some(&b':') {
+            return Ok(tok);
+        }
+        let start = start[..start.len() - self.chars.len()].trim_ascii_end();
+        self.chars = chars;
+        util::skip_ws(&mut self.chars);
+        let stop = self.chars.as_slice();
+        match self.next_token() {
+            Some(Ok(tok)) if tok.is_data() => Ok(Token::ProgramDataRange(
+                start,
+                stop[..stop.len() - self.chars.len()].trim_ascii_end(),
+            )),
+            Some(Err(err)) => Err(err),
+            _ => Err(ErrorCode::NumericDataError),
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_token(&mut self) -> Option<Result<Token<'a>, ErrorCode>> {
         let x = self.chars.clone().next()?;
         let ret = match x {
             /* Common command prefix */
@@ -366,14 +421,22 @@ impl<'a> Iterator for Tokenizer<'a> {
             /* Header mnemonic separator/prefix */
             b':' => {
                 self.chars.next();
+                /* Range separator in program data, must be followed by more data */
+                if !self.in_header {
+                    util::skip_ws(&mut self.chars);
+                    return match self.chars.clone().next() {
+                        None | Some(b',' | b';' | b'\n') => Some(Err(ErrorCode::NumericDataError)),
+                        Some(_) => Some(Ok(Token::ProgramDataRangeSeparator)),
+                    };
+                }
                 //Only one separator is allowed
                 if let Some(x) = self.chars.clone().next() {
                     if !x.is_ascii_alphabetic() {
                         return Some(Err(ErrorCode::InvalidSeparator));
                     }
                 }
-                /* Not allowed outside header and strings */
-                if !self.in_header || self.in_common {
+                /* Not allowed in common commands */
+                if self.in_common {
                     Some(Err(ErrorCode::InvalidSeparator))
                 } else {
                     Some(Ok(Token::HeaderMnemonicSeparator))
@@ -494,3 +557,106 @@ impl<'a> Iterator for Tokenizer<'a> {
         ret
     }
 }
+
+/// Tokenize a program message, Eg. to inspect the tokens passed to a custom `TryFrom<Token>` implementation.
+///
+/// Same as [Tokenizer::new], headers are not checked against any command tree.
+///
+/// ```
+/// # use scpi::parser::{tokenize, tokenizer::Token};
+/// let tokens = tokenize(b"VOLT 5").collect::<Result<Vec<_>, _>>();
+/// assert_eq!(
+///     tokens,
+///     Ok(vec![
+///         Token::ProgramMnemonic(b"VOLT"),
+///         Token::ProgramHeaderSeparator,
+///         Token::DecimalNumericProgramData(b"5"),
+///     ])
+/// );
+/// ```
+pub fn tokenize(msg: &[u8]) -> Tokenizer<'_> {
+    Tokenizer::new(msg)
+}
+
+/// Same as [tokenize] but collects all tokens, returns the first error if any.
+///
+/// ```
+/// # use scpi::{error::ErrorCode, parser::{tokenize_all, tokenizer::Token}};
+/// assert_eq!(
+///     tokenize_all(b"*IDN?"),
+///     Ok(vec![Token::ProgramMnemonic(b"*IDN"), Token::HeaderQuerySuffix])
+/// );
+/// assert_eq!(tokenize_all(b"VOLT 5;&"), Err(ErrorCode::SyntaxError));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn tokenize_all(msg: &[u8]) -> Result<alloc::vec::Vec<Token<'_>>, ErrorCode> {
+    tokenize(msg).collect()
+}
+
+/// Check the syntax of an entire program message without executing it.
+///
+/// Drives the tokenizer to completion and returns the first error along with the byte offset of
+/// the offending token. Headers are not checked against any command tree.
+///
+/// ```
+/// # use scpi::{error::ErrorCode, parser::validate_syntax};
+/// assert_eq!(validate_syntax(b"*IDN?;:VOLT 5"), Ok(()));
+/// assert_eq!(validate_syntax(b"VOLT 5;&"), Err((ErrorCode::SyntaxError, 7)));
+/// ```
+pub fn validate_syntax(msg: &[u8]) -> Result<(), (ErrorCode, usize)> {
+    for (tok, span) in Tokenizer::new(msg).spanned() {
+        tok.map_err(|err| (err, span.start))?;
+    }
+    Ok(())
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, ErrorCode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.chars.len();
+        let start = self.chars.as_slice();
+        let tok = match self.next_token() {
+            Some(Ok(tok)) if tok.is_data() => Some(self.read_range(start, tok)),
+            tok => tok,
+        };
+        // Always make progress on errors so that the input can be skipped
+        if matches!(tok, Some(Err(_))) && self.chars.len() == remaining {
+            self.chars.next();
+        }
+        tok
+    }
+}
+
+impl Tokenizer<'_> {
+    /// Skip the rest of the current program message unit, Eg. after an error.
+    ///
+    /// Returns true if another unit follows, false if the end of the message was reached.
+    pub fn skip_to_next_unit(&mut self) -> bool {
+        skip_to_next_unit(self)
+    }
+}
+
+/// Consume tokens up to and including the next program message unit separator.
+/// See [Tokenizer::skip_to_next_unit].
+pub(crate) fn skip_to_next_unit<'a, I>(tokens: &mut I) -> bool
+where
+    I: Iterator<Item = Result<Token<'a>, ErrorCode>>,
+{
+    tokens.any(|tok| tok == Ok(Token::ProgramMessageUnitSeparator))
+}
+
+/// Tokenizer which also yields the byte span of each token within the input,
+/// useful to point out the offending part of a command. See [Tokenizer::spanned].
+#[derive(Clone)]
+pub struct Spanned<'a>(Tokenizer<'a>);
+
+impl<'a> Iterator for Spanned<'a> {
+    type Item = (Result<Token<'a>, ErrorCode>, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.0.position();
+        let tok = self.0.next()?;
+        Some((tok, start..self.0.position()))
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::parser::validate_mnemonic;
 
 extern crate std;
 
@@ -49,6 +50,83 @@ fn test_eq_mnemonic() {
     assert!(!Token::ProgramMnemonic(b"trig1").match_program_header(b"TRIGger2"));
 }
 
+#[test]
+fn test_mnemonic_digits() {
+    // Digits which are part of the keyword
+    assert!(util::mnemonic_match(b"D0", b"d0"));
+    assert!(!util::mnemonic_match(b"D0", b"d"));
+    assert!(!util::mnemonic_match(b"D0", b"d1"));
+    assert!(!util::mnemonic_match(b"D0", b"d00"));
+    assert!(!util::mnemonic_match(b"D", b"d0"));
+
+    // Suffix of 1 is optional
+    assert!(util::mnemonic_match(b"SMU1", b"smu1"));
+    assert!(util::mnemonic_match(b"SMU1", b"smu"));
+    assert!(util::mnemonic_match(b"SMU", b"smu1"));
+    assert!(!util::mnemonic_match(b"SMU1", b"smu2"));
+
+    // Multi-digit suffixes
+    assert!(util::mnemonic_match(b"PORT10", b"port10"));
+    assert!(!util::mnemonic_match(b"PORT10", b"port1"));
+    assert!(!util::mnemonic_match(b"PORT10", b"port"));
+    assert!(!util::mnemonic_match(b"PORT1", b"port10"));
+
+    // Header suffix matching against a node without digits
+    assert_eq!(
+        Token::ProgramMnemonic(b"port10").match_program_header_suffix(b"PORT"),
+        Some(Some(10))
+    );
+    assert_eq!(
+        Token::ProgramMnemonic(b"smu1").match_program_header_suffix(b"SMU"),
+        Some(Some(1))
+    );
+    // ... and a node with digits
+    assert_eq!(
+        Token::ProgramMnemonic(b"d0").match_program_header_suffix(b"D0"),
+        Some(None)
+    );
+    assert_eq!(
+        Token::ProgramMnemonic(b"d1").match_program_header_suffix(b"D0"),
+        None
+    );
+
+    assert!(validate_mnemonic(b"D0"));
+    assert!(validate_mnemonic(b"PORT10"));
+}
+
+#[test]
+fn test_match_mnemonic_suffix() {
+    assert_eq!(
+        Token::ProgramMnemonic(b"ext").match_program_header_suffix(b"EXTernal"),
+        Some(None)
+    );
+    assert_eq!(
+        Token::ProgramMnemonic(b"ext1").match_program_header_suffix(b"EXTernal"),
+        Some(Some(1))
+    );
+    assert_eq!(
+        Token::CharacterProgramData(b"EXTERNAL2").match_program_header_suffix(b"EXTernal"),
+        Some(Some(2))
+    );
+    assert_eq!(
+        Token::ProgramMnemonic(b"int2").match_program_header_suffix(b"EXTernal"),
+        None
+    );
+    assert_eq!(
+        Token::ProgramMnemonic(b"extx").match_program_header_suffix(b"EXTernal"),
+        None
+    );
+    // Suffix overflowing u32
+    assert_eq!(
+        Token::ProgramMnemonic(b"ext99999999999").match_program_header_suffix(b"EXTernal"),
+        None
+    );
+    assert_eq!(
+        Token::StringProgramData(b"ext", b'"').match_program_header_suffix(b"EXTernal"),
+        None
+    );
+}
+
 #[test]
 fn test_read_character_data() {
     assert_eq!(
@@ -67,8 +145,6 @@ fn test_read_character_data() {
 
 #[test]
 fn test_read_numeric_data() {
-    //TODO: FIX EXPONENTS!
-
     assert_eq!(
         Tokenizer::new(b"25").read_numeric_data().unwrap(),
         Token::DecimalNumericProgramData(b"25")
@@ -99,6 +175,46 @@ fn test_read_numeric_data() {
         Token::DecimalNumericProgramData(b".1E2")
     );
 
+    // Whitespace around exponent separator
+    assert_eq!(
+        Tokenizer::new(b"1.0 E3").read_numeric_data().unwrap(),
+        Token::DecimalNumericProgramData(b"1.0 E3")
+    );
+    assert_eq!(
+        Tokenizer::new(b"1 E 3 V").read_numeric_data().unwrap(),
+        Token::DecimalNumericSuffixProgramData(b"1 E 3", b"V")
+    );
+    assert_eq!(
+        Tokenizer::new(b"1E +3").read_numeric_data().unwrap(),
+        Token::DecimalNumericProgramData(b"1E +3")
+    );
+    assert_eq!(
+        Tokenizer::new(b"1E").read_numeric_data().unwrap_err(),
+        ErrorCode::NumericDataError
+    );
+    assert_eq!(
+        Tokenizer::new(b"1E+ 3").read_numeric_data().unwrap_err(),
+        ErrorCode::NumericDataError
+    );
+    // Not an exponent
+    assert_eq!(
+        Tokenizer::new(b"1 EV").read_numeric_data().unwrap(),
+        Token::DecimalNumericSuffixProgramData(b"1", b"EV")
+    );
+    // Missing exponent, not a suffix
+    assert_eq!(
+        Tokenizer::new(b"1 E").read_numeric_data().unwrap_err(),
+        ErrorCode::NumericDataError
+    );
+    assert_eq!(
+        Tokenizer::new(b"1 e ,2").read_numeric_data().unwrap_err(),
+        ErrorCode::NumericDataError
+    );
+    assert_eq!(
+        Tokenizer::new(b"1 E+").read_numeric_data().unwrap_err(),
+        ErrorCode::NumericDataError
+    );
+
     assert_eq!(
         Tokenizer::new(b".1E2  SUFFIX").read_numeric_data().unwrap(),
         Token::DecimalNumericSuffixProgramData(b".1E2", b"SUFFIX")
@@ -109,6 +225,11 @@ fn test_read_numeric_data() {
         Token::DecimalNumericSuffixProgramData(b".1E2", b"/S")
     );
 
+    assert_eq!(
+        Tokenizer::new(b"50%").read_numeric_data().unwrap(),
+        Token::DecimalNumericSuffixProgramData(b"50", b"%")
+    );
+
     assert_eq!(
         Tokenizer::new(b".1E2  'SUFFIX'")
             .read_numeric_data()
@@ -147,15 +268,15 @@ fn test_read_numeric_suffix_data() {
 fn test_read_string_data() {
     assert_eq!(
         Tokenizer::new(b"\"MOHM\",  gui").read_string_data(b'"', true),
-        Ok(Token::StringProgramData(b"MOHM"))
+        Ok(Token::StringProgramData(b"MOHM", b'"'))
     );
     assert_eq!(
         Tokenizer::new(b"'MOHM',  gui").read_string_data(b'\'', true),
-        Ok(Token::StringProgramData(b"MOHM"))
+        Ok(Token::StringProgramData(b"MOHM", b'\''))
     );
     assert_eq!(
         Tokenizer::new(b"'MO''HM',  gui").read_string_data(b'\'', true),
-        Ok(Token::StringProgramData(b"MO''HM"))
+        Ok(Token::StringProgramData(b"MO''HM", b'\''))
     );
 
     assert_eq!(
@@ -272,7 +393,7 @@ fn test_parse_programdata() {
         Ok(Token::ProgramDataSeparator),
         Ok(Token::DecimalNumericProgramData(b"255")),
         Ok(Token::ProgramDataSeparator),
-        Ok(Token::StringProgramData(b"STRING")),
+        Ok(Token::StringProgramData(b"STRING", b'"')),
         Ok(Token::ProgramDataSeparator),
         Ok(Token::DecimalNumericSuffixProgramData(b"1", b"SUFFIX")),
         Ok(Token::ProgramDataSeparator),
@@ -283,3 +404,162 @@ fn test_parse_programdata() {
         Ok(Token::ExpressionProgramData(b"1,11,3:9"))
     ];
 }
+
+#[test]
+fn test_parse_wide_nondecimal() {
+    match_tokens![b"MASK #HFFFFFFFFFFFFFFFF,#H10000000000000000" =>
+        Ok(Token::ProgramMnemonic(b"MASK")),
+        Ok(Token::ProgramHeaderSeparator),
+        Ok(Token::NonDecimalNumericProgramData(u64::MAX)),
+        Ok(Token::ProgramDataSeparator),
+        Ok(Token::WideNonDecimalNumericProgramData(1 << 64))
+    ];
+}
+
+#[test]
+fn test_parse_range() {
+    match_tokens![b"SWE 1e3:1e6,MIN : MAX,1 V:2:3;SWE 1:" =>
+        Ok(Token::ProgramMnemonic(b"SWE")),
+        Ok(Token::ProgramHeaderSeparator),
+        Ok(Token::ProgramDataRange(b"1e3", b"1e6")),
+        Ok(Token::ProgramDataSeparator),
+        Ok(Token::ProgramDataRange(b"MIN", b"MAX")),
+        Ok(Token::ProgramDataSeparator),
+        Ok(Token::ProgramDataRange(b"1 V", b"2")),
+        Ok(Token::ProgramDataRangeSeparator),
+        Ok(Token::DecimalNumericProgramData(b"3")),
+        Ok(Token::ProgramMessageUnitSeparator),
+        Ok(Token::ProgramMnemonic(b"SWE")),
+        Ok(Token::ProgramHeaderSeparator),
+        Err(ErrorCode::NumericDataError)
+    ];
+}
+
+#[test]
+fn test_spanned() {
+    let spans: std::vec::Vec<_> = Tokenizer::new(b"SYST:ERR? 'A',1").spanned().collect();
+    assert_eq!(
+        spans,
+        [
+            (Ok(Token::ProgramMnemonic(b"SYST")), 0..4),
+            (Ok(Token::HeaderMnemonicSeparator), 4..5),
+            (Ok(Token::ProgramMnemonic(b"ERR")), 5..8),
+            (Ok(Token::HeaderQuerySuffix), 8..9),
+            (Ok(Token::ProgramHeaderSeparator), 9..10),
+            (Ok(Token::StringProgramData(b"A", b'\'')), 10..13),
+            (Ok(Token::ProgramDataSeparator), 13..14),
+            (Ok(Token::DecimalNumericProgramData(b"1")), 14..15),
+        ]
+    );
+
+    // Invalid character is reported at its position
+    let (err, span) = Tokenizer::new(b"*RST;\xb5")
+        .spanned()
+        .find(|(tok, _)| tok.is_err())
+        .unwrap();
+    assert_eq!(err, Err(ErrorCode::InvalidCharacter));
+    assert_eq!(span.start, 5);
+
+    let mut tok = Tokenizer::new(b"*RST;*CLS");
+    tok.next();
+    assert_eq!(tok.position(), 4);
+}
+
+#[test]
+fn test_validate_mnemonic() {
+    const _: () = assert!(validate_mnemonic(b"VOLTage"));
+
+    assert!(validate_mnemonic(b"VOLTage"));
+    assert!(validate_mnemonic(b"VOLT"));
+    assert!(validate_mnemonic(b"TRIGger2"));
+    assert!(validate_mnemonic(b"DC_Offset"));
+    assert!(validate_mnemonic(b"*IDN"));
+    assert!(!validate_mnemonic(b"volt"));
+    assert!(validate_mnemonic(b""));
+    assert!(!validate_mnemonic(b"*"));
+    assert!(!validate_mnemonic(b"VOLTageDC"));
+    assert!(!validate_mnemonic(b"VOLT:DC"));
+    assert!(!validate_mnemonic(b"ABCDEFGHIJKLM"));
+}
+
+#[test]
+fn test_validate_syntax() {
+    assert_eq!(validate_syntax(b"*IDN?;:VOLT 5"), Ok(()));
+    assert_eq!(validate_syntax(b""), Ok(()));
+    assert_eq!(
+        validate_syntax(b"SOUR:DATA #15hello,'str',#HFF,(@1:3);*OPC\n"),
+        Ok(())
+    );
+    assert_eq!(
+        validate_syntax(b"VOLT 5;&"),
+        Err((ErrorCode::SyntaxError, 7))
+    );
+    assert_eq!(
+        validate_syntax(b"VOLT 1,,2"),
+        Err((ErrorCode::SyntaxError, 6))
+    );
+    assert_eq!(
+        validate_syntax(b"DATA #15abc"),
+        Err((ErrorCode::InvalidBlockData, 5))
+    );
+}
+
+#[test]
+fn test_unescape_string() {
+    let mut tokenizer = Tokenizer::new(br#"*STR "a""b",'it''s',"it's",'say "hi"'"#);
+    let mut next = || loop {
+        match tokenizer.next().unwrap().unwrap() {
+            t @ Token::StringProgramData(_, _) => break t,
+            _ => continue,
+        }
+    };
+    let mut buf = [0u8; 8];
+    assert_eq!(
+        next().unescape_string_into(&mut buf),
+        Ok(br#"a"b"#.as_ref())
+    );
+    assert_eq!(next().unescape_string_into(&mut buf), Ok(b"it's".as_ref()));
+    assert_eq!(next().unescape_string_into(&mut buf), Ok(b"it's".as_ref()));
+    assert_eq!(
+        next().unescape_string_into(&mut buf),
+        Ok(br#"say "hi""#.as_ref())
+    );
+
+    // Only the delimiting quote is collapsed
+    assert_eq!(
+        Tokenizer::new(br#"'say ""hi""'"#)
+            .read_string_data(b'\'', true)
+            .unwrap()
+            .unescape_string_into(&mut [0u8; 16]),
+        Ok(br#"say ""hi"""#.as_ref())
+    );
+
+    assert_eq!(
+        Token::StringProgramData(b"too long string", b'"').unescape_string_into(&mut buf),
+        Err(ErrorCode::TooMuchData.into())
+    );
+    assert_eq!(
+        Token::CharacterProgramData(b"CHAR").unescape_string_into(&mut buf),
+        Err(ErrorCode::DataTypeError.into())
+    );
+    assert_eq!(
+        Token::ArbitraryBlockData(b"a\"\"b").unescape_string_into(&mut buf),
+        Ok(b"a\"\"b".as_ref())
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_unescape_string_alloc() {
+    use alloc::borrow::Cow;
+
+    assert_eq!(
+        Token::StringProgramData(br#"a""b"#, b'"').unescape_string(),
+        Some(Cow::Owned(br#"a"b"#.to_vec()))
+    );
+    assert_eq!(
+        Token::StringProgramData(b"ab", b'"').unescape_string(),
+        Some(Cow::Borrowed(b"ab".as_ref()))
+    );
+    assert_eq!(Token::CharacterProgramData(b"ab").unescape_string(), None);
+}
@@ -1,9 +1,13 @@
 use super::util;
+use crate::error::{Error, ErrorCode};
 
 /// SCPI tokens
 /// Loosely based on IEEE488.2 Chapter 7
 ///
+/// New token kinds may be added as the tokenizer grows, so matches outside this crate
+/// need a wildcard arm.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[non_exhaustive]
 pub enum Token<'a> {
     /// A header mnemonic separator `:`
     HeaderMnemonicSeparator,
@@ -15,6 +19,9 @@ pub enum Token<'a> {
     ProgramHeaderSeparator,
     /// A program data separator ','
     ProgramDataSeparator,
+    /// A program data range separator ':' which does not follow any data (Eg. `SWE :1e6`),
+    /// a range is returned as [Self::ProgramDataRange]
+    ProgramDataRangeSeparator,
     /// A program mnemonic
     ProgramMnemonic(&'a [u8]),
     /// A <CHARACTER PROGRAM DATA> 7.7.1
@@ -25,12 +32,17 @@ pub enum Token<'a> {
     DecimalNumericSuffixProgramData(&'a [u8], &'a [u8]),
     /// A <NONDECIMAL NUMERIC PROGRAM DATA> 7.7.4
     NonDecimalNumericProgramData(u64),
-    /// A <STRING PROGRAM DATA> 7.7.5
-    StringProgramData(&'a [u8]),
+    /// A <NONDECIMAL NUMERIC PROGRAM DATA> 7.7.4 too large for a `u64`, Eg. a 128-bit mask
+    WideNonDecimalNumericProgramData(u128),
+    /// A <STRING PROGRAM DATA> 7.7.5 and the quote (`"` or `'`) delimiting it
+    StringProgramData(&'a [u8], u8),
     /// A <ARBITRARY BLOCK PROGRAM DATA> 7.7.6
     ArbitraryBlockData(&'a [u8]),
     /// A <EXPRESSION PROGRAM DATA> 7.7.7
     ExpressionProgramData(&'a [u8]),
+    /// Two program data elements separated by a range separator ':' (Eg. `1e3:1e6`),
+    /// holds the unparsed start and stop data. See [crate::parser::parameters::RangeValue].
+    ProgramDataRange(&'a [u8], &'a [u8]),
 }
 
 impl<'a> Token<'a> {
@@ -41,12 +53,64 @@ impl<'a> Token<'a> {
                 | Self::DecimalNumericProgramData(_)
                 | Self::DecimalNumericSuffixProgramData(_, _)
                 | Self::NonDecimalNumericProgramData(_)
-                | Self::StringProgramData(_)
+                | Self::WideNonDecimalNumericProgramData(_)
+                | Self::StringProgramData(_, _)
                 | Self::ArbitraryBlockData(_)
                 | Self::ExpressionProgramData(_)
+                | Self::ProgramDataRange(_, _)
         )
     }
 
+    /// Returns the payload of string or block data with doubled quotes (`""` or `''`) inside
+    /// strings collapsed into one, see IEEE488.2 7.7.5.
+    ///
+    /// Only the quote which delimited the string is collapsed, Eg. `'say ""hi""'` is kept as is.
+    ///
+    /// Returns `None` if token is not string or block data.
+    ///
+    /// ```
+    /// # use scpi::parser::tokenizer::Token;
+    /// let token = Token::StringProgramData(br#"a""b"#, b'"');
+    /// assert_eq!(token.unescape_string().as_deref(), Some(br#"a"b"#.as_ref()));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn unescape_string(&self) -> Option<alloc::borrow::Cow<'a, [u8]>> {
+        use alloc::borrow::Cow;
+        match *self {
+            Self::StringProgramData(s, quote) => {
+                if s.contains(&quote) {
+                    let mut buf = alloc::vec![0; s.len()];
+                    let len = unescape_into(s, quote, &mut buf)?;
+                    buf.truncate(len);
+                    Some(Cow::Owned(buf))
+                } else {
+                    Some(Cow::Borrowed(s))
+                }
+            }
+            Self::ArbitraryBlockData(s) => Some(Cow::Borrowed(s)),
+            _ => None,
+        }
+    }
+
+    /// Same as [Self::unescape_string] but copies the payload into `buf`.
+    ///
+    /// # Returns
+    /// * `Ok(&[u8])` - The part of `buf` holding the payload.
+    /// * `Err(DataTypeError)` - If token is not string or block data.
+    /// * `Err(TooMuchData)` - If the payload does not fit in `buf`.
+    pub fn unescape_string_into<'b>(&self, buf: &'b mut [u8]) -> Result<&'b [u8], Error> {
+        let len = match *self {
+            Self::StringProgramData(s, quote) => unescape_into(s, quote, buf),
+            Self::ArbitraryBlockData(s) => buf.get_mut(..s.len()).map(|b| {
+                b.copy_from_slice(s);
+                s.len()
+            }),
+            _ => return Err(ErrorCode::DataTypeError.into()),
+        }
+        .ok_or(ErrorCode::TooMuchData)?;
+        Ok(&buf[..len])
+    }
+
     /// Returns true if token is a ProgramMnemonic that matches provided mnemonic.
     /// Header suffix is optional if equal to 1 or not present in mnemonic.
     /// Header suffixes other than 1 must match exactly.
@@ -56,6 +120,7 @@ impl<'a> Token<'a> {
     /// - `head[er]1` == `HEADer`
     /// - `head[er]` == `HEADer1`
     /// - `head[er]<N>` == `HEADer<N>`
+    ///
     /// Where `[]` marks optional, `<>` required.
     ///
     pub fn match_program_header(&self, mnemonic: &'a [u8]) -> bool {
@@ -67,4 +132,49 @@ impl<'a> Token<'a> {
             _ => false,
         }
     }
+
+    /// Matches a ProgramMnemonic against provided mnemonic and returns the numeric header suffix, if any.
+    ///
+    /// Returns `None` if the token does not match, `Some(None)` if it matches without a suffix and
+    /// `Some(Some(n))` if it matches with the suffix `n`. Note that a suffix of `1` is not treated as
+    /// equivalent to no suffix, it's up to the caller to decide that.
+    ///
+    /// Eg:
+    /// - `ext` == `EXTernal` => `Some(None)`
+    /// - `ext1` == `EXTernal` => `Some(Some(1))`
+    /// - `external2` == `EXTernal` => `Some(Some(2))`
+    /// - `int2` != `EXTernal` => `None`
+    ///
+    pub fn match_program_header_suffix(&self, mnemonic: &[u8]) -> Option<Option<u32>> {
+        match self {
+            Token::ProgramMnemonic(s) | Token::CharacterProgramData(s) => {
+                if util::mnemonic_compare(mnemonic, s) {
+                    Some(None)
+                } else {
+                    let (x, suffix) = util::mnemonic_split_index(s)?;
+                    if util::mnemonic_compare(mnemonic, x) {
+                        lexical_core::parse::<u32>(suffix).ok().map(Some)
+                    } else {
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Copy `s` into `buf` with doubled `quote`s collapsed, returns the length or `None` if
+/// `buf` is too small.
+fn unescape_into(s: &[u8], quote: u8, buf: &mut [u8]) -> Option<usize> {
+    let mut len = 0;
+    let mut chars = s.iter().peekable();
+    while let Some(&c) = chars.next() {
+        if c == quote && chars.peek() == Some(&&c) {
+            chars.next();
+        }
+        *buf.get_mut(len)? = c;
+        len += 1;
+    }
+    Some(len)
 }
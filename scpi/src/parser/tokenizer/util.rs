@@ -28,12 +28,33 @@ pub(crate) fn skip_ws(iter: &mut Iter<u8>) {
     while iter
         .clone()
         .next()
-        .map_or(false, |ch| ch.is_ascii_whitespace())
+        .is_some_and(|ch| ch.is_ascii_whitespace())
     {
         iter.next().unwrap();
     }
 }
 
+/// Parse decimal numeric data, Eg. `1.0E3` or `1.0 E 3`.
+///
+/// Lexical-core does not accept whitespace around the exponent separator so it is stripped first,
+/// the number is then limited to 64 characters.
+pub(crate) fn parse_nrf<T>(s: &[u8]) -> Result<T, lexical_core::Error>
+where
+    T: lexical_core::FromLexical,
+{
+    if !s.iter().any(u8::is_ascii_whitespace) {
+        return lexical_core::parse(s);
+    }
+    let mut buf = [0u8; 64];
+    let mut len = 0;
+    for &c in s.iter().filter(|c| !c.is_ascii_whitespace()) {
+        *buf.get_mut(len)
+            .ok_or(lexical_core::Error::InvalidDigit(len))? = c;
+        len += 1;
+    }
+    lexical_core::parse(&buf[..len])
+}
+
 /// Split a mnemonic of the form "ABC123" into ("ABC", "123")
 /// Returns None if the mnemonic does not end with digits.
 pub(crate) fn mnemonic_split_index(mnemonic: &[u8]) -> Option<(&[u8], &[u8])> {
@@ -55,6 +76,8 @@ pub(crate) fn mnemonic_split_index(mnemonic: &[u8]) -> Option<(&[u8], &[u8])> {
 /// * `mnemonic` - Reference mnemonic to compare with (Example `TRIGger2`)
 /// * `s` - String to compare to mnemonic
 ///
+/// Digits are part of the keyword and must always be given, i.e. `D0` matches `d0` but not `d`.
+/// See [mnemonic_match] for numeric suffixes.
 pub fn mnemonic_compare(mnemonic: &[u8], s: &[u8]) -> bool {
     //LONGform == longform || LONG == long
     //TODO: This sucks.
@@ -74,6 +97,51 @@ pub fn mnemonic_compare(mnemonic: &[u8], s: &[u8]) -> bool {
     }
 }
 
+/// Check that a mnemonic is of the form `SHORTlong[<N>]`, Eg. `VOLTage` or `TRIGger2`.
+///
+/// A valid mnemonic is 1 to 12 characters long, starts with an uppercase character, only contains
+/// alphanumeric characters or '_' and has no uppercase characters after the lowercase long form has begun.
+/// A leading `*` (common command, Eg. `*IDN`) is accepted and not counted.
+/// The empty name `b""` of a root branch or unnamed default node is also accepted.
+///
+/// Also used by `#[derive(ScpiEnum)]` to check mnemonics at compile time.
+///
+/// ```
+/// # use scpi::parser::validate_mnemonic;
+/// assert!(validate_mnemonic(b"VOLTage"));
+/// assert!(validate_mnemonic(b"*IDN"));
+/// assert!(validate_mnemonic(b""));
+/// assert!(!validate_mnemonic(b"volt"));
+/// assert!(!validate_mnemonic(b"VOLTageDC"));
+/// ```
+pub const fn validate_mnemonic(mnemonic: &[u8]) -> bool {
+    let mnemonic = match mnemonic {
+        [] => return true,
+        [b'*', rest @ ..] => rest,
+        m => m,
+    };
+    if mnemonic.is_empty() || mnemonic.len() > 12 || !mnemonic[0].is_ascii_uppercase() {
+        return false;
+    }
+    let mut long_form = false;
+    let mut i = 0;
+    while i < mnemonic.len() {
+        let c = mnemonic[i];
+        if !(c.is_ascii_alphanumeric() || c == b'_') || (long_form && c.is_ascii_uppercase()) {
+            return false;
+        }
+        long_form |= c.is_ascii_lowercase();
+        i += 1;
+    }
+    true
+}
+
+/// Compare a string to a mnemonic, allowing for an optional numeric suffix.
+///
+/// In addition to [mnemonic_compare], a trailing numeric suffix of `1` may be omitted or added
+/// from either side, Eg. `TRIGger1` matches `trig` and `TRIGger` matches `trig1`.
+/// Other suffixes must be equal, Eg. `PORT10` matches `port10` but not `port1` or `port`
+/// and `D0` matches `d0` but not `d` or `d1`.
 pub fn mnemonic_match(mnemonic: &[u8], s: &[u8]) -> bool {
     mnemonic_compare(mnemonic, s)
         || match (mnemonic_split_index(mnemonic), mnemonic_split_index(s)) {
@@ -178,7 +178,8 @@ macro_rules! cmd_both {
 pub trait Command<D: Device> {
     /// Hint about the allowed forms this command allows.
     ///
-    /// Not actually binding in any way but can be used to provide autocompletion and help info.
+    /// The tree returns a [ErrorCode::UndefinedHeader] error without calling the handler if the form is not
+    /// allowed, Eg. a query of a [CommandTypeMeta::NoQuery] command. Also used to provide autocompletion and help info.
     /// Use [cmd_nquery!], [cmd_qonly!], or [cmd_both!] to automatically create the corresponding stub.
     fn meta(&self) -> CommandTypeMeta {
         CommandTypeMeta::Unknown
@@ -205,6 +206,83 @@ pub trait Command<D: Device> {
     }
 }
 
+/// A boxed future returned by [AsyncCommand] handlers.
+#[cfg(feature = "async")]
+pub type BoxFuture<'a, T> =
+    core::pin::Pin<alloc::boxed::Box<dyn core::future::Future<Output = T> + 'a>>;
+
+/// Async version of [Command], executed by `Node::run_async`.
+///
+/// Handlers return a `BoxFuture` borrowing the device, context and parameters which is awaited
+/// before the next program message unit is executed.
+///
+/// The `event` and `query` handlers require the `async` feature,
+/// without it an [crate::tree::Node::AsyncLeaf] can be part of a tree but is never executed.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "async")]
+/// # mod example {
+/// use scpi::{error::Result, tree::prelude::*, cmd_qonly};
+///
+/// struct MyCommand;
+/// impl<D> AsyncCommand<D> for MyCommand
+/// where
+///     D: Device,
+/// {
+///     cmd_qonly!();
+///
+///     fn query<'h>(
+///         &'h self,
+///         _device: &'h mut D,
+///         _context: &'h mut Context<'_>,
+///         _params: Parameters<'h, '_>,
+///         mut response: ResponseUnit<'h>,
+///     ) -> BoxFuture<'h, Result<()>> {
+///         Box::pin(async move {
+///             // Await something
+///             response.data(&b"Hello world"[..]).finish()
+///         })
+///     }
+/// }
+/// # }
+/// ```
+///
+/// The default stubs for `event` and `query` returns an [ErrorCode::UndefinedHeader] error.
+pub trait AsyncCommand<D: Device> {
+    /// Hint about the allowed forms this command allows, see [Command::meta].
+    fn meta(&self) -> CommandTypeMeta {
+        CommandTypeMeta::Unknown
+    }
+
+    /// Called when the event form `COMmand` is used.
+    ///
+    /// Default behaviour returns a [ErrorCode::UndefinedHeader] error.
+    #[cfg(feature = "async")]
+    fn event<'h>(
+        &'h self,
+        _device: &'h mut D,
+        _context: &'h mut Context<'_>,
+        _params: Parameters<'h, '_>,
+    ) -> BoxFuture<'h, Result<()>> {
+        alloc::boxed::Box::pin(async { Err(ErrorCode::UndefinedHeader.into()) })
+    }
+
+    /// Called when the query form `COMmand?` is used
+    ///
+    /// Default behaviour returns a [ErrorCode::UndefinedHeader] error.
+    #[cfg(feature = "async")]
+    fn query<'h>(
+        &'h self,
+        _device: &'h mut D,
+        _context: &'h mut Context<'_>,
+        _params: Parameters<'h, '_>,
+        _resp: ResponseUnit<'h>,
+    ) -> BoxFuture<'h, Result<()>> {
+        alloc::boxed::Box::pin(async { Err(ErrorCode::UndefinedHeader.into()) })
+    }
+}
+
 /// Dummy node which calls [todo!] on event and query.
 ///
 /// Indicates an unfinished command similar to the [todo!] macro.
@@ -241,6 +319,19 @@ pub enum CommandTypeMeta {
     Both,
 }
 
+impl CommandTypeMeta {
+    /// Returns true if the query form (`query == true`) or event form is allowed.
+    ///
+    /// [CommandTypeMeta::Unknown] allows both forms.
+    pub fn allows(self, query: bool) -> bool {
+        match self {
+            Self::NoQuery => !query,
+            Self::QueryOnly => query,
+            Self::Unknown | Self::Both => true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_command {
     use crate::tests::fixture_device;
@@ -315,4 +406,14 @@ mod test_command {
     fn test_default() {
         assert_eq!(Default {}.meta(), CommandTypeMeta::Unknown);
     }
+
+    #[test]
+    fn test_allows() {
+        assert!(
+            CommandTypeMeta::QueryOnly.allows(true) && !CommandTypeMeta::QueryOnly.allows(false)
+        );
+        assert!(!CommandTypeMeta::NoQuery.allows(true) && CommandTypeMeta::NoQuery.allows(false));
+        assert!(CommandTypeMeta::Both.allows(true) && CommandTypeMeta::Both.allows(false));
+        assert!(CommandTypeMeta::Unknown.allows(true) && CommandTypeMeta::Unknown.allows(false));
+    }
 }
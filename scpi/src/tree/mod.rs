@@ -13,7 +13,8 @@
 //!     [:DEFault?] <args>
 //! ```
 //! This would be written like below.
-//! Note that a node handles both the query and event form of commands and it's up to the handler to reject a query on an event only node or vice-versa.
+//! Note that a node handles both the query and event form of commands, a query on an event only node (see [command::CommandTypeMeta])
+//! or vice-versa is rejected with a [ErrorCode::UndefinedHeader] error before calling the handler.
 //! ```
 //! # struct MyDevice;
 //! # impl scpi::Device for MyDevice {
@@ -55,23 +56,27 @@
 //! };
 //! ```
 
+use core::cell::Cell;
 use core::iter::Peekable;
+use core::ops::RangeInclusive;
 //extern crate std;
 
 pub mod command;
 
-use command::Command;
+use command::{AsyncCommand, Command, CommandTypeMeta};
 
 use crate::error::{Error, ErrorCode, Result};
 use crate::parser::parameters::Parameters;
-use crate::parser::response::Formatter;
-use crate::parser::tokenizer::{Token, Tokenizer};
+use crate::parser::response::{query_deadlocked, Formatter, MessageTerminator, ResponseUnit};
+use crate::parser::tokenizer::{skip_to_next_unit, util::mnemonic_split_index, Token, Tokenizer};
 use crate::{Context, Device};
 
 /// Everything needed when creating command trees or command handlers
 pub mod prelude {
+    #[cfg(feature = "async")]
+    pub use super::command::BoxFuture;
     pub use super::{
-        command::{Command, CommandTypeMeta},
+        command::{AsyncCommand, Command, CommandTypeMeta},
         Node::{self, Branch, Leaf},
     };
     pub use crate::{
@@ -89,6 +94,7 @@ pub mod prelude {
 /// A SCPI command node
 /// These nodes are structured as a command tree where each node represent a SCPI header mnemonic.
 ///
+#[non_exhaustive]
 pub enum Node<'a, D> {
     /// A leaf node which can be called or queried.
     Leaf {
@@ -100,6 +106,36 @@ pub enum Node<'a, D> {
         /// Command handler
         handler: &'a dyn Command<D>,
     },
+    /// A leaf node with an async handler, only executed by `Node::run_async` (requires the `async` feature).
+    AsyncLeaf {
+        /// Mnemonic of this leaf
+        name: &'static [u8],
+        /// Default node, will be executed if the branch immediately below is executed.
+        /// Only one default node is allowed in each branch.
+        default: bool,
+        /// Command handler
+        handler: &'a dyn AsyncCommand<D>,
+    },
+    /// A node which only accepts a numeric header suffix within `suffix`, Eg. `CHANnel1` to `CHANnel4`.
+    /// No suffix is treated as a suffix of `1`.
+    ///
+    /// Any other suffix returns a [ErrorCode::HeaderSuffixOutOfRange] error.
+    Suffixed {
+        /// Accepted header suffixes
+        suffix: RangeInclusive<u32>,
+        /// Node
+        node: &'a Node<'a, D>,
+    },
+    /// A node declaring which forms it accepts, Eg. a query-only leaf.
+    ///
+    /// Only documents intent, the declared form is cross-checked against the handler's
+    /// [Command::meta] by [Node::validate]. Use [crate::Leaf!] to create one.
+    Declared {
+        /// Accepted forms
+        meta: CommandTypeMeta,
+        /// Node
+        node: &'a Node<'a, D>,
+    },
     /// A branch which contains one or more leaves.
     Branch {
         /// Mnemonic of this branch
@@ -124,6 +160,15 @@ impl<'a, D> Node<'a, D> {
         }
     }
 
+    /// Create an async leaf node
+    pub const fn async_leaf(name: &'static [u8], handler: &'a dyn AsyncCommand<D>) -> Self {
+        Self::AsyncLeaf {
+            name,
+            default: false,
+            handler,
+        }
+    }
+
     /// Create a default leaf node
     ///
     /// Alternatively use [crate::Leaf!]
@@ -157,6 +202,16 @@ impl<'a, D> Node<'a, D> {
         }
     }
 
+    /// Create a node only accepting the given header suffixes
+    pub const fn suffixed(suffix: RangeInclusive<u32>, node: &'a Node<'a, D>) -> Self {
+        Self::Suffixed { suffix, node }
+    }
+
+    /// Create a node declaring the forms it accepts, see [Node::Declared]
+    pub const fn declared(meta: CommandTypeMeta, node: &'a Node<'a, D>) -> Self {
+        Self::Declared { meta, node }
+    }
+
     /// Create a root node
     ///
     /// Alternatively use [crate::Root!]
@@ -170,6 +225,9 @@ impl<'a, D> Node<'a, D> {
 }
 
 /// A utility to create a [Node::Leaf].
+///
+/// Prefix the name with `query` or `event` to declare a query-only or event-only leaf,
+/// see [Node::Declared]. Eg. `Leaf!(default query b"NEXT" => &NextCommand)`.
 #[macro_export]
 macro_rules! Leaf {
     ($name:literal => $handler:expr) => {
@@ -186,6 +244,30 @@ macro_rules! Leaf {
             handler: $handler,
         }
     };
+    (query $name:literal => $handler:expr) => {
+        $crate::tree::Node::Declared {
+            meta: $crate::tree::command::CommandTypeMeta::QueryOnly,
+            node: &$crate::Leaf!($name => $handler),
+        }
+    };
+    (event $name:literal => $handler:expr) => {
+        $crate::tree::Node::Declared {
+            meta: $crate::tree::command::CommandTypeMeta::NoQuery,
+            node: &$crate::Leaf!($name => $handler),
+        }
+    };
+    (default query $name:literal => $handler:expr) => {
+        $crate::tree::Node::Declared {
+            meta: $crate::tree::command::CommandTypeMeta::QueryOnly,
+            node: &$crate::Leaf!(default $name => $handler),
+        }
+    };
+    (default event $name:literal => $handler:expr) => {
+        $crate::tree::Node::Declared {
+            meta: $crate::tree::command::CommandTypeMeta::NoQuery,
+            node: &$crate::Leaf!(default $name => $handler),
+        }
+    };
 }
 
 /// A utility to create a [Node::Branch].
@@ -205,7 +287,7 @@ macro_rules! Branch {
             name: $name,
             default: false,
             sub: &[
-                Leaf!{default b"" => $handler },
+                $crate::Leaf!{default b"" => $handler },
                 $($child),+
             ],
         }
@@ -239,9 +321,447 @@ impl<'a, D> Node<'a, D> {
     pub fn name(&self) -> &'static [u8] {
         match self {
             Self::Leaf { name, .. } => name,
+            Self::AsyncLeaf { name, .. } => name,
+            Self::Suffixed { node, .. } | Self::Declared { node, .. } => node.name(),
             Self::Branch { name, .. } => name,
         }
     }
+
+    fn is_default_leaf(&self) -> bool {
+        match self {
+            Self::Leaf { default, .. } => *default,
+            Self::AsyncLeaf { default, .. } => *default,
+            Self::Suffixed { node, .. } | Self::Declared { node, .. } => node.is_default_leaf(),
+            Self::Branch { .. } => false,
+        }
+    }
+
+    fn is_default_branch(&self) -> bool {
+        match self {
+            Self::Suffixed { node, .. } | Self::Declared { node, .. } => node.is_default_branch(),
+            _ => matches!(self, Self::Branch { default: true, .. }),
+        }
+    }
+
+    /// Match a header mnemonic against this node.
+    ///
+    /// [Node::Suffixed] accepts any numeric suffix but returns an error if it's out of range.
+    fn match_header(&self, mnemonic: &Token) -> Result<bool> {
+        if let Self::Declared { node, .. } = self {
+            node.match_header(mnemonic)
+        } else if let Self::Suffixed { suffix, node } = self {
+            match mnemonic.match_program_header_suffix(node.name()) {
+                Some(n) if suffix.contains(&n.unwrap_or(1)) => Ok(true),
+                Some(_) => Err(ErrorCode::HeaderSuffixOutOfRange.into()),
+                None => Ok(false),
+            }
+        } else {
+            Ok(mnemonic.match_program_header(self.name()))
+        }
+    }
+}
+
+impl<'a, D> Node<'a, D>
+where
+    D: Device,
+{
+    /// Check the tree for ambiguous nodes, see [TreeError].
+    ///
+    /// Intended to be called from a unit test:
+    /// ```
+    /// # use scpi::tree::{prelude::*, command::Todo};
+    /// # struct MyDevice;
+    /// # impl Device for MyDevice {
+    /// #     fn handle_error(&mut self, _err: Error) {}
+    /// # }
+    /// const ROOT: Node<MyDevice> = Branch {
+    ///     name: b"",
+    ///     default: false,
+    ///     sub: &[
+    ///         Leaf { name: b"VOLTage", default: false, handler: &Todo },
+    ///         Leaf { name: b"VOLT", default: false, handler: &Todo },
+    ///     ],
+    /// };
+    ///
+    /// assert!(ROOT.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> core::result::Result<(), TreeError> {
+        if let Self::Suffixed { node, .. } = self {
+            return node.validate();
+        }
+        if let Self::Declared { meta, node } = self {
+            return match node.meta() {
+                Some(CommandTypeMeta::Unknown) => node.validate(),
+                Some(handler) if handler == *meta => node.validate(),
+                handler => Err(TreeError::FormMismatch {
+                    node: node.name(),
+                    declared: *meta,
+                    handler: handler.unwrap_or(CommandTypeMeta::Unknown),
+                }),
+            };
+        }
+        if let Self::Branch { name, sub, .. } = self {
+            let mut default_leaf = false;
+            let mut default_branch = false;
+            for (i, child) in sub.iter().enumerate() {
+                if child.is_default_leaf() {
+                    if default_leaf {
+                        return Err(TreeError::MultipleDefaults { branch: name });
+                    }
+                    default_leaf = true;
+                } else if child.is_default_branch() {
+                    if default_branch {
+                        return Err(TreeError::MultipleDefaults { branch: name });
+                    }
+                    default_branch = true;
+                }
+
+                if let Some(other) = sub[..i]
+                    .iter()
+                    .find(|other| short_form_eq(other.name(), child.name()))
+                {
+                    return Err(TreeError::DuplicateMnemonic {
+                        branch: name,
+                        first: other.name(),
+                        second: child.name(),
+                    });
+                }
+
+                child.validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the node matching a header path without executing anything.
+    ///
+    /// Each mnemonic may be given in short or long form and with a header suffix, Eg. `b"CHAN2"`.
+    /// Default branches along the path may be left out like when executing a command.
+    /// Returns `None` if there's no such node or a header suffix is out of range.
+    ///
+    /// ```
+    /// # use scpi::tree::{prelude::*, command::Todo};
+    /// # struct MyDevice;
+    /// # impl Device for MyDevice {
+    /// #     fn handle_error(&mut self, _err: Error) {}
+    /// # }
+    /// const ROOT: Node<MyDevice> = Node::root(&[Node::branch(
+    ///     b"SYSTem",
+    ///     &[Node::branch(b"ERRor", &[Node::default_leaf(b"NEXT", &Todo)])],
+    /// )]);
+    ///
+    /// let leaf = ROOT.resolve(&[b"SYST", b"ERR", b"NEXT"]).unwrap();
+    /// assert_eq!(leaf.name(), b"NEXT");
+    /// assert!(ROOT.resolve(&[b"SYST", b"ERR", b"ALL"]).is_none());
+    /// ```
+    pub fn resolve(&self, path: &[&[u8]]) -> Option<&Node<'a, D>> {
+        let Some((mnemonic, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        match self {
+            Self::Suffixed { node, .. } | Self::Declared { node, .. } => node.resolve(path),
+            Self::Branch { sub, .. } => {
+                let token = Token::ProgramMnemonic(mnemonic);
+                for child in *sub {
+                    if child.match_header(&token).ok()? {
+                        return child.resolve(rest);
+                    }
+                }
+                sub.iter()
+                    .find(|child| child.is_default_branch())
+                    .and_then(|child| child.resolve(path))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the names of the mnemonics which may follow a partial header, Eg. for completion in a REPL.
+    ///
+    /// The partial header is resolved up to the last `:` (see [Self::resolve]) and the children of the
+    /// resulting branch whose names start with the rest are returned.
+    /// Children of default branches are offered as well as they may be left out.
+    ///
+    /// ```
+    /// # use scpi::tree::{prelude::*, command::Todo};
+    /// # struct MyDevice;
+    /// # impl Device for MyDevice {
+    /// #     fn handle_error(&mut self, _err: Error) {}
+    /// # }
+    /// const ROOT: Node<MyDevice> = Node::root(&[Node::branch(
+    ///     b"SYSTem",
+    ///     &[
+    ///         Node::branch(b"ERRor", &[Node::default_leaf(b"NEXT", &Todo)]),
+    ///         Node::leaf(b"VERSion", &Todo),
+    ///     ],
+    /// )]);
+    ///
+    /// assert!(ROOT.completions(b"SYST:E").eq([&b"ERRor"[..]]));
+    /// ```
+    pub fn completions<'s>(
+        &'s self,
+        partial: &'s [u8],
+    ) -> impl Iterator<Item = &'static [u8]> + 's {
+        let (path, prefix) = match partial.iter().rposition(|c| *c == b':') {
+            Some(i) => (&partial[..i], &partial[i + 1..]),
+            None => (&b""[..], partial),
+        };
+        let node = path
+            .split(|c| *c == b':')
+            .filter(|mnemonic| !mnemonic.is_empty())
+            .try_fold(self, |node, mnemonic| node.resolve(&[mnemonic]));
+        node.map_or(&[][..], Node::children)
+            .iter()
+            .flat_map(|child| {
+                let nested = if child.is_default_branch() {
+                    child.children()
+                } else {
+                    &[]
+                };
+                core::iter::once(child).chain(nested)
+            })
+            .map(Node::name)
+            .filter(move |name| {
+                name.len() >= prefix.len() && name[..prefix.len()].eq_ignore_ascii_case(prefix)
+            })
+    }
+
+    /// Call `f` with the header path and node of every leaf in the tree, Eg. for `SYSTem:HELP:HEADers?`.
+    ///
+    /// The path contains the long form mnemonic of each branch and the leaf, Eg. `[b"SYSTem", b"ERRor", b"NEXT"]`.
+    /// Nodes without a name (the root or a default leaf of [crate::Branch!]) are not included, a default leaf
+    /// is called with the path of its branch. Header suffixes are omitted.
+    ///
+    /// ```
+    /// # use scpi::tree::{prelude::*, command::Todo};
+    /// # struct MyDevice;
+    /// # impl Device for MyDevice {
+    /// #     fn handle_error(&mut self, _err: Error) {}
+    /// # }
+    /// const ROOT: Node<MyDevice> = Node::root(&[
+    ///     Node::leaf(b"*IDN", &Todo),
+    ///     Node::branch(b"SYSTem", &[Node::leaf(b"VERSion", &Todo)]),
+    ///     Node::branch(b"INITiate", &[Node::default_leaf(b"", &Todo)]),
+    /// ]);
+    ///
+    /// let mut headers = Vec::new();
+    /// ROOT.for_each_header(|path, _leaf| headers.push(path.join(&b':')));
+    /// assert_eq!(headers, [&b"*IDN"[..], b"SYSTem:VERSion", b"INITiate"]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn for_each_header<F>(&self, mut f: F)
+    where
+        F: FnMut(&[&'static [u8]], &Node<'a, D>),
+    {
+        self.walk_headers(&mut alloc::vec::Vec::new(), &mut f);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn walk_headers<F>(&self, path: &mut alloc::vec::Vec<&'static [u8]>, f: &mut F)
+    where
+        F: FnMut(&[&'static [u8]], &Node<'a, D>),
+    {
+        match self {
+            Self::Suffixed { node, .. } | Self::Declared { node, .. } => node.walk_headers(path, f),
+            Self::Branch { name, sub, .. } => {
+                if !name.is_empty() {
+                    path.push(name);
+                }
+                for child in *sub {
+                    child.walk_headers(path, f);
+                }
+                if !name.is_empty() {
+                    path.pop();
+                }
+            }
+            _ if self.name().is_empty() => f(path, self),
+            _ => {
+                path.push(self.name());
+                f(path, self);
+                path.pop();
+            }
+        }
+    }
+
+    /// Child nodes of a branch
+    fn children(&self) -> &'a [Node<'a, D>] {
+        match self {
+            Self::Suffixed { node, .. } | Self::Declared { node, .. } => node.children(),
+            Self::Branch { sub, .. } => sub,
+            _ => &[],
+        }
+    }
+
+    /// Returns true if the node accepts the query form.
+    ///
+    /// A branch is queryable if its default leaf is. Handlers with an unknown [CommandTypeMeta]
+    /// are assumed to accept both forms.
+    pub fn is_query_capable(&self) -> bool {
+        self.meta()
+            .is_some_and(|meta| meta != CommandTypeMeta::NoQuery)
+    }
+
+    /// Returns true if the node accepts the event form, see [Self::is_query_capable].
+    pub fn is_event_capable(&self) -> bool {
+        self.meta()
+            .is_some_and(|meta| meta != CommandTypeMeta::QueryOnly)
+    }
+
+    /// Meta of the handler executed when this node is the last in a header
+    fn meta(&self) -> Option<CommandTypeMeta> {
+        match self {
+            Self::Leaf { handler, .. } => Some(handler.meta()),
+            Self::AsyncLeaf { handler, .. } => Some(handler.meta()),
+            Self::Suffixed { node, .. } | Self::Declared { node, .. } => node.meta(),
+            Self::Branch { sub, .. } => sub
+                .iter()
+                .find(|child| child.is_default_leaf())
+                .or_else(|| sub.iter().find(|child| child.is_default_branch()))
+                .and_then(|child| child.meta()),
+        }
+    }
+}
+
+/// Split off the first program message (including the terminating newline).
+///
+/// Newlines inside strings and definite length arbitrary blocks do not terminate a message.
+fn split_message(s: &[u8]) -> (&[u8], &[u8]) {
+    let mut i = 0;
+    while i < s.len() {
+        match s[i] {
+            b'\n' => return s.split_at(i + 1),
+            quote @ (b'"' | b'\'') => {
+                // A doubled quote simply closes and reopens the string
+                i += 1;
+                while i < s.len() && s[i] != quote {
+                    i += 1;
+                }
+            }
+            b'#' => {
+                if let Some(n @ b'1'..=b'9') = s.get(i + 1) {
+                    let n = (n - b'0') as usize;
+                    if let Some(len) = s
+                        .get(i + 2..i + 2 + n)
+                        .and_then(|len| lexical_core::parse::<usize>(len).ok())
+                    {
+                        // Skip to last byte of payload
+                        i += 1 + n + len;
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    (s, &[])
+}
+
+/// Compare the short forms of two mnemonics (uppercase characters, digits and any special characters)
+///
+/// A missing numeric suffix is the same as `1` like when matching a header, Eg. `MEASure` and `MEAS1`,
+/// see [crate::parser::tokenizer::util::mnemonic_match].
+fn short_form_eq(a: &[u8], b: &[u8]) -> bool {
+    let (a, a_index) = mnemonic_split_index(a).unwrap_or((a, b"1"));
+    let (b, b_index) = mnemonic_split_index(b).unwrap_or((b, b"1"));
+    a_index == b_index
+        && a.iter()
+            .filter(|c| !c.is_ascii_lowercase())
+            .eq(b.iter().filter(|c| !c.is_ascii_lowercase()))
+}
+
+/// Limits checked by [Node::run_with_config] before a message is executed.
+///
+/// The default limits are unlimited, Eg. to reject messages from network clients which are too long:
+/// ```
+/// # use scpi::{error::ErrorCode, tree::RunConfig};
+/// let config = RunConfig {
+///     max_length: 256,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RunConfig {
+    /// Maximum length of a message in bytes
+    pub max_length: usize,
+    /// Maximum number of program message units in a message
+    pub max_units: usize,
+    /// Error returned when a limit is exceeded
+    pub error: ErrorCode,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            max_length: usize::MAX,
+            max_units: usize::MAX,
+            error: ErrorCode::TooMuchData,
+        }
+    }
+}
+
+impl RunConfig {
+    /// Returns true if the message is within the limits
+    fn check(&self, command: &[u8]) -> bool {
+        command.len() <= self.max_length
+            && (self.max_units == usize::MAX
+                || Tokenizer::new(command)
+                    .filter(|tok| matches!(tok, Ok(Token::ProgramMessageUnitSeparator)))
+                    .take(self.max_units)
+                    .count()
+                    < self.max_units)
+    }
+}
+
+/// Error returned by [Node::validate]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TreeError {
+    /// Two children of the same branch have the same short form mnemonic,
+    /// making the second one unreachable.
+    DuplicateMnemonic {
+        branch: &'static [u8],
+        first: &'static [u8],
+        second: &'static [u8],
+    },
+    /// A branch has more than one default leaf or more than one default branch.
+    MultipleDefaults { branch: &'static [u8] },
+    /// A [Node::Declared] declares other forms than its handler accepts.
+    FormMismatch {
+        node: &'static [u8],
+        declared: CommandTypeMeta,
+        handler: CommandTypeMeta,
+    },
+}
+
+impl core::fmt::Display for TreeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let str = |s: &'static [u8]| core::str::from_utf8(s).unwrap_or("<invalid utf8>");
+        match self {
+            TreeError::DuplicateMnemonic {
+                branch,
+                first,
+                second,
+            } => write!(
+                f,
+                "Duplicate mnemonic in branch '{}': '{}' shadows '{}'",
+                str(branch),
+                str(first),
+                str(second)
+            ),
+            TreeError::MultipleDefaults { branch } => {
+                write!(f, "Multiple default nodes in branch '{}'", str(branch))
+            }
+            TreeError::FormMismatch {
+                node,
+                declared,
+                handler,
+            } => write!(
+                f,
+                "Node '{}' is declared {:?} but its handler is {:?}",
+                str(node),
+                declared,
+                handler
+            ),
+        }
+    }
 }
 
 impl<'a, D> Node<'a, D>
@@ -250,6 +770,12 @@ where
 {
     /// Execute a command against a given device.
     ///
+    /// Execution is aborted on the first error unless error recovery is enabled,
+    /// see [Context::set_error_recovery].
+    ///
+    /// If the response does not fit in `response` the message fails with [ErrorCode::QueryDeadlocked]
+    /// and the response is cleared, see [Formatter::push_str].
+    ///
     /// # Arguments:
     /// * command - To be executed
     /// * device - To execute against
@@ -268,12 +794,91 @@ where
     {
         let mut tokenizer = Tokenizer::new(command).peekable();
         let res = self.run_tokens(device, context, &mut tokenizer, response);
+        // Errors are reported as they occur in recovery mode
         if let Err(err) = &res {
-            device.handle_error(*err);
+            if !context.error_recovery() {
+                device.handle_error(*err);
+            }
+            // Never leave a truncated response behind
+            if *err == ErrorCode::QueryDeadlocked {
+                response.clear();
+            }
         }
         res
     }
 
+    /// Same as [Self::run] but the message is rejected without being executed if it exceeds the limits
+    /// of `config`, see [RunConfig].
+    pub fn run_with_config<FMT>(
+        &self,
+        command: &[u8],
+        device: &mut D,
+        context: &mut Context,
+        response: &mut FMT,
+        config: &RunConfig,
+    ) -> Result<()>
+    where
+        FMT: Formatter,
+    {
+        if !config.check(command) {
+            let err = Error::new(config.error);
+            device.handle_error(err);
+            return Err(err);
+        }
+        self.run(command, device, context, response)
+    }
+
+    /// Execute a buffer containing any number of newline terminated program messages.
+    ///
+    /// Each message is executed independently as if passed to [Self::run], the response is cleared before each
+    /// message and passed to `on_response` after each message which generated a (terminated) response.
+    /// A message which fails is reported to [Device::handle_error] and its partial response is discarded,
+    /// execution then continues with the next message.
+    ///
+    /// Empty lines are ignored and a trailing message without a terminating newline is executed as well.
+    ///
+    /// Returns the first error encountered, if any.
+    ///
+    /// # Arguments:
+    /// * commands - To be executed
+    /// * device - To execute against
+    /// * context - Context for these commands
+    /// * response - A formatter to write each response into.
+    /// * on_response - Called with each response.
+    pub fn run_all<FMT, F>(
+        &self,
+        mut commands: &[u8],
+        device: &mut D,
+        context: &mut Context,
+        response: &mut FMT,
+        mut on_response: F,
+    ) -> Result<()>
+    where
+        FMT: Formatter,
+        F: FnMut(&[u8]),
+    {
+        let mut result = Ok(());
+        while !commands.is_empty() {
+            let (message, rest) = split_message(commands);
+            commands = rest;
+            if message.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            response.clear();
+            match self.run(message, device, context, response) {
+                Ok(()) if !response.is_empty() => on_response(response.as_slice()),
+                Ok(()) => {}
+                Err(err) => {
+                    if result.is_ok() {
+                        result = Err(err);
+                    }
+                }
+            }
+        }
+        result
+    }
+
     pub(crate) fn run_tokens<FMT>(
         &self,
         device: &mut D,
@@ -285,67 +890,141 @@ where
         FMT: Formatter,
     {
         let mut leaf = self;
+        context.header_path_reset(false);
 
         //Start response message
-        response.message_start()?;
+        let mut recovery = Recovery::new(context);
+        response
+            .message_start()
+            .map_err(|err| recovery.abort(err, device))?;
+        // Set once an indefinite length block has ended the response message
+        let block_ended = Cell::new(false);
         loop {
-            // Execute header
-            match tokens.peek() {
-                // :header..
-                Some(Ok(Token::HeaderMnemonicSeparator)) => {
-                    leaf = self;
-                    // Consume seperator
-                    tokens.next();
-                    self.exec(&mut leaf, device, context, tokens, response)?;
-                }
-                // header.. | *header
-                Some(Ok(Token::ProgramMnemonic(s))) => {
-                    if s.starts_with(b"*") {
-                        let mut _x = self;
-                        self.exec(&mut _x, device, context, tokens, response)?;
-                    } else {
-                        leaf.exec(&mut leaf, device, context, tokens, response)?;
-                    }
-                }
-                // Empty input
-                None => break Ok(()),
-                //
-                Some(Err(err)) => break Err(Error::new(*err)),
-                // idk?
-                Some(_) => break Err(ErrorCode::SyntaxError.into()),
-            }
-            // Should've consumed up to unit seperator
-
-            // What's next?
-            match tokens.next() {
-                // EOM
-                None => {
-                    if !response.is_empty() {
-                        response.message_end()?;
-                    }
-                    break Ok(());
+            let res = self.run_unit(&mut leaf, &block_ended, device, context, tokens, response);
+            if !recovery.check(res, &block_ended, device, tokens, response)? {
+                break recovery.finish();
+            }
+        }
+    }
+
+    /// Execute the next program message unit.
+    /// Returns true if another unit follows.
+    fn run_unit<FMT>(
+        &'a self,
+        leaf: &mut &'a Node<'a, D>,
+        block_ended: &Cell<bool>,
+        device: &mut D,
+        context: &mut Context,
+        tokens: &mut Peekable<Tokenizer>,
+        response: &mut FMT,
+    ) -> Result<bool>
+    where
+        FMT: Formatter,
+    {
+        // Execute header
+        match self.next_unit(leaf, context, tokens)? {
+            Some((Handler::Sync(handler), query)) => Self::call(
+                handler,
+                query,
+                block_ended,
+                device,
+                context,
+                tokens,
+                response,
+            )?,
+            Some((Handler::Async(_), _)) => {
+                return Err(Error::new(ErrorCode::ExecutionError)
+                    .extended(b"Async command requires run_async"))
+            }
+            // Empty input
+            None => return Ok(false),
+        }
+        Self::end_unit(context.message_terminator(), block_ended, tokens, response)
+    }
+
+    /// Resolve the header of the next program message unit into its handler.
+    /// Returns [None] on empty input.
+    fn next_unit(
+        &'a self,
+        leaf: &mut &'a Node<'a, D>,
+        context: &mut Context,
+        tokens: &mut Peekable<Tokenizer>,
+    ) -> Result<Option<(Handler<'a, D>, bool)>> {
+        match tokens.peek() {
+            // :header..
+            Some(Ok(Token::HeaderMnemonicSeparator)) => {
+                *leaf = self;
+                context.header_path_reset(false);
+                // Consume seperator
+                tokens.next();
+                self.resolve_header(leaf, context, tokens).map(Some)
+            }
+            // header.. | *header
+            Some(Ok(Token::ProgramMnemonic(s))) => {
+                if s.starts_with(b"*") {
+                    let mut _x = self;
+                    context.header_path_common();
+                    self.resolve_header(&mut _x, context, tokens).map(Some)
+                } else {
+                    context.header_path_reset(true);
+                    leaf.resolve_header(leaf, context, tokens).map(Some)
                 }
-                // New unit
-                Some(Ok(Token::ProgramMessageUnitSeparator)) => {
-                    continue;
+            }
+            // Empty input
+            None => Ok(None),
+            //
+            Some(Err(err)) => Err(Error::new(*err)),
+            // idk?
+            Some(_) => Err(ErrorCode::SyntaxError.into()),
+        }
+    }
+
+    /// Consume the end of a program message unit.
+    /// Returns true if another unit follows.
+    ///
+    /// The response message is terminated unless an indefinite length block already did.
+    fn end_unit<FMT>(
+        terminator: MessageTerminator,
+        block_ended: &Cell<bool>,
+        tokens: &mut Peekable<Tokenizer>,
+        response: &mut FMT,
+    ) -> Result<bool>
+    where
+        FMT: Formatter,
+    {
+        // Should've consumed up to unit seperator
+
+        // What's next?
+        match tokens.next() {
+            // EOM
+            None => {
+                if !response.is_empty() && !block_ended.get() {
+                    terminator.message_end(response).map_err(query_deadlocked)?;
                 }
-                // More tokens...
-                Some(Ok(tok)) => {
-                    if tok.is_data() || tok == Token::ProgramDataSeparator {
-                        break Err(ErrorCode::ParameterNotAllowed.into());
-                    } else {
-                        break Err(ErrorCode::SyntaxError.into());
-                    }
+                Ok(false)
+            }
+            // New unit
+            Some(Ok(Token::ProgramMessageUnitSeparator)) => Ok(true),
+            // More tokens...
+            Some(Ok(tok)) => {
+                if tok.is_data() || tok == Token::ProgramDataSeparator {
+                    Err(ErrorCode::ParameterNotAllowed.into())
+                } else if tok == Token::ProgramDataRangeSeparator {
+                    Err(ErrorCode::InvalidSeparator.into())
+                } else {
+                    Err(ErrorCode::SyntaxError.into())
                 }
-                // Error
-                Some(Err(err)) => break Err(Error::new(err)),
             }
+            // Error
+            Some(Err(err)) => Err(Error::new(err)),
         }
     }
 
-    pub(crate) fn exec<FMT>(
-        &'a self,
-        leaf: &mut &'a Node<'a, D>,
+    /// Call the event or query form of a handler
+    fn call<FMT>(
+        handler: &dyn Command<D>,
+        query: bool,
+        block_ended: &Cell<bool>,
         device: &mut D,
         context: &mut Context,
         tokens: &mut Peekable<Tokenizer>,
@@ -354,6 +1033,44 @@ where
     where
         FMT: Formatter,
     {
+        if !handler.meta().allows(query) {
+            return Err(ErrorCode::UndefinedHeader.into());
+        }
+        if query {
+            let response_unit =
+                Self::response_unit(context, block_ended, response).map_err(query_deadlocked)?;
+            handler.query(device, context, Parameters::with(tokens), response_unit)
+        } else {
+            handler.event(device, context, Parameters::with(tokens))
+        }
+    }
+
+    /// Start a response unit, a query following an indefinite length block is an error (IEEE 488.2, 6.5.7.5).
+    fn response_unit<'r, FMT>(
+        context: &Context,
+        block_ended: &'r Cell<bool>,
+        response: &'r mut FMT,
+    ) -> Result<ResponseUnit<'r>>
+    where
+        FMT: Formatter,
+    {
+        if block_ended.get() {
+            return Err(ErrorCode::QueryUnterminatedAfterIndefiniteResponse.into());
+        }
+        Ok(response
+            .response_unit()?
+            .with_data_format(context.numeric_format(), context.byte_order())
+            .with_block_ended(block_ended))
+    }
+
+    /// Traverse the tree to the leaf matching the header and return its handler
+    /// and whether it was queried. Tokens are consumed up to the parameters.
+    pub(crate) fn resolve_header(
+        &'a self,
+        leaf: &mut &'a Node<'a, D>,
+        context: &mut Context,
+        tokens: &mut Peekable<Tokenizer>,
+    ) -> Result<(Handler<'a, D>, bool)> {
         let next = match tokens.peek() {
             Some(Ok(tok)) => Some(tok),
             Some(Err(err)) => return Err(Error::new(*err)),
@@ -362,42 +1079,15 @@ where
 
         //extern crate std;
 
-        match self {
-            Node::Leaf { handler, .. } => {
-                //std::println!("Leaf {}", std::str::from_utf8(name).unwrap());
-                match next {
-                    // "Leaf .." | "Leaf\EOM"
-                    Some(Token::ProgramHeaderSeparator | Token::ProgramMessageUnitSeparator)
-                    | None => {
-                        // Consume the header seperator
-                        tokens.next_if(|t| matches!(t, Ok(Token::ProgramHeaderSeparator)));
-
-                        // Execute handler
-                        handler.event(device, context, Parameters::with(tokens))
-                    }
-                    // Branch?..
-                    Some(Token::HeaderQuerySuffix) => {
-                        // Consume query suffix
-                        tokens.next();
-
-                        // Consume header seperator
-                        tokens.next_if(|t| matches!(t, Ok(Token::ProgramHeaderSeparator)));
-
-                        // Execute handler
-                        let response_unit = response.response_unit()?;
-                        handler.query(device, context, Parameters::with(tokens), response_unit)
-                    }
-                    // This is a leaf node, cannot traverse further
-                    Some(Token::HeaderMnemonicSeparator | Token::ProgramMnemonic(..)) => {
-                        Err(ErrorCode::UndefinedHeader.into())
-                    }
-                    // Tokenizer shouldn't emit anything else...
-                    Some(_) => Err(ErrorCode::SyntaxError.into()),
-                }
+        let handler = match self {
+            Node::Leaf { handler, .. } => Handler::Sync(*handler),
+            Node::AsyncLeaf { handler, .. } => Handler::Async(*handler),
+            Node::Suffixed { node, .. } | Node::Declared { node, .. } => {
+                return node.resolve_header(leaf, context, tokens)
             }
             Node::Branch { sub, .. } => {
                 //std::println!("Branch {}", std::str::from_utf8(name).unwrap());
-                match next {
+                return match next {
                     // Branch[:]<mnemonic>..
                     Some(Token::HeaderMnemonicSeparator | Token::ProgramMnemonic(..)) => {
                         // Consume seperator
@@ -414,19 +1104,18 @@ where
 
                         // Try to match a child with mnemonic
                         *leaf = self;
+                        context.header_path_enter_branch();
                         for child in *sub {
-                            if mnemonic.match_program_header(child.name()) {
+                            if child.match_header(mnemonic)? {
                                 tokens.next(); // Consume mnemonic
-                                return child.exec(leaf, device, context, tokens, response);
+                                context.header_path_push(child.name());
+                                return child.resolve_header(leaf, context, tokens);
                             }
                         }
 
                         // Check if there's a default child branch
-                        if let Some(child) = sub
-                            .iter()
-                            .find(|child| matches!(child, Node::Branch { default: true, .. }))
-                        {
-                            child.exec(leaf, device, context, tokens, response)
+                        if let Some(child) = sub.iter().find(|child| child.is_default_branch()) {
+                            child.resolve_header(leaf, context, tokens)
                         } else {
                             Err(ErrorCode::UndefinedHeader.into())
                         }
@@ -439,24 +1128,1171 @@ where
                     )
                     | None => {
                         // Try to find a default leaf or branch execute
-                        if let Some(default_leaf) = sub
-                            .iter()
-                            .find(|child| matches!(child, Node::Leaf { default: true, .. }))
+                        if let Some(default_leaf) = sub.iter().find(|child| child.is_default_leaf())
                         {
-                            default_leaf.exec(leaf, device, context, tokens, response)
-                        } else if let Some(default_branch) = sub
-                            .iter()
-                            .find(|child| matches!(child, Node::Branch { default: true, .. }))
+                            default_leaf.resolve_header(leaf, context, tokens)
+                        } else if let Some(default_branch) =
+                            sub.iter().find(|child| child.is_default_branch())
                         {
-                            default_branch.exec(leaf, device, context, tokens, response)
+                            default_branch.resolve_header(leaf, context, tokens)
                         } else {
                             Err(ErrorCode::UndefinedHeader.into())
                         }
                     }
                     // Tokenizer shouldn't emit anything else...
                     Some(_) => Err(ErrorCode::SyntaxError.into()),
-                }
+                };
+            }
+        };
+
+        //std::println!("Leaf {}", std::str::from_utf8(name).unwrap());
+        match next {
+            // "Leaf .." | "Leaf\EOM"
+            Some(Token::ProgramHeaderSeparator | Token::ProgramMessageUnitSeparator) | None => {
+                // Consume the header seperator
+                tokens.next_if(|t| matches!(t, Ok(Token::ProgramHeaderSeparator)));
+                Ok((handler, false))
+            }
+            // Branch?..
+            Some(Token::HeaderQuerySuffix) => {
+                // Consume query suffix
+                tokens.next();
+
+                // Consume header seperator
+                tokens.next_if(|t| matches!(t, Ok(Token::ProgramHeaderSeparator)));
+                Ok((handler, true))
+            }
+            // This is a leaf node, cannot traverse further
+            Some(Token::HeaderMnemonicSeparator | Token::ProgramMnemonic(..)) => {
+                Err(ErrorCode::UndefinedHeader.into())
             }
+            // Tokenizer shouldn't emit anything else...
+            Some(_) => Err(ErrorCode::SyntaxError.into()),
         }
     }
 }
+
+#[cfg(feature = "async")]
+impl<'a, D> Node<'a, D>
+where
+    D: Device,
+{
+    /// Execute a command against a given device, awaiting any [AsyncCommand] handlers.
+    ///
+    /// Same as [Self::run] but both [Node::Leaf] and [Node::AsyncLeaf] handlers are executed.
+    pub async fn run_async<FMT>(
+        &self,
+        command: &[u8],
+        device: &mut D,
+        context: &mut Context<'_>,
+        response: &mut FMT,
+    ) -> Result<()>
+    where
+        FMT: Formatter,
+    {
+        let mut tokenizer = Tokenizer::new(command).peekable();
+        let res = self
+            .run_tokens_async(device, context, &mut tokenizer, response)
+            .await;
+        // Errors are reported as they occur in recovery mode
+        if let Err(err) = &res {
+            if !context.error_recovery() {
+                device.handle_error(*err);
+            }
+            // Never leave a truncated response behind
+            if *err == ErrorCode::QueryDeadlocked {
+                response.clear();
+            }
+        }
+        res
+    }
+
+    async fn run_tokens_async<FMT>(
+        &self,
+        device: &mut D,
+        context: &mut Context<'_>,
+        tokens: &mut Peekable<Tokenizer<'_>>,
+        response: &mut FMT,
+    ) -> Result<()>
+    where
+        FMT: Formatter,
+    {
+        let mut leaf = self;
+        context.header_path_reset(false);
+
+        //Start response message
+        let mut recovery = Recovery::new(context);
+        response
+            .message_start()
+            .map_err(|err| recovery.abort(err, device))?;
+        // Set once an indefinite length block has ended the response message
+        let block_ended = Cell::new(false);
+        loop {
+            let res = self
+                .run_unit_async(&mut leaf, &block_ended, device, context, tokens, response)
+                .await;
+            if !recovery.check(res, &block_ended, device, tokens, response)? {
+                break recovery.finish();
+            }
+        }
+    }
+
+    /// Execute the next program message unit, see [Self::run_unit].
+    async fn run_unit_async<FMT>(
+        &'a self,
+        leaf: &mut &'a Node<'a, D>,
+        block_ended: &Cell<bool>,
+        device: &mut D,
+        context: &mut Context<'_>,
+        tokens: &mut Peekable<Tokenizer<'_>>,
+        response: &mut FMT,
+    ) -> Result<bool>
+    where
+        FMT: Formatter,
+    {
+        // Execute header
+        match self.next_unit(leaf, context, tokens)? {
+            Some((Handler::Sync(handler), query)) => Self::call(
+                handler,
+                query,
+                block_ended,
+                device,
+                context,
+                tokens,
+                response,
+            )?,
+            Some((Handler::Async(handler), query)) if !handler.meta().allows(query) => {
+                return Err(ErrorCode::UndefinedHeader.into())
+            }
+            Some((Handler::Async(handler), true)) => {
+                let response_unit = Self::response_unit(context, block_ended, response)
+                    .map_err(query_deadlocked)?;
+                handler
+                    .query(device, context, Parameters::with(tokens), response_unit)
+                    .await?
+            }
+            Some((Handler::Async(handler), false)) => {
+                handler
+                    .event(device, context, Parameters::with(tokens))
+                    .await?
+            }
+            // Empty input
+            None => return Ok(false),
+        }
+        Self::end_unit(context.message_terminator(), block_ended, tokens, response)
+    }
+}
+
+/// Error handling of a program message, see [Context::set_error_recovery]
+struct Recovery {
+    enabled: bool,
+    terminator: MessageTerminator,
+    first_error: Option<Error>,
+}
+
+impl Recovery {
+    fn new(context: &Context) -> Self {
+        Self {
+            enabled: context.error_recovery(),
+            terminator: context.message_terminator(),
+            first_error: None,
+        }
+    }
+
+    /// Check the result of a program message unit.
+    ///
+    /// Returns `Ok(true)` if another unit follows. If recovery is enabled an error is reported
+    /// to the device and the rest of the failed unit is skipped, otherwise the error is returned.
+    fn check<D, FMT>(
+        &mut self,
+        res: Result<bool>,
+        block_ended: &Cell<bool>,
+        device: &mut D,
+        tokens: &mut Peekable<Tokenizer>,
+        response: &mut FMT,
+    ) -> Result<bool>
+    where
+        D: Device,
+        FMT: Formatter,
+    {
+        match res {
+            Err(err) if self.enabled => {
+                device.handle_error(err);
+                self.first_error.get_or_insert(err);
+                // Resynchronize at the next unit
+                if skip_to_next_unit(tokens) {
+                    Ok(true)
+                } else {
+                    if !response.is_empty() && !block_ended.get() {
+                        self.terminator
+                            .message_end(response)
+                            .map_err(|err| self.abort(query_deadlocked(err), device))?;
+                    }
+                    Ok(false)
+                }
+            }
+            res => res,
+        }
+    }
+
+    /// Abort the message with an error which cannot be recovered from.
+    fn abort<D: Device>(&self, err: Error, device: &mut D) -> Error {
+        if self.enabled {
+            device.handle_error(err);
+        }
+        err
+    }
+
+    /// Returns the first error encountered, if any
+    fn finish(self) -> Result<()> {
+        self.first_error.map_or(Ok(()), Err)
+    }
+}
+
+/// Handler of a resolved leaf
+pub(crate) enum Handler<'a, D> {
+    Sync(&'a dyn Command<D>),
+    Async(#[cfg_attr(not(feature = "async"), allow(dead_code))] &'a dyn AsyncCommand<D>),
+}
+
+#[cfg(test)]
+mod test_split_message {
+    use super::split_message;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            split_message(b"*IDN?\n*ESR?\n"),
+            (&b"*IDN?\n"[..], &b"*ESR?\n"[..])
+        );
+        assert_eq!(split_message(b"*ESR?"), (&b"*ESR?"[..], &b""[..]));
+        // Newlines in strings and blocks
+        assert_eq!(
+            split_message(b"CMD \"a\nb\",'\n'\nNEXT"),
+            (&b"CMD \"a\nb\",'\n'\n"[..], &b"NEXT"[..])
+        );
+        assert_eq!(
+            split_message(b"CMD #12\n\n,#HFF\nNEXT"),
+            (&b"CMD #12\n\n,#HFF\n"[..], &b"NEXT"[..])
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_validate {
+    use super::{command::Todo, prelude::*, TreeError};
+    use crate::tests::fixture_device;
+
+    struct TestValidateDevice;
+    fixture_device!(TestValidateDevice);
+
+    #[test]
+    fn test_valid() {
+        let tree: Node<TestValidateDevice> = Branch {
+            name: b"",
+            default: false,
+            sub: &[
+                Leaf {
+                    name: b"*IDN",
+                    default: false,
+                    handler: &Todo,
+                },
+                Branch {
+                    name: b"SOURce",
+                    default: true,
+                    sub: &[
+                        Leaf {
+                            name: b"",
+                            default: true,
+                            handler: &Todo,
+                        },
+                        Branch {
+                            name: b"VOLTage",
+                            default: true,
+                            sub: &[Leaf {
+                                name: b"LEVel",
+                                default: true,
+                                handler: &Todo,
+                            }],
+                        },
+                        Leaf {
+                            name: b"VOLTage2",
+                            default: false,
+                            handler: &Todo,
+                        },
+                    ],
+                },
+            ],
+        };
+        assert_eq!(tree.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let tree: Node<TestValidateDevice> = Branch {
+            name: b"",
+            default: false,
+            sub: &[Branch {
+                name: b"SOURce",
+                default: false,
+                sub: &[
+                    Leaf {
+                        name: b"VOLTage",
+                        default: false,
+                        handler: &Todo,
+                    },
+                    Leaf {
+                        name: b"CURRent",
+                        default: false,
+                        handler: &Todo,
+                    },
+                    Branch {
+                        name: b"VOLTage",
+                        default: false,
+                        sub: &[],
+                    },
+                ],
+            }],
+        };
+        let err = tree.validate().unwrap_err();
+        assert_eq!(
+            err,
+            TreeError::DuplicateMnemonic {
+                branch: b"SOURce",
+                first: b"VOLTage",
+                second: b"VOLTage"
+            }
+        );
+        extern crate std;
+        use std::string::ToString;
+        assert_eq!(
+            err.to_string(),
+            "Duplicate mnemonic in branch 'SOURce': 'VOLTage' shadows 'VOLTage'"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_implicit_suffix() {
+        // MEAS matches both
+        let tree: Node<TestValidateDevice> = Branch {
+            name: b"",
+            default: false,
+            sub: &[
+                Leaf {
+                    name: b"MEASure",
+                    default: false,
+                    handler: &Todo,
+                },
+                Leaf {
+                    name: b"MEAS1",
+                    default: false,
+                    handler: &Todo,
+                },
+                Leaf {
+                    name: b"MEAS2",
+                    default: false,
+                    handler: &Todo,
+                },
+            ],
+        };
+        assert_eq!(
+            tree.validate(),
+            Err(TreeError::DuplicateMnemonic {
+                branch: b"",
+                first: b"MEASure",
+                second: b"MEAS1"
+            })
+        );
+    }
+
+    #[test]
+    fn test_multiple_defaults() {
+        let tree: Node<TestValidateDevice> = Branch {
+            name: b"",
+            default: false,
+            sub: &[Branch {
+                name: b"SOURce",
+                default: false,
+                sub: &[
+                    Leaf {
+                        name: b"VOLTage",
+                        default: true,
+                        handler: &Todo,
+                    },
+                    Leaf {
+                        name: b"CURRent",
+                        default: true,
+                        handler: &Todo,
+                    },
+                ],
+            }],
+        };
+        assert_eq!(
+            tree.validate(),
+            Err(TreeError::MultipleDefaults { branch: b"SOURce" })
+        );
+    }
+}
+
+#[cfg(all(test, feature = "header-path", feature = "alloc"))]
+mod test_header_path {
+    use super::prelude::*;
+    use crate::{cmd_both, error::Result};
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    #[derive(Default)]
+    struct PathDevice {
+        paths: Vec<Vec<&'static [u8]>>,
+    }
+
+    impl Device for PathDevice {
+        fn handle_error(&mut self, _err: Error) {}
+    }
+
+    struct PathCommand;
+    impl Command<PathDevice> for PathCommand {
+        cmd_both!();
+
+        fn event(
+            &self,
+            device: &mut PathDevice,
+            context: &mut Context,
+            _params: Parameters,
+        ) -> Result<()> {
+            device.paths.push(context.header_path().to_vec());
+            Ok(())
+        }
+
+        fn query(
+            &self,
+            device: &mut PathDevice,
+            context: &mut Context,
+            _params: Parameters,
+            _response: ResponseUnit,
+        ) -> Result<()> {
+            device.paths.push(context.header_path().to_vec());
+            Ok(())
+        }
+    }
+
+    const TREE: Node<PathDevice> = Branch {
+        name: b"",
+        default: false,
+        sub: &[
+            Leaf {
+                name: b"*PATH",
+                default: false,
+                handler: &PathCommand,
+            },
+            Branch {
+                name: b"SENSe",
+                default: true,
+                sub: &[Branch {
+                    name: b"VOLTage",
+                    default: false,
+                    sub: &[
+                        Leaf {
+                            name: b"DC",
+                            default: true,
+                            handler: &PathCommand,
+                        },
+                        Leaf {
+                            name: b"AC",
+                            default: false,
+                            handler: &PathCommand,
+                        },
+                    ],
+                }],
+            },
+        ],
+    };
+
+    fn run(command: &[u8]) -> Vec<Vec<&'static [u8]>> {
+        let mut device = PathDevice::default();
+        let mut context = Context::new();
+        let mut response = Vec::new();
+        TREE.run(command, &mut device, &mut context, &mut response)
+            .unwrap();
+        device.paths
+    }
+
+    #[test]
+    fn test_header_path() {
+        assert_eq!(run(b"SENS:VOLT:DC?"), [[&b"SENSe"[..], b"VOLTage", b"DC"]]);
+    }
+
+    #[test]
+    fn test_header_path_reset() {
+        assert_eq!(
+            run(b"SENS:VOLT:DC;AC;*PATH;:VOLT"),
+            [
+                &[&b"SENSe"[..], b"VOLTage", b"DC"][..],
+                &[b"SENSe", b"VOLTage", b"AC"],
+                &[b"*PATH"],
+                &[b"VOLTage"],
+            ]
+        );
+        // Common commands keep the relative branch
+        assert_eq!(
+            run(b"SENS:VOLT:DC;*PATH;*PATH;AC"),
+            [
+                &[&b"SENSe"[..], b"VOLTage", b"DC"][..],
+                &[b"*PATH"],
+                &[b"*PATH"],
+                &[b"SENSe", b"VOLTage", b"AC"],
+            ]
+        );
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod test_async {
+    use super::prelude::*;
+    use crate::{cmd_both, cmd_qonly, error::Result, tests::fixture_device};
+    use alloc::{boxed::Box, vec::Vec};
+    use core::{
+        future::Future,
+        pin::{pin, Pin},
+        task::{Context as TaskContext, Poll, Waker},
+    };
+
+    /// Future which is pending a number of polls before completing
+    struct Timer(usize);
+    impl Future for Timer {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<()> {
+            if self.0 == 0 {
+                Poll::Ready(())
+            } else {
+                self.0 -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = TaskContext::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+                break out;
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct AsyncDevice {
+        value: i32,
+    }
+    fixture_device!(AsyncDevice);
+
+    struct DelayedCommand;
+    impl AsyncCommand<AsyncDevice> for DelayedCommand {
+        cmd_both!();
+
+        fn event<'h>(
+            &'h self,
+            device: &'h mut AsyncDevice,
+            _context: &'h mut Context<'_>,
+            mut params: Parameters<'h, '_>,
+        ) -> BoxFuture<'h, Result<()>> {
+            Box::pin(async move {
+                let value: i32 = params.next_data()?;
+                Timer(3).await;
+                device.value = value;
+                Ok(())
+            })
+        }
+
+        fn query<'h>(
+            &'h self,
+            device: &'h mut AsyncDevice,
+            _context: &'h mut Context<'_>,
+            _params: Parameters<'h, '_>,
+            mut response: ResponseUnit<'h>,
+        ) -> BoxFuture<'h, Result<()>> {
+            Box::pin(async move {
+                Timer(3).await;
+                response.data(device.value).finish()
+            })
+        }
+    }
+
+    struct SyncCommand;
+    impl Command<AsyncDevice> for SyncCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut AsyncDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.data(1i32).finish()
+        }
+    }
+
+    const TREE: Node<AsyncDevice> = Node::root(&[
+        Node::leaf(b"*SYNC", &SyncCommand),
+        Node::async_leaf(b"*DELay", &DelayedCommand),
+    ]);
+
+    #[test]
+    fn test_async_query() {
+        let mut device = AsyncDevice::default();
+        let mut context = Context::new();
+        let mut response = Vec::new();
+        block_on(TREE.run_async(
+            b"*DEL 42;*SYNC?;*DEL?",
+            &mut device,
+            &mut context,
+            &mut response,
+        ))
+        .unwrap();
+        assert_eq!(device.value, 42);
+        assert_eq!(response, b"1;42\n");
+    }
+
+    #[test]
+    fn test_async_in_sync_run() {
+        let mut device = AsyncDevice::default();
+        let mut context = Context::new();
+        let mut response = Vec::new();
+        let err = TREE
+            .run(b"*DEL?", &mut device, &mut context, &mut response)
+            .unwrap_err();
+        assert_eq!(err, ErrorCode::ExecutionError);
+    }
+}
+
+#[cfg(test)]
+mod test_suffix {
+    use super::prelude::*;
+    use crate::{cmd_qonly, error::Result, tests::fixture_device};
+    use alloc::vec::Vec;
+
+    struct SuffixDevice;
+    fixture_device!(SuffixDevice);
+
+    struct SuffixCommand;
+    impl Command<SuffixDevice> for SuffixCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut SuffixDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.data(1i32).finish()
+        }
+    }
+
+    const TREE: Node<SuffixDevice> = Node::root(&[
+        Node::suffixed(
+            1..=4,
+            &Node::branch(b"CHANnel", &[Node::leaf(b"STATe", &SuffixCommand)]),
+        ),
+        Node::suffixed(2..=3, &Node::leaf(b"PORT", &SuffixCommand)),
+    ]);
+
+    fn run(command: &[u8]) -> Result<Vec<u8>> {
+        let mut context = Context::new();
+        let mut response = Vec::new();
+        TREE.run(command, &mut SuffixDevice, &mut context, &mut response)?;
+        Ok(response)
+    }
+
+    #[test]
+    fn test_suffix_range() {
+        assert_eq!(run(b"CHAN:STAT?").unwrap(), b"1\n");
+        assert_eq!(run(b"chan1:stat?;:channel4:stat?").unwrap(), b"1;1\n");
+        assert_eq!(run(b"PORT2?").unwrap(), b"1\n");
+    }
+
+    #[test]
+    fn test_suffix_out_of_range() {
+        assert_eq!(
+            run(b"CHAN5:STAT?").unwrap_err(),
+            ErrorCode::HeaderSuffixOutOfRange
+        );
+        assert_eq!(
+            run(b"CHAN0:STAT?").unwrap_err(),
+            ErrorCode::HeaderSuffixOutOfRange
+        );
+        // No suffix is suffix 1
+        assert_eq!(
+            run(b"PORT?").unwrap_err(),
+            ErrorCode::HeaderSuffixOutOfRange
+        );
+        assert!(TREE.validate().is_ok());
+    }
+}
+
+#[cfg(test)]
+mod test_resolve {
+    use super::prelude::*;
+    use crate::{cmd_nquery, cmd_qonly, tests::fixture_device};
+
+    struct ResolveDevice;
+    fixture_device!(ResolveDevice);
+
+    struct QueryCommand;
+
+    impl Command<ResolveDevice> for QueryCommand {
+        cmd_qonly!();
+    }
+
+    struct EventCommand;
+
+    impl Command<ResolveDevice> for EventCommand {
+        cmd_nquery!();
+    }
+
+    const TREE: Node<ResolveDevice> = Node::root(&[
+        Node::branch(
+            b"SYSTem",
+            &[Node::branch(
+                b"ERRor",
+                &[Node::default_leaf(b"NEXT", &QueryCommand)],
+            )],
+        ),
+        Node::default_branch(b"SENSe", &[Node::leaf(b"ZERO", &EventCommand)]),
+        Node::suffixed(
+            1..=4,
+            &Node::branch(b"CHANnel", &[Node::leaf(b"STATe", &QueryCommand)]),
+        ),
+    ]);
+
+    #[test]
+    fn test_resolve() {
+        let leaf = TREE.resolve(&[b"SYST", b"ERR", b"NEXT"]).unwrap();
+        assert_eq!(leaf.name(), b"NEXT");
+        assert!(leaf.is_query_capable() && !leaf.is_event_capable());
+        let leaf = TREE.resolve(&[b"system", b"error", b"next"]).unwrap();
+        assert_eq!(leaf.name(), b"NEXT");
+
+        // Branch is queryable through its default leaf
+        let branch = TREE.resolve(&[b"SYST", b"ERR"]).unwrap();
+        assert_eq!(branch.name(), b"ERRor");
+        assert!(branch.is_query_capable());
+        assert!(!TREE.resolve(&[b"SYST"]).unwrap().is_query_capable());
+
+        // Default branch may be left out
+        let leaf = TREE.resolve(&[b"ZERO"]).unwrap();
+        assert!(!leaf.is_query_capable() && leaf.is_event_capable());
+        assert!(TREE.resolve(&[b"SENS", b"ZERO"]).is_some());
+
+        assert_eq!(
+            TREE.resolve(&[b"CHAN2", b"STAT"]).map(Node::name),
+            Some(&b"STATe"[..])
+        );
+        assert!(TREE.resolve(&[b"CHAN5", b"STAT"]).is_none());
+        assert!(TREE.resolve(&[b"SYST", b"ERR", b"ALL"]).is_none());
+        assert!(TREE.resolve(&[b"SYST", b"ERR", b"NEXT", b"MORE"]).is_none());
+    }
+
+    #[test]
+    fn test_completions() {
+        assert!(TREE.completions(b"SYST:E").eq([&b"ERRor"[..]]));
+        assert!(TREE.completions(b":syst:err:").eq([&b"NEXT"[..]]));
+        assert!(TREE
+            .completions(b"")
+            .eq([&b"SYSTem"[..], b"SENSe", b"ZERO", b"CHANnel"]));
+        assert!(TREE.completions(b"s").eq([&b"SYSTem"[..], b"SENSe"]));
+        assert!(TREE.completions(b"CHAN2:").eq([&b"STATe"[..]]));
+        assert_eq!(TREE.completions(b"SYST:ERR:NEXT:").count(), 0);
+        assert_eq!(TREE.completions(b"FOO:").count(), 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_for_each_header() {
+        let mut headers = alloc::vec::Vec::new();
+        TREE.for_each_header(|path, leaf| {
+            headers.push((path.join(&b':'), leaf.is_query_capable()))
+        });
+        assert_eq!(
+            headers,
+            [
+                (b"SYSTem:ERRor:NEXT".to_vec(), true),
+                (b"SENSe:ZERO".to_vec(), false),
+                (b"CHANnel:STATe".to_vec(), true),
+            ]
+        );
+        // Default leaf without a name is listed with the path of its branch
+        const DEFAULT: Node<ResolveDevice> = crate::Branch!(b"INITiate" => &EventCommand;
+            crate::Leaf!(b"CONTinuous" => &QueryCommand)
+        );
+        let mut headers = alloc::vec::Vec::new();
+        DEFAULT.for_each_header(|path, _leaf| headers.push(path.join(&b':')));
+        assert_eq!(headers, [&b"INITiate"[..], b"INITiate:CONTinuous"]);
+    }
+}
+
+#[cfg(test)]
+mod test_meta {
+    use super::{prelude::*, TreeError};
+    use crate::{cmd_nquery, cmd_qonly, error::Result, tests::fixture_device};
+    use alloc::vec::Vec;
+
+    struct MetaDevice;
+    fixture_device!(MetaDevice);
+
+    /// Panics if called in the wrong form
+    struct EventCommand;
+
+    impl Command<MetaDevice> for EventCommand {
+        cmd_nquery!();
+
+        fn event(
+            &self,
+            _device: &mut MetaDevice,
+            _context: &mut Context,
+            _params: Parameters,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn query(
+            &self,
+            _device: &mut MetaDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            _response: ResponseUnit,
+        ) -> Result<()> {
+            unreachable!("query must not be called on a nquery command")
+        }
+    }
+
+    struct QueryCommand;
+
+    impl Command<MetaDevice> for QueryCommand {
+        cmd_qonly!();
+
+        fn event(
+            &self,
+            _device: &mut MetaDevice,
+            _context: &mut Context,
+            _params: Parameters,
+        ) -> Result<()> {
+            unreachable!("event must not be called on a qonly command")
+        }
+
+        fn query(
+            &self,
+            _device: &mut MetaDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.data(1u8).finish()
+        }
+    }
+
+    const TREE: Node<MetaDevice> = Node::root(&[
+        Node::leaf(b"EVENt", &EventCommand),
+        Node::leaf(b"QUERy", &QueryCommand),
+    ]);
+
+    fn run(command: &[u8]) -> Result<Vec<u8>> {
+        let mut device = MetaDevice;
+        let mut context = Context::new();
+        let mut response = Vec::new();
+        TREE.run(command, &mut device, &mut context, &mut response)
+            .map(|_| response)
+    }
+
+    #[test]
+    fn test_meta_enforced() {
+        assert_eq!(run(b"EVEN;QUER?").unwrap(), b"1\n");
+        assert_eq!(run(b"EVEN?").unwrap_err(), ErrorCode::UndefinedHeader);
+        assert_eq!(run(b"QUER").unwrap_err(), ErrorCode::UndefinedHeader);
+    }
+
+    #[test]
+    fn test_declared_form() {
+        const DECLARED: Node<MetaDevice> = crate::Root![
+            crate::Leaf!(event b"EVENt" => &EventCommand),
+            crate::Leaf!(default query b"QUERy" => &QueryCommand)
+        ];
+        assert_eq!(DECLARED.validate(), Ok(()));
+
+        const MISMATCH: Node<MetaDevice> =
+            crate::Root![crate::Leaf!(query b"EVENt" => &EventCommand)];
+        assert_eq!(
+            MISMATCH.validate(),
+            Err(TreeError::FormMismatch {
+                node: b"EVENt",
+                declared: CommandTypeMeta::QueryOnly,
+                handler: CommandTypeMeta::NoQuery,
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_run_config {
+    use super::{prelude::*, RunConfig};
+    use crate::{cmd_qonly, error::Result};
+    use alloc::vec::Vec;
+
+    #[derive(Default)]
+    struct LimitDevice {
+        errors: Vec<Error>,
+    }
+
+    impl Device for LimitDevice {
+        fn handle_error(&mut self, err: Error) {
+            self.errors.push(err);
+        }
+    }
+
+    struct IdnCommand;
+
+    impl Command<LimitDevice> for IdnCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut LimitDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.data(Character(b"IDN")).finish()
+        }
+    }
+
+    const TREE: Node<LimitDevice> = Node::root(&[Node::leaf(b"*IDN", &IdnCommand)]);
+
+    fn run(command: &[u8], config: &RunConfig) -> (Result<Vec<u8>>, Vec<Error>) {
+        let mut device = LimitDevice::default();
+        let mut context = Context::new();
+        let mut response = Vec::new();
+        let res = TREE
+            .run_with_config(command, &mut device, &mut context, &mut response, config)
+            .map(|_| response);
+        (res, device.errors)
+    }
+
+    #[test]
+    fn test_default_unlimited() {
+        let (res, errors) = run(b"*IDN?;*IDN?;*IDN?", &RunConfig::default());
+        assert_eq!(res.unwrap(), b"IDN;IDN;IDN\n");
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_max_length() {
+        let config = RunConfig {
+            max_length: 11,
+            ..Default::default()
+        };
+        assert_eq!(run(b"*IDN?;*IDN?", &config).0.unwrap(), b"IDN;IDN\n");
+        let (res, errors) = run(b"*IDN?;*IDN?;", &config);
+        assert_eq!(res.unwrap_err(), ErrorCode::TooMuchData);
+        assert_eq!(errors, [Error::new(ErrorCode::TooMuchData)]);
+    }
+
+    #[test]
+    fn test_max_units() {
+        let config = RunConfig {
+            max_units: 2,
+            error: ErrorCode::InputBufferOverrun,
+            ..Default::default()
+        };
+        assert_eq!(run(b"*IDN?;*IDN?", &config).0.unwrap(), b"IDN;IDN\n");
+        // Separators in strings are not counted
+        assert_eq!(
+            run(b"*IDN? ';;';*IDN?", &config).0.unwrap_err(),
+            ErrorCode::ParameterNotAllowed
+        );
+        assert_eq!(
+            run(b"*IDN?;*IDN?;*IDN?", &config).0.unwrap_err(),
+            ErrorCode::InputBufferOverrun
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_recovery {
+    use super::prelude::*;
+    use crate::{cmd_qonly, error::Result};
+    use alloc::vec::Vec;
+
+    #[derive(Default)]
+    struct RecoveryDevice {
+        errors: Vec<Error>,
+    }
+
+    impl Device for RecoveryDevice {
+        fn handle_error(&mut self, err: Error) {
+            self.errors.push(err);
+        }
+    }
+
+    struct IdnCommand;
+    impl Command<RecoveryDevice> for IdnCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut RecoveryDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.data(Character(b"IDN")).finish()
+        }
+    }
+
+    /// Responds with an indefinite length block
+    struct TraceCommand;
+    impl Command<RecoveryDevice> for TraceCommand {
+        cmd_qonly!();
+
+        fn query(
+            &self,
+            _device: &mut RecoveryDevice,
+            _context: &mut Context,
+            _params: Parameters,
+            mut response: ResponseUnit,
+        ) -> Result<()> {
+            response.begin_indefinite_block().block_data(b"ab").finish()
+        }
+    }
+
+    const TREE: Node<RecoveryDevice> = Node::root(&[
+        Node::leaf(b"*IDN", &IdnCommand),
+        Node::leaf(b"TRACe", &TraceCommand),
+    ]);
+
+    fn run(command: &[u8], recovery: bool) -> (Result<Vec<u8>>, Vec<Error>) {
+        let mut device = RecoveryDevice::default();
+        let mut context = Context::new();
+        context.set_error_recovery(recovery);
+        let mut response = Vec::new();
+        let res = TREE
+            .run(command, &mut device, &mut context, &mut response)
+            .map(|_| response);
+        (res, device.errors)
+    }
+
+    #[test]
+    fn test_recovery() {
+        let (res, errors) = run(b"BADCMD; *IDN?", true);
+        assert_eq!(res, Err(Error::new(ErrorCode::UndefinedHeader)));
+        assert_eq!(errors, [Error::new(ErrorCode::UndefinedHeader)]);
+
+        // Each failed unit is reported once
+        let mut device = RecoveryDevice::default();
+        let mut context = Context::new();
+        context.set_error_recovery(true);
+        let mut response = Vec::new();
+        let _ = TREE.run(
+            b"*IDN?;BADCMD;*IDN? 1,'a;b';12FOO;*IDN?",
+            &mut device,
+            &mut context,
+            &mut response,
+        );
+        // Response of a query failing after responding is kept
+        assert_eq!(response, b"IDN;IDN;IDN\n");
+        assert_eq!(
+            device.errors,
+            [
+                Error::new(ErrorCode::UndefinedHeader),
+                Error::new(ErrorCode::ParameterNotAllowed),
+                Error::new(ErrorCode::CommandHeaderError)
+            ]
+        );
+
+        // Error in last unit
+        let mut device = RecoveryDevice::default();
+        let mut response = Vec::new();
+        let _ = TREE.run(b"*IDN?;BADCMD", &mut device, &mut context, &mut response);
+        assert_eq!(response, b"IDN\n");
+        assert_eq!(device.errors, [Error::new(ErrorCode::UndefinedHeader)]);
+    }
+
+    #[test]
+    fn test_no_recovery() {
+        let (res, errors) = run(b"BADCMD; *IDN?", false);
+        assert_eq!(res, Err(Error::new(ErrorCode::UndefinedHeader)));
+        assert_eq!(errors, [Error::new(ErrorCode::UndefinedHeader)]);
+    }
+
+    #[test]
+    fn test_message_terminator() {
+        use crate::parser::response::MessageTerminator;
+
+        let mut device = RecoveryDevice::default();
+        let mut context = Context::new();
+        for (terminator, expected) in [
+            (MessageTerminator::Lf, &b"IDN;IDN\n"[..]),
+            (MessageTerminator::CrLf, b"IDN;IDN\r\n"),
+            (MessageTerminator::None, b"IDN;IDN"),
+        ] {
+            context.set_message_terminator(terminator);
+            let mut response = Vec::new();
+            TREE.run(b"*IDN?;*IDN?", &mut device, &mut context, &mut response)
+                .unwrap();
+            assert_eq!(response, expected);
+        }
+
+        // Also used when a recovered error ends the message
+        context.set_error_recovery(true);
+        context.set_message_terminator(MessageTerminator::CrLf);
+        let mut response = Vec::new();
+        let _ = TREE.run(b"*IDN?;BADCMD", &mut device, &mut context, &mut response);
+        assert_eq!(response, b"IDN\r\n");
+    }
+
+    #[test]
+    fn test_indefinite_block_terminator() {
+        use crate::parser::response::MessageTerminator;
+
+        let mut device = RecoveryDevice::default();
+        let mut context = Context::new();
+        // The NL ending the block is the message terminator
+        for terminator in [
+            MessageTerminator::Lf,
+            MessageTerminator::CrLf,
+            MessageTerminator::None,
+        ] {
+            context.set_message_terminator(terminator);
+            let mut response = Vec::new();
+            TREE.run(b"*IDN?;TRACE?", &mut device, &mut context, &mut response)
+                .unwrap();
+            assert_eq!(response, b"IDN;#0ab\n");
+        }
+    }
+
+    #[test]
+    fn test_query_after_indefinite_block() {
+        let (res, errors) = run(b"TRACE?;*IDN?", false);
+        assert_eq!(
+            res.unwrap_err(),
+            ErrorCode::QueryUnterminatedAfterIndefiniteResponse
+        );
+        assert_eq!(
+            errors,
+            [Error::new(
+                ErrorCode::QueryUnterminatedAfterIndefiniteResponse
+            )]
+        );
+        // Nothing is written into the block
+        let mut device = RecoveryDevice::default();
+        let mut context = Context::new();
+        context.set_error_recovery(true);
+        let mut response = Vec::new();
+        let _ = TREE.run(b"TRACE?;*IDN?", &mut device, &mut context, &mut response);
+        assert_eq!(response, b"#0ab\n");
+    }
+}
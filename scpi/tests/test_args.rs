@@ -1,6 +1,8 @@
 mod util;
 
 use scpi::cmd_qonly;
+use scpi::parser::expression::channel_list::ChannelList;
+use scpi::parser::parameters::{RangeValue, ScalarOrChannel};
 use scpi::{error::Result, tree::prelude::*};
 use util::TestDevice;
 
@@ -70,6 +72,30 @@ impl Command<TestDevice> for ArbEchoCommand {
     }
 }
 
+/// Returns length, number of chunks and checksum of a arbitrary block processed in chunks
+struct ArbChunksCommand;
+
+impl Command<TestDevice> for ArbChunksCommand {
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        _device: &mut TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let mut chunks = 0usize;
+        let mut sum = 0u32;
+        let Arbitrary(block) = params.next_data()?;
+        for chunk in block.chunks(1024) {
+            chunks += 1;
+            sum = chunk.iter().fold(sum, |acc, x| acc.wrapping_add(*x as u32));
+        }
+        response.data(block.len()).data(chunks).data(sum).finish()
+    }
+}
+
 struct ChrEchoCommand;
 
 impl Command<TestDevice> for ChrEchoCommand {
@@ -87,6 +113,129 @@ impl Command<TestDevice> for ChrEchoCommand {
     }
 }
 
+struct RangeEchoCommand;
+
+impl Command<TestDevice> for RangeEchoCommand {
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        _device: &mut TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let x: RangeValue<f32> = params.next_data()?;
+        response.data(x.start).data(x.stop).finish()
+    }
+}
+
+/// Returns `CHAN` for each channel list parameter and `SCAL` for anything else
+struct PeekCommand;
+
+impl Command<TestDevice> for PeekCommand {
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        _device: &mut TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        while let Some(token) = params.peek_token()? {
+            if matches!(token, Token::ExpressionProgramData(s) if s.starts_with(b"@")) {
+                let _list: ChannelList = params.next_data()?;
+                response.data(Character(b"CHAN"));
+            } else {
+                let _scalar: f32 = params.next_data()?;
+                response.data(Character(b"SCAL"));
+            }
+        }
+        response.finish()
+    }
+}
+
+struct ScalarOrChannelCommand;
+
+impl Command<TestDevice> for ScalarOrChannelCommand {
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        _device: &mut TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        while params.peek_token()?.is_some() {
+            match params.next_scalar_or_channel::<f32>()? {
+                ScalarOrChannel::Scalar(_) => response.data(Character(b"SCAL")),
+                ScalarOrChannel::Channel(_) => response.data(Character(b"CHAN")),
+            };
+        }
+        response.finish()
+    }
+}
+
+/// Doubles an optional parameter, extra parameters are rejected before responding
+struct StrictCommand;
+
+impl Command<TestDevice> for StrictCommand {
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        _device: &mut TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let x: u8 = params.next_or(1)?;
+        params.finish()?;
+        response.data(x.saturating_mul(2)).finish()
+    }
+}
+
+struct DefaultCommand;
+
+impl Command<TestDevice> for DefaultCommand {
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        _device: &mut TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let x = params.next_or(1.0f32)?;
+        let y = params.next_or_else(|| x * 2.0)?;
+        response.data(x).data(y).finish()
+    }
+}
+
+struct PointCommand;
+
+impl Command<TestDevice> for PointCommand {
+    cmd_qonly!();
+
+    fn query(
+        &self,
+        _device: &mut TestDevice,
+        _context: &mut Context,
+        mut params: Parameters,
+        mut response: ResponseUnit,
+    ) -> Result<()> {
+        let [x, y, z]: [i32; 3] = params.next_array()?;
+        response.data(x).data(y).data(z);
+        if let Some([u, v]) = params.next_optional_array::<i32, 2>()? {
+            response.data(u).data(v);
+        }
+        response.finish()
+    }
+}
+
 trait InfOrNan {
     fn is_t_inf(&self) -> bool;
     fn is_t_nan(&self) -> bool;
@@ -376,8 +525,16 @@ const TEST_TREE: &Node<TestDevice> = &Branch {
     sub: &[
         add_numeric_command!(b"*STR": &StrEchoCommand),
         add_numeric_command!(b"*ARB": &ArbEchoCommand),
+        add_numeric_command!(b"*ARBCHUNK": &ArbChunksCommand),
         add_numeric_command!(b"*CHR": &ChrEchoCommand),
+        add_numeric_command!(b"*SCHR": &EchoCommand::<SingleChar>::new()),
         add_numeric_command!(b"*UTF8": &Utf8Command::new()),
+        add_numeric_command!(b"*RANGE": &RangeEchoCommand),
+        add_numeric_command!(b"*PEEK": &PeekCommand),
+        add_numeric_command!(b"*SCOChannel": &ScalarOrChannelCommand),
+        add_numeric_command!(b"*FINish": &StrictCommand),
+        add_numeric_command!(b"*DEFault": &DefaultCommand),
+        add_numeric_command!(b"*POINt": &PointCommand),
         add_numeric_command!(b"*F64": &EchoCommand::<f64>::new()),
         add_numeric_command!(b"*F64ISINF": &IsInf::<f64>::new()),
         add_numeric_command!(b"*F64ISNAN": &IsNan::<f64>::new()),
@@ -385,6 +542,8 @@ const TEST_TREE: &Node<TestDevice> = &Branch {
         add_numeric_command!(b"*F32ISINF": &IsInf::<f32>::new()),
         add_numeric_command!(b"*F32ISNAN": &IsNan::<f32>::new()),
         add_numeric_command!(b"*BOOL": &EchoCommand::<bool>::new()),
+        add_numeric_command!(b"*U128": &EchoCommand::<u128>::new()),
+        add_numeric_command!(b"*I128": &EchoCommand::<i128>::new()),
         add_numeric_command!(b"*U64": &EchoCommand::<u64>::new()),
         add_numeric_command!(b"*I64": &EchoCommand::<i64>::new()),
         add_numeric_command!(b"*U32": &EchoCommand::<u32>::new()),
@@ -406,6 +565,10 @@ test_real!(real_f32; "*F32?", "*F32ISINF?", "*F32ISNAN?", f32::MIN, f32::MAX);
 
 test_real!(real_f64; "*F64?", "*F64ISINF?", "*F64ISNAN?", f64::MIN, f64::MAX);
 
+test_integer!(integer_u128; "*U128?", u128::MIN, u128::MAX);
+
+test_integer!(integer_i128; "*I128?", i128::MIN, i128::MAX);
+
 test_integer!(integer_u64; "*U64?", u64::MIN, u64::MAX);
 
 test_integer!(integer_i64; "*I64?", i64::MIN, i64::MAX);
@@ -426,6 +589,31 @@ test_integer!(integer_usize; "*USIZE?", usize::MIN, usize::MAX);
 
 test_integer!(integer_isize; "*ISIZE?", isize::MIN, isize::MAX);
 
+mod wide_nondecimal {
+    use super::*;
+    #[test]
+    fn test_wide_hex() {
+        let mut dev = TestDevice::new();
+
+        let res =
+            util::test_execute_str(TEST_TREE, b"*U128? #HFFFFFFFFFFFFFFFFF", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"295147905179352825855\n");
+
+        let res =
+            util::test_execute_str(TEST_TREE, b"*U64? #HFFFFFFFFFFFFFFFFF", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataOutOfRange));
+
+        // 129 bits
+        let res = util::test_execute_str(
+            TEST_TREE,
+            b"*U128? #H1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            &mut dev,
+        )
+        .unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataOutOfRange));
+    }
+}
+
 mod string {
     use super::*;
     #[test]
@@ -462,6 +650,33 @@ mod arbitrary {
     }
 }
 
+mod arbitrary_chunks {
+    use super::*;
+    #[test]
+    fn test_arb_chunks() {
+        let mut dev = TestDevice::new();
+        let payload: Vec<u8> = (0..10240u32).map(|x| (x % 251) as u8).collect();
+        let sum: u32 = payload.iter().map(|x| *x as u32).sum();
+
+        // Definite length
+        let mut cmd = b"*ARBCHUNK? #510240".to_vec();
+        cmd.extend_from_slice(&payload);
+        let res = util::test_execute_str(TEST_TREE, &cmd, &mut dev).unwrap();
+        assert_eq!(res, format!("10240,10,{sum}\n").as_bytes());
+
+        // Indefinite length, terminated by NL
+        let mut cmd = b"*ARBCHUNK? #0".to_vec();
+        cmd.extend_from_slice(&payload);
+        cmd.push(b'\n');
+        let res = util::test_execute_str(TEST_TREE, &cmd, &mut dev).unwrap();
+        assert_eq!(res, format!("10240,10,{sum}\n").as_bytes());
+
+        let res =
+            util::test_execute_str(TEST_TREE, "*ARBCHUNK? 'STR'".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataTypeError));
+    }
+}
+
 mod character {
     use super::*;
     #[test]
@@ -480,6 +695,31 @@ mod character {
     }
 }
 
+mod single_char {
+    use super::*;
+    #[test]
+    fn test_single_char() {
+        assert_eq!(
+            SingleChar::try_from(Token::CharacterProgramData(b"P")),
+            Ok(SingleChar(b'P'))
+        );
+
+        let mut dev = TestDevice::new();
+
+        let res = util::test_execute_str(TEST_TREE, b"*SCHR? P", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"P\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*SCHR? PN", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::IllegalParameterValue));
+
+        let res = util::test_execute_str(TEST_TREE, b"*SCHR? 'P'", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataTypeError));
+
+        let res = util::test_execute_str(TEST_TREE, b"*SCHR? 1", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataTypeError));
+    }
+}
+
 struct Utf8Command {}
 
 impl Utf8Command {
@@ -524,6 +764,198 @@ mod utf8 {
     }
 }
 
+mod range {
+    use super::*;
+    #[test]
+    fn test_range() {
+        let mut dev = TestDevice::new();
+
+        let res =
+            util::test_execute_str(TEST_TREE, "*RANGE? 1e3:1e6".as_bytes(), &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"1000.0,1000000.0\n");
+
+        let res =
+            util::test_execute_str(TEST_TREE, "*RANGE? -1 : MAX".as_bytes(), &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"-1.0,3.4028235e38\n");
+
+        let res = util::test_execute_str(TEST_TREE, "*RANGE? MIN:5".as_bytes(), &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"-3.4028235e38,5.0\n");
+
+        let res = util::test_execute_str(TEST_TREE, "*RANGE? 1:".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::NumericDataError));
+
+        let res = util::test_execute_str(TEST_TREE, "*RANGE? 1".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::NumericDataError));
+
+        let res =
+            util::test_execute_str(TEST_TREE, "*RANGE? 1:'STR'".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataTypeError));
+
+        // Stray range separator
+        let res =
+            util::test_execute_str(TEST_TREE, "*RANGE? 1:2:3".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::InvalidSeparator));
+    }
+}
+
+mod peek {
+    use super::*;
+    #[test]
+    fn test_peek() {
+        let mut dev = TestDevice::new();
+
+        let res =
+            util::test_execute_str(TEST_TREE, "*PEEK? 1, (@1:3), 2".as_bytes(), &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"SCAL,CHAN,SCAL\n");
+
+        let res = util::test_execute_str(TEST_TREE, "*PEEK?".as_bytes(), &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"");
+
+        let res =
+            util::test_execute_str(TEST_TREE, "*PEEK? (@1),".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::MissingParameter));
+    }
+}
+
+mod scalar_or_channel {
+    use super::*;
+    #[test]
+    fn test_next_scalar_or_channel() {
+        let mut dev = TestDevice::new();
+
+        let res = util::test_execute_str(TEST_TREE, b"*SCOC? 1, 2, (@1:3)", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"SCAL,SCAL,CHAN\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*SCOC? 1", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"SCAL\n");
+
+        // Channel list must be last
+        let res = util::test_execute_str(TEST_TREE, b"*SCOC? (@1), 2", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::ParameterNotAllowed));
+
+        let res = util::test_execute_str(TEST_TREE, b"*SCOC? (@1:)", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::InvalidExpression));
+
+        let res = util::test_execute_str(TEST_TREE, b"*SCOC? 'STR'", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataTypeError));
+    }
+}
+
+mod finish {
+    use super::*;
+    #[test]
+    fn test_finish() {
+        let mut dev = TestDevice::new();
+
+        let res = util::test_execute_str(TEST_TREE, b"*FIN? 4", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"8\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*FIN?", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"2\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*FIN? 4, 5", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::ParameterNotAllowed));
+    }
+}
+
+mod exponent {
+    use super::*;
+    #[test]
+    fn test_exponent_whitespace() {
+        let mut dev = TestDevice::new();
+
+        let res = util::test_execute_str(TEST_TREE, b"*F32? 1.0 E3", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"1000.0\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*F64? 1 E -3", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"0.001\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*U16? 1E +3", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"1000\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*F32? 1.0E", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::NumericDataError));
+    }
+}
+
+mod default {
+    use super::*;
+    #[test]
+    fn test_next_or() {
+        let mut dev = TestDevice::new();
+
+        let res = util::test_execute_str(TEST_TREE, b"*DEF?", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"1.0,2.0\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*DEF? 3", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"3.0,6.0\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*DEF? 3,4", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"3.0,4.0\n");
+
+        // Invalid data is not replaced by the default
+        let res = util::test_execute_str(TEST_TREE, b"*DEF? 'STRING'", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataTypeError));
+    }
+}
+
+mod array {
+    use super::*;
+    #[test]
+    fn test_next_array() {
+        let mut dev = TestDevice::new();
+
+        let res = util::test_execute_str(TEST_TREE, b"*POIN? 1,2,3", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"1,2,3\n");
+
+        let res = util::test_execute_str(TEST_TREE, b"*POIN? 1,2", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::MissingParameter));
+
+        let res = util::test_execute_str(TEST_TREE, b"*POIN? 1,'STR',3", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::DataTypeError));
+    }
+
+    #[test]
+    fn test_next_optional_array() {
+        let mut dev = TestDevice::new();
+
+        let res = util::test_execute_str(TEST_TREE, b"*POIN? 1,2,3,4,5", &mut dev).unwrap();
+        assert_eq!(res.as_slice(), b"1,2,3,4,5\n");
+
+        // Partial optional group
+        let res = util::test_execute_str(TEST_TREE, b"*POIN? 1,2,3,4", &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::MissingParameter));
+    }
+}
+
+mod bitmask {
+    use super::*;
+    #[test]
+    fn test_bitmask() {
+        let token = Token::NonDecimalNumericProgramData(0xFFFF_FFFF);
+        assert_eq!(Bitmask::<i32>::try_from(token), Ok(Bitmask(-1)));
+        assert_eq!(Bitmask::<u32>::try_from(token), Ok(Bitmask(u32::MAX)));
+        assert_eq!(
+            Bitmask::<i16>::try_from(token),
+            Err(ErrorCode::DataOutOfRange.into())
+        );
+        // Normal conversion is not affected
+        assert_eq!(i32::try_from(token), Err(ErrorCode::DataOutOfRange.into()));
+
+        let token = Token::NonDecimalNumericProgramData(0x80);
+        assert_eq!(Bitmask::<i8>::try_from(token), Ok(Bitmask(i8::MIN)));
+
+        // Decimal data is range checked
+        let token = Token::DecimalNumericProgramData(b"-1");
+        assert_eq!(Bitmask::<i32>::try_from(token), Ok(Bitmask(-1)));
+        let token = Token::DecimalNumericProgramData(b"4294967295");
+        assert_eq!(
+            Bitmask::<i32>::try_from(token),
+            Err(ErrorCode::DataOutOfRange.into())
+        );
+    }
+}
+
 mod boolean {
     use super::*;
     #[test]
@@ -547,6 +979,25 @@ mod boolean {
             util::test_execute_str(TEST_TREE, "*BOOL? ON;*BOOL? OFF".as_bytes(), &mut dev).unwrap();
         assert_eq!(res.as_slice(), b"1;0\n");
 
+        let res = util::test_execute_str(
+            TEST_TREE,
+            "*BOOL? true;*BOOL? FALSE;*BOOL? on;*BOOL? 0".as_bytes(),
+            &mut dev,
+        )
+        .unwrap();
+        assert_eq!(res.as_slice(), b"1;0;1;0\n");
+
+        let res = util::test_execute_str(TEST_TREE, "*BOOL? YES".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::IllegalParameterValue));
+
+        // No short form
+        let res = util::test_execute_str(TEST_TREE, "*BOOL? TRU".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::IllegalParameterValue));
+
+        let res =
+            util::test_execute_str(TEST_TREE, "*BOOL? FALS".as_bytes(), &mut dev).unwrap_err();
+        assert_eq!(res, Error::from(ErrorCode::IllegalParameterValue));
+
         let res =
             util::test_execute_str(TEST_TREE, "*BOOL? POTATO".as_bytes(), &mut dev).unwrap_err();
         assert_eq!(res, Error::from(ErrorCode::IllegalParameterValue));